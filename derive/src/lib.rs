@@ -0,0 +1,206 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Fields, GenericArgument, LitStr, PathArguments, Type, parse_macro_input,
+    spanned::Spanned,
+};
+
+/// Derive `lunaris_api::timeline::elements::PropertySet` for a struct with
+/// named fields.
+///
+/// Every field type must implement `PropertyValue`, or be an `Option` of
+/// one. Field attributes:
+/// - `#[property(rename = "key")]` stores the field under `key`.
+/// - `#[property(default)]` uses `Default::default()` when the key is
+///   missing instead of failing.
+///
+/// `#[property_set(crate = "path")]` on the struct names the
+/// `lunaris_api` crate, for crates that rename the dependency.
+#[proc_macro_derive(PropertySet, attributes(property, property_set))]
+pub fn derive_property_set(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Field {
+    ident: syn::Ident,
+    key: String,
+    ty: Type,
+    /// Inner type when the field is an `Option<T>`.
+    optional: Option<Type>,
+    default: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "PropertySet can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new(
+            input.span(),
+            "PropertySet requires named fields",
+        ));
+    };
+    let fields = named
+        .named
+        .iter()
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let krate = crate_path(&input)?;
+    let elements = quote!(#krate::timeline::elements);
+    let error = quote!(#krate::util::error::LunarisError);
+
+    let reads = fields.iter().map(|field| {
+        let Field { ident, key, ty, .. } = field;
+        let missing = if field.optional.is_some() || field.default {
+            quote!(::core::default::Default::default())
+        } else {
+            quote!(return ::core::result::Result::Err(#error::NotFound {
+                item: ::std::format!("property {}", #key),
+            }))
+        };
+        let value_ty = field.optional.as_ref().unwrap_or(ty);
+        let read = quote!(<#value_ty as #elements::PropertyValue>::from_property(value)?);
+        let read = if field.optional.is_some() {
+            quote!(::core::option::Option::Some(#read))
+        } else {
+            read
+        };
+        quote! {
+            #ident: match properties.get(#key) {
+                ::core::option::Option::Some(value) => #read,
+                ::core::option::Option::None => #missing,
+            }
+        }
+    });
+
+    let writes = fields.iter().map(|field| {
+        let Field { ident, key, ty, .. } = field;
+        match &field.optional {
+            Some(inner) => quote! {
+                if let ::core::option::Option::Some(value) = &self.#ident {
+                    properties.insert(#key, <#inner as #elements::PropertyValue>::to_property(value));
+                }
+            },
+            None => quote! {
+                properties.insert(#key, <#ty as #elements::PropertyValue>::to_property(&self.#ident));
+            },
+        }
+    });
+
+    let schema_fields = fields.iter().map(|field| {
+        let Field { key, ty, .. } = field;
+        let value_ty = field.optional.as_ref().unwrap_or(ty);
+        let mut built = quote! {
+            #elements::PropertyField::new(#key, <#value_ty as #elements::PropertyValue>::KIND)
+        };
+        if field.default && field.optional.is_none() {
+            built = quote! {
+                #built.default_value(<#ty as #elements::PropertyValue>::to_property(
+                    &<#ty as ::core::default::Default>::default(),
+                ))
+            };
+        } else if field.optional.is_none() {
+            built = quote!(#built.required());
+        }
+        quote!(.field(#built))
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #elements::PropertySet for #name #ty_generics #where_clause {
+            fn from_properties(
+                properties: &#elements::Properties,
+            ) -> ::core::result::Result<Self, #error> {
+                ::core::result::Result::Ok(Self {
+                    #(#reads,)*
+                })
+            }
+
+            fn to_properties(&self) -> #elements::Properties {
+                let mut properties = #elements::Properties::default();
+                #(#writes)*
+                properties
+            }
+
+            fn schema() -> #elements::PropertySchema {
+                #elements::PropertySchema::new()
+                    #(#schema_fields)*
+            }
+        }
+    })
+}
+
+/// Path of the `lunaris_api` crate, from `#[property_set(crate = "...")]`.
+fn crate_path(input: &DeriveInput) -> syn::Result<syn::Path> {
+    let mut path = syn::parse_quote!(::lunaris_api);
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("property_set"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                path = meta.value()?.parse::<LitStr>()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(path)
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<Field> {
+    let ident = field
+        .ident
+        .clone()
+        .ok_or_else(|| syn::Error::new(field.span(), "expected a named field"))?;
+    let mut key = ident.to_string();
+    let mut default = false;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("property")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                key = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                default = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename = \"...\"` or `default`"))
+            }
+        })?;
+    }
+    Ok(Field {
+        ident,
+        key,
+        optional: option_inner(&field.ty).cloned(),
+        ty: field.ty.clone(),
+        default,
+    })
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
@@ -1,10 +1,12 @@
-static mut TPS: u64 = 141_120_000;
+/// Tick rate of a freshly created project. Divisible by every common frame
+/// rate and audio sample rate, so most conversions are exact.
+pub const DEFAULT_TPS: u64 = 141_120_000;
 
-#[doc(hidden)]
-pub unsafe fn change_tps(to: u64) {
-    unsafe { TPS = to }
-}
-
-pub fn tps() -> u64 {
-    unsafe { TPS }
+/// Ticks per second of the default timebase.
+///
+/// Projects carry their own rate in the
+/// [`Timebase`](crate::timeline::timebase::Timebase) resource; prefer reading
+/// that whenever a `World` is at hand.
+pub const fn tps() -> u64 {
+    DEFAULT_TPS
 }
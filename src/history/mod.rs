@@ -0,0 +1,563 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    path::Path,
+    sync::Arc,
+};
+
+use bevy_ecs::{
+    entity::Entity,
+    event::{Event, Events},
+    resource::Resource,
+    system::{Command, ResMut},
+    world::World,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{prelude::*, timeline::TimelineEditToken};
+
+/// Default number of undo steps kept by [`UndoHistory`].
+pub const DEFAULT_HISTORY_LIMIT: usize = 256;
+
+/// An undo token that knows how to revert and re-apply its own change.
+///
+/// Every [`SystemPlugin::UndoTok`](crate::plugin::SystemPlugin::UndoTok)
+/// implements this. Tokens reach the shared [`UndoHistory`] through
+/// [`record_undo_events`], [`RecordUndo`] or [`UndoHistory::push`].
+pub trait Undoable: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Stable identifier of the token type, used to decode persisted
+    /// history. Prefix it with the plugin name to avoid collisions.
+    const KIND: &'static str;
+
+    fn undo(&self, world: &mut World) -> Result;
+    fn redo(&self, world: &mut World) -> Result;
+
+    /// Text shown in "Undo ..." menu entries.
+    fn label(&self) -> String {
+        Self::KIND.to_string()
+    }
+
+    /// Rewrite every entity the token refers to through `map`. Needed when
+    /// replaying a token in a world whose entities were respawned, such as
+    /// after crash recovery.
+    fn remap_entities(&mut self, map: &mut dyn FnMut(Entity) -> Entity) {
+        let _ = map;
+    }
+}
+
+impl Undoable for TimelineEditToken {
+    const KIND: &'static str = "lunaris.timeline_edit";
+
+    fn undo(&self, world: &mut World) -> Result {
+        TimelineEditToken::undo(self, world)
+    }
+
+    fn redo(&self, world: &mut World) -> Result {
+        TimelineEditToken::redo(self, world)
+    }
+
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    fn remap_entities(&mut self, map: &mut dyn FnMut(Entity) -> Entity) {
+        for change in &mut self.changes {
+            change.entity = map(Entity::from_bits(change.entity)).to_bits();
+            if let Some(split) = &mut change.split_from {
+                split.template = map(Entity::from_bits(split.template)).to_bits();
+            }
+        }
+        for change in &mut self.transitions {
+            change.entity = map(Entity::from_bits(change.entity)).to_bits();
+            change.outgoing = map(Entity::from_bits(change.outgoing)).to_bits();
+            change.incoming = map(Entity::from_bits(change.incoming)).to_bits();
+        }
+    }
+}
+
+/// A type-erased token as stored in the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub kind: String,
+    pub payload: Value,
+}
+
+impl HistoryEntry {
+    pub fn new<T: Undoable>(token: &T) -> Result<Self> {
+        let payload = serde_json::to_value(token).map_err(|e| LunarisError::InvalidArgument {
+            name: format!("undo token {}", T::KIND),
+            reason: Some(e.to_string()),
+        })?;
+        Ok(Self {
+            kind: T::KIND.to_string(),
+            payload,
+        })
+    }
+}
+
+/// One undo step. Everything pushed inside a group lands in the same
+/// transaction and is undone together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub label: String,
+    pub entries: Vec<HistoryEntry>,
+}
+
+/// A change to the history, as seen by a [`HistoryJournal`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HistoryOp {
+    /// A step was added on top of the undo stack.
+    Commit(Transaction),
+    Undo,
+    Redo,
+}
+
+/// Receives every change to an [`UndoHistory`] as it happens, e.g. to keep
+/// a crash-recovery log.
+pub trait HistoryJournal: Send + Sync {
+    fn record(&self, op: &HistoryOp);
+}
+
+type RemapFn = fn(&mut Value, &mut dyn FnMut(Entity) -> Entity) -> Result;
+
+#[derive(Clone, Copy)]
+struct Handler {
+    undo: fn(&Value, &mut World) -> Result,
+    redo: fn(&Value, &mut World) -> Result,
+    remap: RemapFn,
+}
+
+fn decode<T: Undoable>(payload: &Value) -> Result<T> {
+    T::deserialize(payload).map_err(|e| LunarisError::InvalidArgument {
+        name: format!("undo token {}", T::KIND),
+        reason: Some(e.to_string()),
+    })
+}
+
+fn undo_erased<T: Undoable>(payload: &Value, world: &mut World) -> Result {
+    decode::<T>(payload)?.undo(world)
+}
+
+fn redo_erased<T: Undoable>(payload: &Value, world: &mut World) -> Result {
+    decode::<T>(payload)?.redo(world)
+}
+
+fn remap_erased<T: Undoable>(payload: &mut Value, map: &mut dyn FnMut(Entity) -> Entity) -> Result {
+    let mut token = decode::<T>(payload)?;
+    token.remap_entities(map);
+    *payload = HistoryEntry::new(&token)?.payload;
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedHistory {
+    undo: Vec<Transaction>,
+    redo: Vec<Transaction>,
+}
+
+/// Shared undo/redo stack for every plugin.
+///
+/// Pushing a new step discards everything that could have been redone, and
+/// the oldest steps are dropped once more than `limit` are kept.
+#[derive(Resource)]
+pub struct UndoHistory {
+    undo: VecDeque<Transaction>,
+    redo: Vec<Transaction>,
+    open: Option<Transaction>,
+    open_depth: usize,
+    limit: usize,
+    handlers: HashMap<String, Handler>,
+    journal: Option<Arc<dyn HistoryJournal>>,
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        Self::with_limit(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl UndoHistory {
+    pub fn with_limit(limit: usize) -> Self {
+        let mut history = Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            open: None,
+            open_depth: 0,
+            limit: limit.max(1),
+            handlers: HashMap::new(),
+            journal: None,
+        };
+        history.register::<TimelineEditToken>();
+        history
+    }
+
+    /// Make tokens of type `T` replayable. Pushing a token registers its
+    /// type automatically; this is only needed before undoing history that
+    /// was loaded from disk.
+    pub fn register<T: Undoable>(&mut self) {
+        self.handlers.insert(
+            T::KIND.to_string(),
+            Handler {
+                undo: undo_erased::<T>,
+                redo: redo_erased::<T>,
+                remap: remap_erased::<T>,
+            },
+        );
+    }
+
+    /// Record a change that has already been applied to the world.
+    pub fn push<T: Undoable>(&mut self, token: T) -> Result {
+        self.register::<T>();
+        let entry = HistoryEntry::new(&token)?;
+        match &mut self.open {
+            Some(group) => group.entries.push(entry),
+            None => self.commit(Transaction {
+                label: token.label(),
+                entries: vec![entry],
+            }),
+        }
+        Ok(())
+    }
+
+    /// Start grouping pushes into one step labelled `label`. Groups nest;
+    /// only the outermost label is kept.
+    pub fn begin_group(&mut self, label: impl Into<String>) {
+        self.open_depth += 1;
+        if self.open.is_none() {
+            self.open = Some(Transaction {
+                label: label.into(),
+                entries: Vec::new(),
+            });
+        }
+    }
+
+    /// Close the innermost group. The outermost close commits the step.
+    pub fn end_group(&mut self) -> Result {
+        if self.open_depth == 0 {
+            return Err(LunarisError::InvalidState {
+                expected: "an open undo group".to_string(),
+                found: "no open group".to_string(),
+            });
+        }
+        self.open_depth -= 1;
+        if self.open_depth == 0
+            && let Some(group) = self.open.take()
+            && !group.entries.is_empty()
+        {
+            self.commit(group);
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self, transaction: Transaction) {
+        self.record(|| HistoryOp::Commit(transaction.clone()));
+        self.redo.clear();
+        self.undo.push_back(transaction);
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Change the depth limit, dropping the oldest steps if needed.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit.max(1);
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.back().map(|t| t.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|t| t.label.as_str())
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.open = None;
+        self.open_depth = 0;
+    }
+
+    /// Revert the latest step of the world's history. Returns its label, or
+    /// `None` when there was nothing to undo.
+    pub fn undo(world: &mut World) -> Result<Option<String>> {
+        Self::step(world, Direction::Undo)
+    }
+
+    /// Re-apply the latest undone step. Returns its label, or `None` when
+    /// there was nothing to redo.
+    pub fn redo(world: &mut World) -> Result<Option<String>> {
+        Self::step(world, Direction::Redo)
+    }
+
+    fn step(world: &mut World, direction: Direction) -> Result<Option<String>> {
+        let Some(mut history) = world.remove_resource::<Self>() else {
+            return Err(LunarisError::Uninit {
+                resource: "UndoHistory".to_string(),
+            });
+        };
+        let result = history.step_in(world, direction);
+        world.insert_resource(history);
+        result
+    }
+
+    fn step_in(&mut self, world: &mut World, direction: Direction) -> Result<Option<String>> {
+        if let Some(group) = &self.open {
+            return Err(LunarisError::InvalidState {
+                expected: "no open undo group".to_string(),
+                found: format!("open group {:?}", group.label),
+            });
+        }
+        let transaction = match direction {
+            Direction::Undo => self.undo.pop_back(),
+            Direction::Redo => self.redo.pop(),
+        };
+        let Some(transaction) = transaction else {
+            return Ok(None);
+        };
+
+        if let Err(e) = self.replay(world, &transaction, direction) {
+            match direction {
+                Direction::Undo => self.undo.push_back(transaction),
+                Direction::Redo => self.redo.push(transaction),
+            }
+            return Err(e);
+        }
+
+        self.record(|| match direction {
+            Direction::Undo => HistoryOp::Undo,
+            Direction::Redo => HistoryOp::Redo,
+        });
+        let label = transaction.label.clone();
+        match direction {
+            Direction::Undo => self.redo.push(transaction),
+            Direction::Redo => self.undo.push_back(transaction),
+        }
+        Ok(Some(label))
+    }
+
+    /// Apply every entry of `transaction` in `direction`. If one fails, the
+    /// entries already applied are put back so the step stays atomic.
+    fn replay(&self, world: &mut World, transaction: &Transaction, direction: Direction) -> Result {
+        let ordered: Vec<&HistoryEntry> = match direction {
+            Direction::Undo => transaction.entries.iter().rev().collect(),
+            Direction::Redo => transaction.entries.iter().collect(),
+        };
+        for (done, entry) in ordered.iter().enumerate() {
+            if let Err(e) = self.apply(world, entry, direction) {
+                for entry in ordered[..done].iter().rev() {
+                    if let Err(e) = self.apply(world, entry, direction.reverse()) {
+                        warn!("Failed to restore {} after a failed step: {e}", entry.kind);
+                    }
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn handler(&self, kind: &str) -> Result<&Handler> {
+        self.handlers
+            .get(kind)
+            .ok_or_else(|| LunarisError::NotFound {
+                item: format!("undo handler for {kind}"),
+            })
+    }
+
+    fn apply(&self, world: &mut World, entry: &HistoryEntry, direction: Direction) -> Result {
+        let handler = self.handler(&entry.kind)?;
+        match direction {
+            Direction::Undo => (handler.undo)(&entry.payload, world),
+            Direction::Redo => (handler.redo)(&entry.payload, world),
+        }
+    }
+
+    /// Attach or detach the journal that observes this history.
+    pub fn set_journal(&mut self, journal: Option<Arc<dyn HistoryJournal>>) {
+        self.journal = journal;
+    }
+
+    pub fn journal(&self) -> Option<&Arc<dyn HistoryJournal>> {
+        self.journal.as_ref()
+    }
+
+    fn record(&self, op: impl FnOnce() -> HistoryOp) {
+        if let Some(journal) = &self.journal {
+            journal.record(&op());
+        }
+    }
+
+    /// Rewrite the entities referenced by every entry of `transaction`.
+    pub fn remap_entities(
+        &self,
+        transaction: &mut Transaction,
+        map: &mut dyn FnMut(Entity) -> Entity,
+    ) -> Result {
+        for entry in &mut transaction.entries {
+            let handler = self.handler(&entry.kind)?;
+            (handler.remap)(&mut entry.payload, map)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the entities referenced by every step on both stacks.
+    pub fn remap_stacks(&mut self, map: &mut dyn FnMut(Entity) -> Entity) -> Result {
+        let mut undo = std::mem::take(&mut self.undo);
+        let mut redo = std::mem::take(&mut self.redo);
+        let result = undo
+            .iter_mut()
+            .chain(redo.iter_mut())
+            .try_for_each(|transaction| self.remap_entities(transaction, map));
+        self.undo = undo;
+        self.redo = redo;
+        result
+    }
+
+    /// Apply a journaled operation to `world` and to this history, as if it
+    /// had just happened. The history must not be inside `world` while this
+    /// runs.
+    ///
+    /// A journaled undo or redo always had a step to act on, so an empty
+    /// stack means the history does not match the journal and is an error.
+    pub fn replay_op(&mut self, world: &mut World, op: HistoryOp) -> Result {
+        let direction = match op {
+            HistoryOp::Commit(transaction) => {
+                self.replay(world, &transaction, Direction::Redo)?;
+                self.commit(transaction);
+                return Ok(());
+            }
+            HistoryOp::Undo => Direction::Undo,
+            HistoryOp::Redo => Direction::Redo,
+        };
+        match self.step_in(world, direction)? {
+            Some(_) => Ok(()),
+            None => Err(LunarisError::InvalidState {
+                expected: format!("a step to {}", direction.verb()),
+                found: format!("an empty {} stack", direction.verb()),
+            }),
+        }
+    }
+
+    /// Serialize both stacks. Registered handlers are not part of the data.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let persisted = PersistedHistory {
+            undo: self.undo.iter().cloned().collect(),
+            redo: self.redo.clone(),
+        };
+        serde_json::to_vec(&persisted).map_err(|e| LunarisError::InvalidArgument {
+            name: "undo history".to_string(),
+            reason: Some(e.to_string()),
+        })
+    }
+
+    /// Replace both stacks with previously serialized ones, keeping the
+    /// registered handlers and the depth limit.
+    pub fn load_bytes(&mut self, bytes: &[u8]) -> Result {
+        let persisted: PersistedHistory =
+            serde_json::from_slice(bytes).map_err(|e| LunarisError::FailedSaveLoad {
+                reason: format!("undo history: {e}"),
+            })?;
+        self.clear();
+        self.undo = persisted.undo.into();
+        self.redo = persisted.redo;
+        self.set_limit(self.limit);
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_bytes()?).map_err(|e| LunarisError::FileWriteError {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })
+    }
+
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| LunarisError::FileReadError {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+        self.load_bytes(&bytes)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+impl Direction {
+    fn verb(self) -> &'static str {
+        match self {
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+        }
+    }
+
+    fn reverse(self) -> Self {
+        match self {
+            Self::Undo => Self::Redo,
+            Self::Redo => Self::Undo,
+        }
+    }
+}
+
+/// Command that records an already applied change in the [`UndoHistory`].
+///
+/// Queue it from a system right after making the change the token reverts.
+pub struct RecordUndo<T: Undoable>(pub T);
+
+impl<T: Undoable> Command for RecordUndo<T> {
+    fn apply(self, world: &mut World) {
+        let Some(mut history) = world.get_resource_mut::<UndoHistory>() else {
+            warn!("No UndoHistory in the world; dropping {} token", T::KIND);
+            return;
+        };
+        if let Err(e) = history.push(self.0) {
+            warn!("Failed to record undo step: {e}");
+        }
+    }
+}
+
+/// Move every pending `T` event into the [`UndoHistory`], oldest first.
+///
+/// This is the default
+/// [`SystemPlugin::undo_system`](crate::plugin::SystemPlugin::undo_system).
+pub fn record_undo_events<T: Event + Undoable>(
+    events: Option<ResMut<Events<T>>>,
+    history: Option<ResMut<UndoHistory>>,
+) {
+    let Some(mut events) = events else {
+        return;
+    };
+    let Some(mut history) = history else {
+        if !events.is_empty() {
+            warn!("No UndoHistory in the world; dropping {} tokens", T::KIND);
+            events.clear();
+        }
+        return;
+    };
+    for token in events.drain() {
+        if let Err(e) = history.push(token) {
+            warn!("Failed to record undo step: {e}");
+        }
+    }
+}
@@ -0,0 +1,728 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    path::PathBuf,
+};
+
+use bevy_ecs::{entity::Entity, world::World};
+
+use crate::{
+    interchange::{Exported, Imported, MEDIA_KEY, NAME_KEY, REEL_KEY, Warning, warn_untracked},
+    prelude::*,
+    timeline::{
+        FrameRate, Timebase, Timecode, TimelineSpan, Track, TrackKind, Transition, TransitionKind,
+        elements::{Properties, Property, SourceOffset, TimeRemap, TimelineElement},
+    },
+};
+
+/// Record timecode of the first frame of an exported timeline. Imports
+/// count record time from here unless an event starts before it.
+pub const RECORD_START: Timecode = Timecode::new(1, 0, 0, 0, false);
+
+/// Reel of events without a tape, such as generated media.
+const AUX_REEL: &str = "AX";
+/// Reel of black filler.
+const BLACK_REEL: &str = "BL";
+const MAX_REEL_LEN: usize = 8;
+const MAX_AUDIO_CHANNELS: u8 = 4;
+
+/// An EDL channel. Each one is imported as a track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Channel {
+    Video,
+    /// 1-based audio channel.
+    Audio(u8),
+}
+
+impl Channel {
+    /// Channels named by an event's channel field, such as `V`, `A2` or
+    /// `AA/V`.
+    fn parse(field: &str) -> Option<Vec<Self>> {
+        let (video, a1, a2) = (Self::Video, Self::Audio(1), Self::Audio(2));
+        Some(match field {
+            "V" => vec![video],
+            "A" => vec![a1],
+            "AA" => vec![a1, a2],
+            "B" | "A/V" => vec![video, a1],
+            "AA/V" => vec![video, a1, a2],
+            _ => {
+                let n = field.strip_prefix('A')?.parse().ok().filter(|n| *n > 0)?;
+                vec![Self::Audio(n)]
+            }
+        })
+    }
+
+    /// Channel field of an event on this channel alone.
+    fn field(self) -> String {
+        match self {
+            Self::Video => "V".to_string(),
+            Self::Audio(1) => "A".to_string(),
+            Self::Audio(n) => format!("A{n}"),
+        }
+    }
+
+    fn track_name(self) -> String {
+        match self {
+            Self::Video => "V".to_string(),
+            Self::Audio(n) => format!("A{n}"),
+        }
+    }
+
+    fn track_kind(self) -> TrackKind {
+        match self {
+            Self::Video => TrackKind::Video,
+            Self::Audio(_) => TrackKind::Audio,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Cut,
+    Dissolve,
+    /// SMPTE wipe code.
+    Wipe(u16),
+    Key,
+}
+
+impl Edit {
+    fn parse(field: &str) -> Option<Self> {
+        match field {
+            "C" => Some(Self::Cut),
+            "D" => Some(Self::Dissolve),
+            _ if field.starts_with('K') => Some(Self::Key),
+            _ => field.strip_prefix('W')?.parse().ok().map(Self::Wipe),
+        }
+    }
+}
+
+/// Name and media given by an event's comments for one of its clips.
+#[derive(Debug, Clone, Default)]
+struct ClipNote {
+    name: Option<String>,
+    media: Option<PathBuf>,
+}
+
+impl ClipNote {
+    fn apply(&self, properties: &mut Properties) {
+        if let Some(name) = &self.name {
+            properties.insert(NAME_KEY, Property::String(name.clone()));
+        }
+        if let Some(media) = &self.media {
+            properties.insert(MEDIA_KEY, Property::Path(media.clone()));
+        }
+    }
+}
+
+/// One event line with the comment and speed lines following it. Times are
+/// in frames.
+#[derive(Debug, Clone)]
+struct Event {
+    line: usize,
+    number: String,
+    reel: String,
+    channels: Vec<Channel>,
+    edit: Edit,
+    /// Transition length.
+    duration: u64,
+    source_in: u64,
+    record: (u64, u64),
+    drop_frame: bool,
+    /// From `FROM CLIP NAME`, the outgoing clip of a transition.
+    from: ClipNote,
+    /// From `TO CLIP NAME`, the incoming clip of a transition.
+    to: Option<ClipNote>,
+    /// `M2` speeds by reel, as multiples of normal speed.
+    speeds: Vec<(String, f64)>,
+}
+
+impl Event {
+    fn comment(&mut self, comment: &str) {
+        let value = |prefix| Some(comment.strip_prefix(prefix)?.trim().to_string());
+        let name = |name: String| (!name.is_empty()).then_some(name);
+        if let Some(from) = value("FROM CLIP NAME:") {
+            self.from.name = name(from);
+        } else if let Some(to) = value("TO CLIP NAME:") {
+            self.to = Some(ClipNote {
+                name: name(to),
+                media: None,
+            });
+        } else if let Some(path) = value("SOURCE FILE:") {
+            // Belongs to whichever clip name came last.
+            self.to.as_mut().unwrap_or(&mut self.from).media = Some(PathBuf::from(path));
+        }
+    }
+
+    /// Clip spawned by this event.
+    fn clip(&self) -> Option<&ClipNote> {
+        match self.edit {
+            Edit::Cut | Edit::Key => Some(&self.from),
+            Edit::Dissolve | Edit::Wipe(_) => self.to.as_ref(),
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> LunarisError {
+    LunarisError::InvalidInterchange {
+        format: "CMX3600",
+        reason: reason.into(),
+    }
+}
+
+fn parse_event(line: usize, text: &str, drop_frame: bool, rate: FrameRate) -> Result<Event> {
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    if fields.len() < 8 {
+        return Err(invalid("event has too few fields"));
+    }
+    let (head, times) = fields.split_at(fields.len() - 4);
+    let channels = Channel::parse(head[2])
+        .ok_or_else(|| invalid(format!("unsupported channel {:?}", head[2])))?;
+    let edit =
+        Edit::parse(head[3]).ok_or_else(|| invalid(format!("unsupported edit {:?}", head[3])))?;
+    let duration = match head[4..].last() {
+        Some(field) if field.bytes().all(|b| b.is_ascii_digit()) => field
+            .parse()
+            .map_err(|_| invalid(format!("{field:?} is not a duration")))?,
+        _ => 0,
+    };
+    let mut frames = [0; 4];
+    for (frame, field) in frames.iter_mut().zip(times) {
+        let mut timecode: Timecode = field.parse()?;
+        timecode.drop_frame |= drop_frame;
+        *frame = timecode.to_frame(rate)?;
+    }
+    let [source_in, _, record_in, record_out] = frames;
+    if record_out < record_in {
+        return Err(invalid("record out is before record in"));
+    }
+    Ok(Event {
+        line,
+        number: head[0].to_string(),
+        reel: head[1].to_string(),
+        channels,
+        edit,
+        duration,
+        source_in,
+        record: (record_in, record_out),
+        drop_frame,
+        from: ClipNote::default(),
+        to: None,
+        speeds: Vec::new(),
+    })
+}
+
+/// Reel and speed of an `M2` line.
+fn parse_speed(text: &str, rate: FrameRate) -> Result<(String, f64)> {
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    let [_, reel, fps, ..] = fields[..] else {
+        return Err(invalid("speed change has too few fields"));
+    };
+    let fps: f64 = fps
+        .parse()
+        .ok()
+        .filter(|fps: &f64| fps.is_finite())
+        .ok_or_else(|| invalid(format!("{fps:?} is not a speed")))?;
+    Ok((reel.to_string(), fps / rate.nominal() as f64))
+}
+
+fn parse(edl: &str, rate: FrameRate, warnings: &mut Vec<Warning>) -> Vec<Event> {
+    let mut events: Vec<Event> = Vec::new();
+    let mut drop_frame = false;
+    // Whether comments and speeds belong to the last event, or to one that
+    // was skipped.
+    let mut open = false;
+    for (index, text) in edl.lines().enumerate() {
+        let line = index + 1;
+        let text = text.trim();
+        let first = text.split_whitespace().next().unwrap_or_default();
+        if text.is_empty() || text.starts_with("TITLE:") {
+            continue;
+        }
+        if let Some(mode) = text.strip_prefix("FCM:") {
+            match mode.trim() {
+                "DROP FRAME" => drop_frame = true,
+                "NON-DROP FRAME" => drop_frame = false,
+                mode => warnings.push(Warning::at_line(
+                    line,
+                    format!("ignored unknown frame code mode {mode:?}"),
+                )),
+            }
+        } else if let Some(comment) = text.strip_prefix('*') {
+            if open && let Some(event) = events.last_mut() {
+                event.comment(comment.trim());
+            }
+        } else if first == "M2" {
+            match parse_speed(text, rate) {
+                Ok(speed) => {
+                    if open && let Some(event) = events.last_mut() {
+                        event.speeds.push(speed);
+                    }
+                }
+                Err(e) => {
+                    warnings.push(Warning::at_line(line, format!("skipped speed change: {e}")))
+                }
+            }
+        } else if first.bytes().all(|b| b.is_ascii_digit()) {
+            match parse_event(line, text, drop_frame, rate) {
+                Ok(event) => events.push(event),
+                Err(e) => warnings.push(Warning::at_line(line, format!("skipped event: {e}"))),
+            }
+            open = events.last().is_some_and(|e| e.line == line);
+        } else {
+            warnings.push(Warning::at_line(
+                line,
+                format!("skipped unsupported line {text:?}"),
+            ));
+        }
+    }
+    events
+}
+
+/// Frame that record times count from: [`RECORD_START`] unless an event
+/// starts before it.
+fn record_base(events: &[Event], rate: FrameRate) -> u64 {
+    let Some(first) = events.iter().min_by_key(|e| e.record.0) else {
+        return 0;
+    };
+    let start = Timecode {
+        drop_frame: first.drop_frame,
+        ..RECORD_START
+    }
+    .to_frame(rate)
+    .unwrap_or(0);
+    if first.record.0 >= start { start } else { 0 }
+}
+
+/// Read a CMX3600 edit decision list into the root timeline of `world`,
+/// above its existing tracks, with timecodes at `rate`.
+///
+/// Each channel becomes a track and each event an element per channel,
+/// with its reel under [`REEL_KEY`] and the `FROM CLIP NAME`, `TO CLIP
+/// NAME` and `SOURCE FILE` comments under [`NAME_KEY`] and [`MEDIA_KEY`].
+/// Dissolves and wipes become a [`Transition`] starting at the cut, `M2`
+/// speed changes a [`TimeRemap`]. Black filler is left as a gap. Keys,
+/// split edits and lines that cannot be read are skipped with a warning.
+pub fn import(world: &mut World, edl: &str, rate: FrameRate) -> Result<Imported> {
+    let tps = Timebase::of(world).tps();
+    let mut imported = Imported::default();
+    let events = parse(edl, rate, &mut imported.warnings);
+
+    let channels: BTreeSet<Channel> = events
+        .iter()
+        .flat_map(|e| e.channels.iter().copied())
+        .collect();
+    let mut tracks = BTreeMap::new();
+    for channel in channels {
+        let entity = Track::push(world, channel.track_name(), channel.track_kind());
+        imported.tracks.push(entity);
+        let index = world
+            .get::<Track>(entity)
+            .map(Track::index)
+            .unwrap_or_default();
+        tracks.insert(channel, index);
+    }
+
+    let base = record_base(&events, rate);
+    let ticks = |frame: u64| rate.frame_to_ticks(frame, tps);
+    // Last element spawned on each channel, the outgoing side of a
+    // transition.
+    let mut last: BTreeMap<Channel, Entity> = BTreeMap::new();
+    for (index, event) in events.iter().enumerate() {
+        let warn = |imported: &mut Imported, message: String| {
+            imported
+                .warnings
+                .push(Warning::at_line(event.line, message))
+        };
+        let position =
+            TimelineSpan::new(ticks(event.record.0 - base), ticks(event.record.1 - base))?;
+        if position.is_empty() {
+            // The outgoing side of a transition is written as an empty cut
+            // with the same event number.
+            let opens_transition = events.get(index + 1).is_some_and(|next| {
+                next.number == event.number && matches!(next.edit, Edit::Dissolve | Edit::Wipe(_))
+            });
+            if !opens_transition {
+                warn(
+                    &mut imported,
+                    format!("skipped event {} with no duration", event.number),
+                );
+            }
+            continue;
+        }
+        let kind = match event.edit {
+            Edit::Cut => None,
+            Edit::Key => {
+                warn(
+                    &mut imported,
+                    format!("imported key in event {} as a cut", event.number),
+                );
+                None
+            }
+            Edit::Dissolve => Some(TransitionKind::Dissolve),
+            Edit::Wipe(code) => {
+                if code > 2 {
+                    warn(
+                        &mut imported,
+                        format!("imported wipe {code:03} as a horizontal wipe"),
+                    );
+                }
+                Some(TransitionKind::Wipe {
+                    angle: if code == 2 { 90.0 } else { 0.0 },
+                })
+            }
+        };
+        if event.reel == BLACK_REEL {
+            if kind.is_some() {
+                warn(
+                    &mut imported,
+                    format!("skipped transition to black in event {}", event.number),
+                );
+            }
+            continue;
+        }
+        let speed = event
+            .speeds
+            .iter()
+            .find(|(reel, _)| *reel == event.reel)
+            .map(|(_, speed)| *speed);
+
+        for &channel in &event.channels {
+            let mut properties = Properties::default();
+            if event.reel != AUX_REEL {
+                properties.insert(REEL_KEY, Property::String(event.reel.clone()));
+            }
+            if let Some(clip) = event.clip() {
+                clip.apply(&mut properties);
+            }
+            let mut entity = world.spawn((
+                TimelineElement {
+                    track_num: tracks[&channel],
+                    position,
+                },
+                SourceOffset {
+                    ticks: ticks(event.source_in),
+                },
+                properties,
+            ));
+            match speed {
+                Some(0.0) => {
+                    entity.insert(TimeRemap::Freeze);
+                }
+                Some(speed) => {
+                    entity.insert(TimeRemap::Speed(speed));
+                }
+                None => {}
+            }
+            let entity = entity.id();
+            imported.elements.push(entity);
+
+            if let Some(kind) = &kind {
+                let outgoing = last.get(&channel).copied().filter(|outgoing| {
+                    world
+                        .get::<TimelineElement>(*outgoing)
+                        .is_some_and(|e| e.position.end() == position.start())
+                });
+                match outgoing {
+                    Some(outgoing) => {
+                        if let Some(mut properties) = world.get_mut::<Properties>(outgoing) {
+                            event.from.apply(&mut properties);
+                        }
+                        let end = (position.start() + ticks(event.duration)).min(position.end());
+                        let transition = Transition::new(
+                            outgoing,
+                            entity,
+                            TimelineSpan::new(position.start(), end)?,
+                            kind.clone(),
+                        );
+                        match transition.check(world) {
+                            Ok(()) => {
+                                world.spawn(transition);
+                            }
+                            Err(e) => warn(&mut imported, format!("skipped transition: {e}")),
+                        }
+                    }
+                    None => warn(
+                        &mut imported,
+                        format!(
+                            "imported transition in event {} as a cut, nothing ends where it starts",
+                            event.number
+                        ),
+                    ),
+                }
+            }
+            last.insert(channel, entity);
+        }
+    }
+    imported.warnings.sort_by_key(|w| w.line);
+    Ok(imported)
+}
+
+/// An event to write. Times are in ticks.
+struct Row {
+    channel: Channel,
+    entity: Entity,
+    record: TimelineSpan,
+    /// Edit field, length and outgoing element of the transition into
+    /// `entity`.
+    transition: Option<(&'static str, u64, Entity)>,
+}
+
+/// Source tick `element` shows at `tick`, or `None` before the start of its
+/// source. Also answers for ticks before the element, which transitions
+/// read from.
+fn source_at(world: &World, element: Entity, tick: u64) -> Option<u64> {
+    let start = world.get::<TimelineElement>(element)?.position.start();
+    let offset = world.get::<SourceOffset>(element).map_or(0, |s| s.ticks);
+    match world.get::<TimeRemap>(element) {
+        Some(remap) => remap.source_tick(start, offset, tick),
+        None => (offset + tick).checked_sub(start),
+    }
+}
+
+/// Reel of `element`, if it has one CMX3600 can hold.
+fn reel(world: &World, element: Entity) -> Option<&str> {
+    match world.get::<Properties>(element)?.get(REEL_KEY)? {
+        Property::String(reel)
+            if !reel.is_empty()
+                && reel.len() <= MAX_REEL_LEN
+                && !reel.chars().any(char::is_whitespace) =>
+        {
+            Some(reel)
+        }
+        _ => None,
+    }
+}
+
+fn edit_field(kind: &TransitionKind, entity: Entity, warnings: &mut Vec<Warning>) -> &'static str {
+    match kind {
+        TransitionKind::Dissolve => "D",
+        TransitionKind::Wipe { angle } if *angle == 0.0 => "W001",
+        TransitionKind::Wipe { angle } if *angle == 90.0 => "W002",
+        kind => {
+            warnings.push(Warning::new(format!(
+                "transition {entity} exported as a dissolve, CMX3600 has no {kind:?}"
+            )));
+            "D"
+        }
+    }
+}
+
+fn track_rows(
+    world: &World,
+    track: Entity,
+    channel: Channel,
+    rows: &mut Vec<Row>,
+    warnings: &mut Vec<Warning>,
+) -> Result {
+    let mut elements: Vec<(TimelineSpan, Entity)> = Track::elements(world, track)?
+        .into_iter()
+        .filter_map(|e| Some((world.get::<TimelineElement>(e)?.position, e)))
+        .collect();
+    elements.sort_by_key(|(span, entity)| (span.start(), span.end(), *entity));
+    let mut kept: Vec<(TimelineSpan, Entity)> = Vec::new();
+    for (position, entity) in elements {
+        if kept
+            .last()
+            .is_some_and(|(last, _)| position.start() < last.end())
+        {
+            warnings.push(Warning::new(format!(
+                "skipped {entity}, which overlaps another element on channel {}",
+                channel.field()
+            )));
+            continue;
+        }
+        kept.push((position, entity));
+    }
+
+    // Transitions start at the cut in an EDL, so the incoming element
+    // starts early and the outgoing one ends early by the part of the
+    // transition before the cut.
+    let mut transitions: Vec<Option<(&'static str, u64, u64)>> = vec![None; kept.len()];
+    for (i, pair) in kept.windows(2).enumerate() {
+        let [(before, outgoing), (after, incoming)] = *pair else {
+            continue;
+        };
+        let Some(transition) = Transition::between(world, outgoing, incoming)
+            .and_then(|t| world.get::<Transition>(t))
+            .filter(|_| before.end() == after.start())
+        else {
+            continue;
+        };
+        let edit = edit_field(&transition.kind, incoming, warnings);
+        let mut start = transition.span.start().max(before.start());
+        if source_at(world, incoming, start).is_none() {
+            warnings.push(Warning::new(format!(
+                "transition into {incoming} moved to start at the cut, there is no media before it"
+            )));
+            start = after.start();
+        }
+        if transition.span.end() > start {
+            transitions[i + 1] = Some((edit, start, transition.span.end()));
+        }
+    }
+
+    for (i, (position, entity)) in kept.iter().enumerate() {
+        let start = transitions[i].map_or(position.start(), |(_, start, _)| start);
+        let end = transitions
+            .get(i + 1)
+            .copied()
+            .flatten()
+            .map_or(position.end(), |(_, start, _)| start);
+        rows.push(Row {
+            channel,
+            entity: *entity,
+            record: TimelineSpan::new(start, end.max(start))?,
+            transition: transitions[i].map(|(edit, _, end)| (edit, end - start, kept[i - 1].1)),
+        });
+    }
+    Ok(())
+}
+
+/// `FROM CLIP NAME` or `TO CLIP NAME` comment and the `SOURCE FILE` of
+/// `element`.
+fn clip_comments(world: &World, element: Entity, side: &str, out: &mut String) {
+    let properties = world.get::<Properties>(element);
+    let name = match properties.and_then(|p| p.get(NAME_KEY)) {
+        Some(Property::String(name)) => Some(name.as_str()),
+        _ => None,
+    };
+    let media = match properties.and_then(|p| p.get(MEDIA_KEY)) {
+        Some(Property::Path(path)) => Some(path),
+        _ => None,
+    };
+    if name.is_some() || media.is_some() {
+        let _ = writeln!(out, "* {side} CLIP NAME: {}", name.unwrap_or_default());
+    }
+    if let Some(media) = media {
+        let _ = writeln!(out, "* SOURCE FILE: {}", media.display());
+    }
+}
+
+/// Write the root timeline of `world` as a CMX3600 edit decision list
+/// titled `title`, with timecodes at `rate`.
+///
+/// The first video track and up to four audio tracks are written, each
+/// element as one event starting at [`RECORD_START`]. Times are rounded to
+/// the nearest frame. Other tracks, overlapping elements, speed ramps and
+/// transitions other than dissolves and horizontal or vertical wipes
+/// cannot be represented and are reported as warnings.
+pub fn export(world: &World, title: &str, rate: FrameRate, drop_frame: bool) -> Result<Exported> {
+    let tps = Timebase::of(world).tps();
+    let record_start = Timecode {
+        drop_frame,
+        ..RECORD_START
+    }
+    .to_frame(rate)?;
+    let mut warnings = Vec::new();
+    let mut rows = Vec::new();
+    let (mut video, mut audio) = (false, 0);
+    for (entity, track) in Track::all(world) {
+        let channel = match track.kind {
+            TrackKind::Video if !video => Channel::Video,
+            TrackKind::Audio if audio < MAX_AUDIO_CHANNELS => Channel::Audio(audio + 1),
+            _ => {
+                warnings.push(Warning::new(format!(
+                    "skipped track {}, CMX3600 has one video and {MAX_AUDIO_CHANNELS} audio channels",
+                    track.index()
+                )));
+                continue;
+            }
+        };
+        match channel {
+            Channel::Video => video = true,
+            Channel::Audio(n) => audio = n,
+        }
+        track_rows(world, entity, channel, &mut rows, &mut warnings)?;
+    }
+    warn_untracked(world, &mut warnings);
+    rows.sort_by_key(|row| (row.record.start(), row.channel));
+
+    let source = |element: Entity, tick: u64, warnings: &mut Vec<Warning>| {
+        let ticks = source_at(world, element, tick).unwrap_or_else(|| {
+            warnings.push(Warning::new(format!(
+                "{element} has no source at tick {tick}, exported as the start of its media"
+            )));
+            0
+        });
+        Timecode::from_frame(rate.ticks_to_nearest_frame(ticks, tps), rate, drop_frame)
+    };
+    let record = |tick: u64| {
+        let frame = record_start + rate.ticks_to_nearest_frame(tick, tps);
+        Timecode::from_frame(frame, rate, drop_frame)
+    };
+    let mode = if drop_frame {
+        "DROP FRAME"
+    } else {
+        "NON-DROP FRAME"
+    };
+    let mut text = format!("TITLE: {title}\nFCM: {mode}\n");
+    for (i, row) in rows.iter().enumerate() {
+        let number = i + 1;
+        let channel = row.channel.field();
+        let (start, end) = (row.record.start(), row.record.end());
+        let reel_name = match (world.get::<Properties>(row.entity), reel(world, row.entity)) {
+            (_, Some(reel)) => reel,
+            (Some(properties), None) if properties.get(REEL_KEY).is_some() => {
+                warnings.push(Warning::new(format!(
+                    "reel of {} is not a valid CMX3600 reel, exported as {AUX_REEL}",
+                    row.entity
+                )));
+                AUX_REEL
+            }
+            _ => AUX_REEL,
+        };
+        text.push('\n');
+        let (edit, duration) = match row.transition {
+            Some((edit, duration, outgoing)) => {
+                let from = source(outgoing, start, &mut warnings)?;
+                let _ = writeln!(
+                    text,
+                    "{number:03}  {:<8} {channel:<5} C        {from} {from} {} {}",
+                    reel(world, outgoing).unwrap_or(AUX_REEL),
+                    record(start)?,
+                    record(start)?,
+                );
+                (
+                    edit,
+                    format!("{:03}", rate.ticks_to_nearest_frame(duration, tps)),
+                )
+            }
+            None => ("C", String::new()),
+        };
+        let source_in = source(row.entity, start, &mut warnings)?;
+        let _ = writeln!(
+            text,
+            "{number:03}  {reel_name:<8} {channel:<5} {edit:<4} {duration:>3} {source_in} {} {} {}",
+            source(row.entity, end, &mut warnings)?,
+            record(start)?,
+            record(end)?,
+        );
+        let speed = match world.get::<TimeRemap>(row.entity) {
+            Some(TimeRemap::Speed(speed)) => Some(*speed),
+            Some(TimeRemap::Freeze) => Some(0.0),
+            Some(TimeRemap::Ramp(_)) => {
+                warnings.push(Warning::new(format!(
+                    "speed ramp on {} exported at normal speed",
+                    row.entity
+                )));
+                None
+            }
+            None => None,
+        };
+        if let Some(speed) = speed {
+            let _ = writeln!(
+                text,
+                "M2   {reel_name:<8} {:05.1}                {}",
+                speed * rate.nominal() as f64,
+                source_in,
+            );
+        }
+        if let Some((_, _, outgoing)) = row.transition {
+            clip_comments(world, outgoing, "FROM", &mut text);
+            clip_comments(world, row.entity, "TO", &mut text);
+        } else {
+            clip_comments(world, row.entity, "FROM", &mut text);
+        }
+    }
+    Ok(Exported { text, warnings })
+}
@@ -0,0 +1,83 @@
+use bevy_ecs::{entity::Entity, world::World};
+
+use crate::timeline::{Track, elements::TimelineElement, sequence::sequence_of};
+
+pub mod edl;
+pub mod otio;
+
+/// Property holding an element's display name, a [`Property::String`].
+///
+/// [`Property::String`]: crate::timeline::elements::Property::String
+pub const NAME_KEY: &str = "name";
+/// Property holding the media file an element plays, a
+/// [`Property::Path`].
+///
+/// [`Property::Path`]: crate::timeline::elements::Property::Path
+pub const MEDIA_KEY: &str = "media";
+/// Property holding the tape or card an element's media came from, a
+/// [`Property::String`].
+///
+/// [`Property::String`]: crate::timeline::elements::Property::String
+pub const REEL_KEY: &str = "reel";
+
+/// Something an import or export could not carry over exactly. Importers
+/// and exporters report these instead of failing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub message: String,
+    /// 1-based line of the input, for line-oriented formats.
+    pub line: Option<usize>,
+}
+
+impl Warning {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            line: None,
+        }
+    }
+
+    pub(crate) fn at_line(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            line: Some(line),
+        }
+    }
+}
+
+/// Entities created by an import. Importers append to the root timeline.
+#[derive(Debug, Clone, Default)]
+pub struct Imported {
+    /// New tracks, bottom to top.
+    pub tracks: Vec<Entity>,
+    pub elements: Vec<Entity>,
+    pub warnings: Vec<Warning>,
+}
+
+/// Output of an export. Exporters write the root timeline.
+#[derive(Debug, Clone)]
+pub struct Exported {
+    pub text: String,
+    pub warnings: Vec<Warning>,
+}
+
+/// Warn about root timeline elements whose `track_num` has no [`Track`].
+/// Exporters walk the tracks, so these would be dropped silently.
+pub(crate) fn warn_untracked(world: &World, warnings: &mut Vec<Warning>) {
+    let Some(mut query) = world.try_query::<(Entity, &TimelineElement)>() else {
+        return;
+    };
+    let mut untracked: Vec<(u64, u64, Entity)> = query
+        .iter(world)
+        .filter(|(entity, element)| {
+            sequence_of(world, *entity).is_none() && Track::find(world, element.track_num).is_none()
+        })
+        .map(|(entity, element)| (element.track_num, element.position.start(), entity))
+        .collect();
+    untracked.sort_unstable();
+    for (track, _, entity) in untracked {
+        warnings.push(Warning::new(format!(
+            "skipped {entity}, which is on track {track} that does not exist"
+        )));
+    }
+}
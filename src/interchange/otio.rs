@@ -0,0 +1,762 @@
+use std::path::PathBuf;
+
+use bevy_ecs::{entity::Entity, world::World};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    interchange::{Exported, Imported, MEDIA_KEY, NAME_KEY, Warning, warn_untracked},
+    prelude::*,
+    timeline::{
+        CompoundClip, FrameRate, Marker, Region, Timebase, TimelineSpan, Track, TrackKind,
+        Transition, TransitionKind,
+        elements::{Properties, Property, SourceOffset, TimeRemap, TimelineElement},
+    },
+};
+
+/// Metadata namespace for values OpenTimelineIO has no field for.
+const METADATA_KEY: &str = "lunaris";
+
+/// OpenTimelineIO marker colors and the RGBA used for them.
+const MARKER_COLORS: [(&str, [u8; 4]); 11] = [
+    ("PINK", [0xFF, 0x70, 0x70, 0xFF]),
+    ("RED", [0xFF, 0x00, 0x00, 0xFF]),
+    ("ORANGE", [0xFF, 0xA0, 0x00, 0xFF]),
+    ("YELLOW", [0xFF, 0xFF, 0x00, 0xFF]),
+    ("GREEN", [0x00, 0xFF, 0x00, 0xFF]),
+    ("CYAN", [0x00, 0xFF, 0xFF, 0xFF]),
+    ("BLUE", [0x00, 0x00, 0xFF, 0xFF]),
+    ("PURPLE", [0xA0, 0x00, 0xD0, 0xFF]),
+    ("MAGENTA", [0xFF, 0x00, 0xFF, 0xFF]),
+    ("BLACK", [0x00, 0x00, 0x00, 0xFF]),
+    ("WHITE", [0xFF, 0xFF, 0xFF, 0xFF]),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "OTIO_SCHEMA", rename = "RationalTime.1")]
+struct RationalTime {
+    rate: f64,
+    value: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "OTIO_SCHEMA", rename = "TimeRange.1")]
+struct TimeRange {
+    duration: RationalTime,
+    start_time: RationalTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "OTIO_SCHEMA", rename = "Timeline.1")]
+struct OtioTimeline {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    metadata: Map<String, Value>,
+    #[serde(default)]
+    global_start_time: Option<RationalTime>,
+    tracks: Item,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Composition {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    children: Vec<Item>,
+    #[serde(default)]
+    markers: Vec<OtioMarker>,
+    #[serde(default)]
+    effects: Vec<Effect>,
+    #[serde(default)]
+    metadata: Map<String, Value>,
+    #[serde(default)]
+    source_range: Option<TimeRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OtioTrack {
+    #[serde(default)]
+    kind: String,
+    #[serde(flatten)]
+    composition: Composition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Clip {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    source_range: Option<TimeRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    media_reference: Option<MediaReference>,
+    /// `Clip.2` keeps its references here instead.
+    #[serde(default, skip_serializing)]
+    media_references: Map<String, Value>,
+    #[serde(default, skip_serializing)]
+    active_media_reference_key: Option<String>,
+    #[serde(default)]
+    markers: Vec<OtioMarker>,
+    #[serde(default)]
+    effects: Vec<Effect>,
+    #[serde(default)]
+    metadata: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Gap {
+    #[serde(default)]
+    name: String,
+    source_range: TimeRange,
+    #[serde(default)]
+    markers: Vec<OtioMarker>,
+    #[serde(default)]
+    effects: Vec<Effect>,
+    #[serde(default)]
+    metadata: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OtioTransition {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    transition_type: String,
+    in_offset: RationalTime,
+    out_offset: RationalTime,
+    #[serde(default)]
+    metadata: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "OTIO_SCHEMA")]
+enum Item {
+    #[serde(rename = "Stack.1")]
+    Stack(Composition),
+    #[serde(rename = "Track.1")]
+    Track(OtioTrack),
+    #[serde(rename = "Clip.1", alias = "Clip.2")]
+    Clip(Clip),
+    #[serde(rename = "Gap.1")]
+    Gap(Gap),
+    #[serde(rename = "Transition.1")]
+    Transition(OtioTransition),
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "OTIO_SCHEMA")]
+enum MediaReference {
+    #[serde(rename = "ExternalReference.1")]
+    External {
+        target_url: String,
+        #[serde(default)]
+        available_range: Option<TimeRange>,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    #[serde(rename = "MissingReference.1")]
+    Missing {
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "OTIO_SCHEMA")]
+enum Effect {
+    #[serde(rename = "LinearTimeWarp.1")]
+    LinearTimeWarp {
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        effect_name: String,
+        time_scalar: f64,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    #[serde(rename = "FreezeFrame.1")]
+    FreezeFrame {
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        effect_name: String,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "OTIO_SCHEMA", rename = "Marker.2")]
+struct OtioMarker {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    color: String,
+    marked_range: TimeRange,
+    #[serde(default)]
+    comment: String,
+    #[serde(default)]
+    metadata: Map<String, Value>,
+}
+
+/// Converts between ticks and [`RationalTime`]s at a fixed rate.
+struct Clock {
+    tps: u64,
+    rate: FrameRate,
+}
+
+impl Clock {
+    fn time(&self, ticks: u64) -> RationalTime {
+        let frames = ticks as f64 * self.rate.numerator() as f64
+            / (self.tps as f64 * self.rate.denominator() as f64);
+        RationalTime {
+            rate: self.rate.as_f64(),
+            value: frames,
+        }
+    }
+
+    fn range(&self, span: TimelineSpan) -> TimeRange {
+        TimeRange {
+            duration: self.time(span.duration()),
+            start_time: self.time(span.start()),
+        }
+    }
+}
+
+/// Ticks in `time`, rounded to the nearest tick. Negative times clamp to
+/// `0`.
+fn ticks(time: RationalTime, tps: u64) -> Result<u64> {
+    if !(time.rate > 0.0 && time.value.is_finite()) {
+        return Err(invalid(format!(
+            "time {} at rate {} is not a valid time",
+            time.value, time.rate
+        )));
+    }
+    Ok((time.value / time.rate * tps as f64).round().max(0.0) as u64)
+}
+
+fn span(range: TimeRange, tps: u64) -> Result<TimelineSpan> {
+    let start = ticks(range.start_time, tps)?;
+    TimelineSpan::with_duration(start, ticks(range.duration, tps)?)
+}
+
+fn invalid(reason: impl Into<String>) -> LunarisError {
+    LunarisError::InvalidInterchange {
+        format: "OpenTimelineIO",
+        reason: reason.into(),
+    }
+}
+
+fn color_name(color: [u8; 4]) -> &'static str {
+    let distance = |other: [u8; 4]| -> u32 {
+        (0..3)
+            .map(|i| (color[i] as i32 - other[i] as i32).unsigned_abs().pow(2))
+            .sum()
+    };
+    MARKER_COLORS
+        .iter()
+        .min_by_key(|(_, rgba)| distance(*rgba))
+        .map_or("RED", |(name, _)| name)
+}
+
+/// Exact RGBA from our metadata, or the color OpenTimelineIO names.
+fn marker_color(marker: &OtioMarker) -> [u8; 4] {
+    marker
+        .metadata
+        .get(METADATA_KEY)
+        .and_then(|m| serde_json::from_value(m.get("color")?.clone()).ok())
+        .or_else(|| {
+            MARKER_COLORS
+                .iter()
+                .find(|(name, _)| marker.color.eq_ignore_ascii_case(name))
+                .map(|(_, rgba)| *rgba)
+        })
+        .unwrap_or(crate::timeline::marker::DEFAULT_MARKER_COLOR)
+}
+
+fn track_kind(kind: &str) -> TrackKind {
+    match kind {
+        "Video" => TrackKind::Video,
+        "Audio" => TrackKind::Audio,
+        "Subtitle" => TrackKind::Subtitle,
+        _ => TrackKind::Data,
+    }
+}
+
+fn kind_name(kind: TrackKind) -> &'static str {
+    match kind {
+        TrackKind::Video => "Video",
+        TrackKind::Audio => "Audio",
+        TrackKind::Subtitle => "Subtitle",
+        TrackKind::Data => "Data",
+    }
+}
+
+/// Read an OpenTimelineIO `.otio` document into the root timeline of
+/// `world`, above its existing tracks.
+///
+/// Times are converted against the world's [`Timebase`]. Clips become
+/// elements with a [`SourceOffset`], their name under [`NAME_KEY`] and
+/// their media under [`MEDIA_KEY`]; linear time warps and freeze frames
+/// become a [`TimeRemap`]. Nested stacks and unknown schemas are skipped
+/// with a warning.
+pub fn import(world: &mut World, otio: &str) -> Result<Imported> {
+    let timeline: OtioTimeline = serde_json::from_str(otio).map_err(|e| invalid(e.to_string()))?;
+    let Item::Stack(stack) = timeline.tracks else {
+        return Err(invalid("timeline tracks are not a Stack"));
+    };
+    let tps = Timebase::of(world).tps();
+    let mut imported = Imported::default();
+
+    for child in stack.children {
+        let Item::Track(track) = child else {
+            imported
+                .warnings
+                .push(Warning::new("skipped a top-level item that is not a track"));
+            continue;
+        };
+        import_track(world, track, tps, &mut imported)?;
+    }
+    for marker in &stack.markers {
+        spawn_marker(world, marker, None, 0, tps)?;
+    }
+    Ok(imported)
+}
+
+impl Clip {
+    fn media(&self) -> Option<MediaReference> {
+        if let Some(media) = &self.media_reference {
+            return Some(media.clone());
+        }
+        let key = self
+            .active_media_reference_key
+            .as_deref()
+            .unwrap_or("DEFAULT_MEDIA");
+        serde_json::from_value(self.media_references.get(key)?.clone()).ok()
+    }
+}
+
+fn import_track(world: &mut World, track: OtioTrack, tps: u64, imported: &mut Imported) -> Result {
+    let entity = Track::push(world, &track.composition.name, track_kind(&track.kind));
+    imported.tracks.push(entity);
+    let index = world
+        .get::<Track>(entity)
+        .map(Track::index)
+        .unwrap_or_default();
+
+    let mut cursor = 0u64;
+    let mut previous: Option<Entity> = None;
+    let mut pending: Option<(OtioTransition, u64)> = None;
+    for item in track.composition.children {
+        match item {
+            Item::Clip(clip) => {
+                let range = match (&clip.source_range, &clip.media()) {
+                    (Some(range), _) => *range,
+                    (
+                        None,
+                        Some(MediaReference::External {
+                            available_range: Some(range),
+                            ..
+                        }),
+                    ) => *range,
+                    _ => {
+                        imported.warnings.push(Warning::new(format!(
+                            "skipped clip {:?} without a source range",
+                            clip.name
+                        )));
+                        continue;
+                    }
+                };
+                let source = span(range, tps)?;
+                let position = TimelineSpan::with_duration(cursor, source.duration())?;
+                let element = spawn_clip(world, &clip, index, position, source.start(), imported);
+                // Clip markers are in source time.
+                let shift = cursor as i128 - source.start() as i128;
+                for marker in &clip.markers {
+                    spawn_marker(world, marker, Some(index), shift, tps)?;
+                }
+                if let Some((transition, cut)) = pending.take() {
+                    match previous {
+                        Some(outgoing) => spawn_transition(
+                            world,
+                            &transition,
+                            outgoing,
+                            element,
+                            cut,
+                            tps,
+                            imported,
+                        )?,
+                        None => imported.warnings.push(Warning::new(format!(
+                            "skipped transition {:?} at the start of a track",
+                            transition.name
+                        ))),
+                    }
+                }
+                cursor = position.end();
+                previous = Some(element);
+            }
+            Item::Gap(gap) => {
+                cursor += span(gap.source_range, tps)?.duration();
+                previous = None;
+            }
+            Item::Transition(transition) => pending = Some((transition, cursor)),
+            Item::Stack(Composition { source_range, .. })
+            | Item::Track(OtioTrack {
+                composition: Composition { source_range, .. },
+                ..
+            }) => {
+                imported
+                    .warnings
+                    .push(Warning::new("skipped a nested stack or track"));
+                if let Some(range) = source_range {
+                    cursor += span(range, tps)?.duration();
+                }
+                previous = None;
+            }
+            Item::Unsupported => {
+                imported
+                    .warnings
+                    .push(Warning::new("skipped an item with an unsupported schema"));
+                previous = None;
+            }
+        }
+    }
+    if let Some((transition, _)) = pending {
+        imported.warnings.push(Warning::new(format!(
+            "skipped transition {:?} at the end of a track",
+            transition.name
+        )));
+    }
+    for marker in &track.composition.markers {
+        spawn_marker(world, marker, Some(index), 0, tps)?;
+    }
+    Ok(())
+}
+
+fn spawn_clip(
+    world: &mut World,
+    clip: &Clip,
+    track: u64,
+    position: TimelineSpan,
+    source_offset: u64,
+    imported: &mut Imported,
+) -> Entity {
+    let mut properties = Properties::default();
+    if !clip.name.is_empty() {
+        properties.insert(NAME_KEY, Property::String(clip.name.clone()));
+    }
+    match &clip.media() {
+        Some(MediaReference::External { target_url, .. }) => {
+            let path = target_url.strip_prefix("file://").unwrap_or(target_url);
+            properties.insert(MEDIA_KEY, Property::Path(PathBuf::from(path)));
+        }
+        Some(MediaReference::Unsupported) => imported.warnings.push(Warning::new(format!(
+            "clip {:?} has an unsupported media reference",
+            clip.name
+        ))),
+        Some(MediaReference::Missing { .. }) | None => {}
+    }
+    let mut entity = world.spawn((
+        TimelineElement {
+            track_num: track,
+            position,
+        },
+        SourceOffset {
+            ticks: source_offset,
+        },
+        properties,
+    ));
+    for effect in &clip.effects {
+        match effect {
+            Effect::LinearTimeWarp { time_scalar, .. } if *time_scalar == 0.0 => {
+                entity.insert(TimeRemap::Freeze);
+            }
+            Effect::LinearTimeWarp { time_scalar, .. } => {
+                entity.insert(TimeRemap::Speed(*time_scalar));
+            }
+            Effect::FreezeFrame { .. } => {
+                entity.insert(TimeRemap::Freeze);
+            }
+            Effect::Unsupported => imported.warnings.push(Warning::new(format!(
+                "skipped an unsupported effect on clip {:?}",
+                clip.name
+            ))),
+        }
+    }
+    let entity = entity.id();
+    imported.elements.push(entity);
+    entity
+}
+
+fn spawn_transition(
+    world: &mut World,
+    transition: &OtioTransition,
+    outgoing: Entity,
+    incoming: Entity,
+    cut: u64,
+    tps: u64,
+    imported: &mut Imported,
+) -> Result {
+    let kind = transition
+        .metadata
+        .get(METADATA_KEY)
+        .and_then(|m| serde_json::from_value(m.get("kind")?.clone()).ok())
+        .unwrap_or_else(|| {
+            if transition.transition_type != "SMPTE_Dissolve" {
+                imported.warnings.push(Warning::new(format!(
+                    "transition type {:?} imported as a dissolve",
+                    transition.transition_type
+                )));
+            }
+            TransitionKind::Dissolve
+        });
+    let start = cut.saturating_sub(ticks(transition.in_offset, tps)?);
+    let end = cut + ticks(transition.out_offset, tps)?;
+    let transition = Transition::new(outgoing, incoming, TimelineSpan::new(start, end)?, kind);
+    match transition.check(world) {
+        Ok(()) => {
+            world.spawn(transition);
+        }
+        Err(e) => imported
+            .warnings
+            .push(Warning::new(format!("skipped transition: {e}"))),
+    }
+    Ok(())
+}
+
+/// Spawn `marker` with its range moved by `shift` ticks.
+fn spawn_marker(
+    world: &mut World,
+    marker: &OtioMarker,
+    track: Option<u64>,
+    shift: i128,
+    tps: u64,
+) -> Result {
+    let range = span(marker.marked_range, tps)?;
+    let start = (range.start() as i128 + shift).max(0) as u64;
+    let color = marker_color(marker);
+    if range.is_empty() {
+        let mut m = Marker::new(start, &marker.name)
+            .with_color(color)
+            .with_note(&marker.comment);
+        m.track = track;
+        world.spawn(m);
+    } else {
+        let mut r = Region::new(
+            TimelineSpan::with_duration(start, range.duration())?,
+            &marker.name,
+        )
+        .with_color(color)
+        .with_note(&marker.comment);
+        r.track = track;
+        world.spawn(r);
+    }
+    Ok(())
+}
+
+/// Write the root timeline of `world` as an OpenTimelineIO `.otio`
+/// document named `name`, with times expressed at `rate`.
+///
+/// Tracks are written bottom to top with the space between elements as
+/// gaps. Overlapping elements, elements on missing tracks, speed ramps and
+/// the contents of compound clips cannot be represented and are reported as
+/// warnings.
+pub fn export(world: &World, name: &str, rate: FrameRate) -> Result<Exported> {
+    let clock = Clock {
+        tps: Timebase::of(world).tps(),
+        rate,
+    };
+    let mut warnings = Vec::new();
+    let mut stack = Composition {
+        name: "tracks".to_string(),
+        ..Default::default()
+    };
+
+    for (track_entity, track) in Track::all(world) {
+        let mut composition = Composition {
+            name: track.name.clone(),
+            ..Default::default()
+        };
+        let mut elements: Vec<(TimelineSpan, Entity)> = Track::elements(world, track_entity)?
+            .into_iter()
+            .filter_map(|e| Some((world.get::<TimelineElement>(e)?.position, e)))
+            .collect();
+        elements.sort_by_key(|(span, entity)| (span.start(), span.end(), *entity));
+
+        let mut cursor = 0;
+        let mut previous: Option<Entity> = None;
+        for (position, entity) in elements {
+            if position.start() < cursor {
+                warnings.push(Warning::new(format!(
+                    "skipped {entity}, which overlaps another element on track {}",
+                    track.index()
+                )));
+                continue;
+            }
+            if position.start() > cursor {
+                composition.children.push(Item::Gap(Gap {
+                    name: String::new(),
+                    source_range: clock.range(TimelineSpan::new(0, position.start() - cursor)?),
+                    markers: Vec::new(),
+                    effects: Vec::new(),
+                    metadata: Map::new(),
+                }));
+                previous = None;
+            }
+            if let Some(outgoing) = previous
+                && let Some(transition) = Transition::between(world, outgoing, entity)
+                    .and_then(|t| world.get::<Transition>(t))
+            {
+                composition
+                    .children
+                    .push(Item::Transition(export_transition(
+                        transition,
+                        position.start(),
+                        &clock,
+                    )));
+            }
+            composition.children.push(Item::Clip(export_clip(
+                world,
+                entity,
+                position,
+                &clock,
+                &mut warnings,
+            )));
+            cursor = position.end();
+            previous = Some(entity);
+        }
+        track_markers(world, Some(track.index()), &clock, &mut composition.markers);
+        stack.children.push(Item::Track(OtioTrack {
+            kind: kind_name(track.kind).to_string(),
+            composition,
+        }));
+    }
+    track_markers(world, None, &clock, &mut stack.markers);
+    warn_untracked(world, &mut warnings);
+
+    let timeline = OtioTimeline {
+        name: name.to_string(),
+        metadata: Map::new(),
+        global_start_time: None,
+        tracks: Item::Stack(stack),
+    };
+    Ok(Exported {
+        text: serde_json::to_string_pretty(&timeline).map_err(|e| invalid(e.to_string()))?,
+        warnings,
+    })
+}
+
+fn export_clip(
+    world: &World,
+    entity: Entity,
+    position: TimelineSpan,
+    clock: &Clock,
+    warnings: &mut Vec<Warning>,
+) -> Clip {
+    let properties = world.get::<Properties>(entity);
+    let name = match properties.and_then(|p| p.get(NAME_KEY)) {
+        Some(Property::String(name)) => name.clone(),
+        _ => String::new(),
+    };
+    let media_reference = match properties.and_then(|p| p.get(MEDIA_KEY)) {
+        Some(Property::Path(path)) => MediaReference::External {
+            target_url: path.to_string_lossy().into_owned(),
+            available_range: None,
+            metadata: Map::new(),
+        },
+        _ => MediaReference::Missing {
+            metadata: Map::new(),
+        },
+    };
+    if world.get::<CompoundClip>(entity).is_some() {
+        warnings.push(Warning::new(format!(
+            "compound clip {entity} exported without its sequence"
+        )));
+    }
+    let effects = match world.get::<TimeRemap>(entity) {
+        Some(TimeRemap::Speed(speed)) => vec![Effect::LinearTimeWarp {
+            name: String::new(),
+            effect_name: "LinearTimeWarp".to_string(),
+            time_scalar: *speed,
+            metadata: Map::new(),
+        }],
+        Some(TimeRemap::Freeze) => vec![Effect::FreezeFrame {
+            name: String::new(),
+            effect_name: "FreezeFrame".to_string(),
+            metadata: Map::new(),
+        }],
+        Some(TimeRemap::Ramp(_)) => {
+            warnings.push(Warning::new(format!(
+                "speed ramp on {entity} exported at normal speed"
+            )));
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+    let offset = world.get::<SourceOffset>(entity).map_or(0, |s| s.ticks);
+    Clip {
+        name,
+        source_range: Some(clock.range(TimelineSpan::new_unchecked(
+            offset,
+            offset.saturating_add(position.duration()),
+        ))),
+        media_reference: Some(media_reference),
+        media_references: Map::new(),
+        active_media_reference_key: None,
+        markers: Vec::new(),
+        effects,
+        metadata: Map::new(),
+    }
+}
+
+fn export_transition(transition: &Transition, cut: u64, clock: &Clock) -> OtioTransition {
+    let (transition_type, metadata) = match &transition.kind {
+        TransitionKind::Dissolve => ("SMPTE_Dissolve", Map::new()),
+        kind => {
+            let mut metadata = Map::new();
+            metadata.insert(METADATA_KEY.to_string(), json!({ "kind": kind }));
+            ("Custom_Transition", metadata)
+        }
+    };
+    OtioTransition {
+        name: String::new(),
+        transition_type: transition_type.to_string(),
+        in_offset: clock.time(cut.saturating_sub(transition.span.start())),
+        out_offset: clock.time(transition.span.end().saturating_sub(cut)),
+        metadata,
+    }
+}
+
+/// Markers and regions scoped to exactly `track`, or the timeline-wide
+/// ones for `None`.
+fn track_markers(world: &World, track: Option<u64>, clock: &Clock, out: &mut Vec<OtioMarker>) {
+    let marker = |name: &str, color: [u8; 4], note: &str, span: TimelineSpan| {
+        let mut metadata = Map::new();
+        metadata.insert(METADATA_KEY.to_string(), json!({ "color": color }));
+        OtioMarker {
+            name: name.to_string(),
+            color: color_name(color).to_string(),
+            marked_range: clock.range(span),
+            comment: note.to_string(),
+            metadata,
+        }
+    };
+    out.extend(
+        Marker::all(world, track)
+            .into_iter()
+            .filter(|(_, m)| m.track == track)
+            .map(|(_, m)| marker(&m.name, m.color, &m.note, TimelineSpan::empty_at(m.tick))),
+    );
+    out.extend(
+        Region::all(world, track)
+            .into_iter()
+            .filter(|(_, r)| r.track == track)
+            .map(|(_, r)| marker(&r.name, r.color, &r.note, r.span)),
+    );
+}
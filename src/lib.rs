@@ -4,8 +4,11 @@
 #![deny(clippy::style)]
 
 pub mod consts;
+pub mod history;
+pub mod interchange;
 pub mod plugin;
 pub mod prelude;
+pub mod project;
 pub mod protocol;
 pub mod render;
 pub mod request;
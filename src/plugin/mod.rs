@@ -2,13 +2,17 @@ use bevy_ecs::{prelude::*, system::BoxedSystem};
 use debug_unreachable::debug_unreachable;
 use egui::{MenuBar, Ui};
 use futures::future::BoxFuture;
-use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::{
+    history::{Undoable, record_undo_events},
     render::RawImage,
     request::DynOrchestrator,
-    timeline::elements::{Properties, Property},
+    timeline::{
+        Playhead, TrackKind,
+        elements::{CurveValue, Properties, Property, PropertySchema, PropertySet},
+        sequence::resolve_in,
+    },
     util::error::Result,
 };
 
@@ -30,32 +34,86 @@ pub trait Plugin: Send + Sync {
 }
 
 pub trait SystemPlugin: System {
-    type UndoTok: Event + Serialize + for<'de> Deserialize<'de>;
+    /// Change made by this plugin. Send one as an event after each change.
+    type UndoTok: Event + Undoable;
 
+    /// System that moves this plugin's undo tokens into the shared
+    /// [`UndoHistory`](crate::history::UndoHistory). The host should run it
+    /// after the plugin's systems.
     fn undo_system(&mut self) -> Option<BoxedSystem<(), ()>> {
-        None
+        Some(Box::new(IntoSystem::into_system(
+            record_undo_events::<Self::UndoTok>,
+        )))
     }
 }
 
 pub struct RenderJob {
+    /// Tick to render, in the sequence that holds `entity`. See
+    /// [`sequence::resolve`](crate::timeline::sequence::resolve).
     pub frame: u64,
+    /// Position in the element's source media at `frame`, with its
+    /// [`TimeRemap`](crate::timeline::elements::TimeRemap) applied. Decoders
+    /// should fetch this rather than derive it from `frame`.
+    pub source_tick: u64,
     pub entity: Entity,
     pub parameters: Properties,
+    /// Viewer the frame is rendered for, if any.
+    pub playhead: Option<Entity>,
 }
 
 impl RenderJob {
     pub fn new(frame: u64, entity: Entity, parameters: Properties) -> Self {
         Self {
             frame,
+            source_tick: frame,
             entity,
             parameters,
+            playhead: None,
         }
     }
 
+    /// Jobs for every element of `kind` that `playhead` currently shows,
+    /// bottom to top. Playheads on media show no elements; the host decodes
+    /// their source directly.
+    ///
+    /// Elements are looked up in the
+    /// [`TimelineIndex`](crate::timeline::TimelineIndex) when the world has
+    /// one, so this is cheap enough to call on every scrub.
+    pub fn for_playhead(world: &World, playhead: Entity, kind: TrackKind) -> Result<Vec<Self>> {
+        let tick = Playhead::tick(world, playhead)?;
+        let Some(sequence) = Playhead::target(world, playhead)?.sequence() else {
+            return Ok(Vec::new());
+        };
+        Ok(resolve_in(world, sequence, tick, kind)?
+            .into_iter()
+            .map(|resolved| Self {
+                frame: resolved.local_tick,
+                source_tick: resolved.source_tick,
+                entity: resolved.entity,
+                parameters: world
+                    .get::<Properties>(resolved.entity)
+                    .cloned()
+                    .unwrap_or_default(),
+                playhead: Some(playhead),
+            })
+            .collect())
+    }
+
     pub fn parameter(&self, key: &str) -> Option<&Property> {
         self.parameters.get(key)
     }
 
+    /// Numeric or animated parameter `key` as seen at `frame`. Curves are
+    /// evaluated, plain numbers are returned as [`CurveValue::Float`].
+    pub fn parameter_value(&self, key: &str) -> Option<CurveValue> {
+        self.parameters.value_at(key, self.frame)
+    }
+
+    /// Parameters read into a typed [`PropertySet`].
+    pub fn params<T: PropertySet>(&self) -> Result<T> {
+        T::from_properties(&self.parameters)
+    }
+
     pub fn parameters(&self) -> &Properties {
         &self.parameters
     }
@@ -69,6 +127,38 @@ pub type RenderTask = BoxFuture<'static, Result<RawImage>>;
 
 pub trait Renderer: Plugin {
     fn schedule_render(&self, job: RenderJob) -> Result<RenderTask>;
+
+    /// Properties this renderer reads from its elements. The host validates
+    /// and fills in defaults against it before scheduling a job.
+    fn property_schema(&self) -> PropertySchema {
+        PropertySchema::any()
+    }
+}
+
+/// Both sides of a [`Transition`](crate::timeline::Transition) at one tick,
+/// already rendered.
+pub struct TransitionJob {
+    pub outgoing: RawImage,
+    pub incoming: RawImage,
+    /// `0.0` at the start of the transition, `1.0` at its end.
+    pub progress: f64,
+    /// The transition entity.
+    pub entity: Entity,
+    pub parameters: Properties,
+}
+
+pub type TransitionTask = BoxFuture<'static, Result<RawImage>>;
+
+/// Blends transitions of kind
+/// [`TransitionKind::Plugin`](crate::timeline::TransitionKind::Plugin)
+/// naming this plugin.
+pub trait Transition: Plugin {
+    fn schedule_transition(&self, job: TransitionJob) -> Result<TransitionTask>;
+
+    /// Properties this transition reads from its entity.
+    fn property_schema(&self) -> PropertySchema {
+        PropertySchema::any()
+    }
 }
 
 // Optional GUI capability; separate trait keeps core Plugin object-safe.
@@ -111,9 +201,15 @@ pub struct RendererRegistration {
     pub build: fn() -> Box<dyn Renderer>,
 }
 
+pub struct TransitionRegistration {
+    pub name: &'static str,
+    pub build: fn() -> Box<dyn Transition>,
+}
+
 inventory::collect!(PluginRegistration);
 inventory::collect!(GuiRegistration);
 inventory::collect!(RendererRegistration);
+inventory::collect!(TransitionRegistration);
 
 // Optional: System contribution capability. Plugins that implement this trait can
 // register ECS systems/resources/events into the host `World`.
@@ -333,6 +429,89 @@ impl<T: Plugin + Renderer> Renderer for __ArcPluginRendererAdapter<T> {
         let guard = self.inner.read();
         Renderer::schedule_render(&*guard, job)
     }
+
+    fn property_schema(&self) -> PropertySchema {
+        let guard = self.inner.read();
+        Renderer::property_schema(&*guard)
+    }
+}
+
+#[doc(hidden)]
+pub struct __ArcPluginTransitionAdapter<T> {
+    inner: std::sync::Arc<parking_lot::RwLock<T>>,
+}
+
+impl<T> __ArcPluginTransitionAdapter<T> {
+    pub fn new_with_shared(inner: std::sync::Arc<parking_lot::RwLock<T>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T: Plugin + Transition> Plugin for __ArcPluginTransitionAdapter<T> {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        unsafe {
+            debug_unreachable!(
+                "__ArcPluginTransitionAdapter is constructed via export_plugin! macro"
+            );
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        if let Some(guard) = self.inner.try_read() {
+            Plugin::name(&*guard)
+        } else {
+            "<locked>"
+        }
+    }
+
+    fn init(&self, ctx: PluginContext<'_>) -> Result {
+        let guard = self.inner.read();
+        Plugin::init(&*guard, ctx)
+    }
+
+    fn update_world(&mut self, ctx: PluginContext<'_>) -> Result {
+        let mut guard = self.inner.write();
+        Plugin::update_world(&mut *guard, ctx)
+    }
+
+    fn report(&self, ctx: PluginContext<'_>) -> PluginReport {
+        if let Some(guard) = self.inner.try_read() {
+            Plugin::report(&*guard, ctx)
+        } else {
+            PluginReport::Operational
+        }
+    }
+
+    fn shutdown(&mut self, ctx: PluginContext<'_>) {
+        let mut guard = self.inner.write();
+        Plugin::shutdown(&mut *guard, ctx)
+    }
+
+    fn reset(&mut self, ctx: PluginContext<'_>) {
+        let mut guard = self.inner.write();
+        Plugin::reset(&mut *guard, ctx)
+    }
+
+    fn register_menu(&self, menu_bar: &mut MenuBar) {
+        if let Some(guard) = self.inner.try_read() {
+            Plugin::register_menu(&*guard, menu_bar)
+        }
+    }
+}
+
+impl<T: Plugin + Transition> Transition for __ArcPluginTransitionAdapter<T> {
+    fn schedule_transition(&self, job: TransitionJob) -> Result<TransitionTask> {
+        let guard = self.inner.read();
+        Transition::schedule_transition(&*guard, job)
+    }
+
+    fn property_schema(&self) -> PropertySchema {
+        let guard = self.inner.read();
+        Transition::property_schema(&*guard)
+    }
 }
 // Map supported feature string literals to feature idents for the helper above.
 #[doc(hidden)]
@@ -344,11 +523,14 @@ macro_rules! __map_feat_str {
     ("Renderer") => {
         Renderer
     };
+    ("Transition") => {
+        Transition
+    };
     ($other:literal) => {
         compile_error!(concat!(
             "Unknown plugin feature string in register_plugin!: ",
             $other,
-            ". Supported: \"Gui\", \"Renderer\""
+            ". Supported: \"Gui\", \"Renderer\", \"Transition\""
         ));
     };
 }
@@ -440,11 +622,23 @@ macro_rules! __private_export_feature {
             }
         }
     };
+    ($ty:ty, $name:expr, $shared:path, Transition) => {
+        const _: fn() = || {
+            fn assert_impl<T: $crate::plugin::Transition>() {}
+            let _ = assert_impl::<$ty>;
+        };
+        $crate::submit_raw! {
+            $crate::plugin::TransitionRegistration {
+                name: $name,
+                build: || Box::new($crate::plugin::__ArcPluginTransitionAdapter::<$ty>::new_with_shared($shared())),
+            }
+        }
+    };
     ($ty:ty, $name:expr, $shared:path, $other:ident) => {
         compile_error!(concat!(
             "Unknown plugin feature in export_plugin!: ",
             stringify!($other),
-            ". Supported: Gui, Renderer"
+            ". Supported: Gui, Renderer, Transition"
         ));
     };
 }
@@ -0,0 +1,497 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use bevy_ecs::{entity::Entity, resource::Resource, world::World};
+use parking_lot::Mutex;
+use tracing::{error, warn};
+
+use crate::{
+    history::{HistoryJournal, HistoryOp, UndoHistory},
+    prelude::*,
+    project::{ProjectCompression, ProjectDocument},
+    request::{AsyncJob, DynOrchestrator, Priority},
+};
+
+/// Default time between two autosave snapshots.
+pub const DEFAULT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(120);
+
+const SNAPSHOT_PREFIX: &str = "snapshot-";
+const SNAPSHOT_SUFFIX: &str = ".lunaris";
+const JOURNAL_PREFIX: &str = "journal-";
+const JOURNAL_SUFFIX: &str = ".lz4";
+const HISTORY_PREFIX: &str = "history-";
+const HISTORY_SUFFIX: &str = ".json";
+
+fn snapshot_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!(
+        "{SNAPSHOT_PREFIX}{generation:020}{SNAPSHOT_SUFFIX}"
+    ))
+}
+
+fn journal_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("{JOURNAL_PREFIX}{generation:020}{JOURNAL_SUFFIX}"))
+}
+
+fn history_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("{HISTORY_PREFIX}{generation:020}{HISTORY_SUFFIX}"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Snapshot,
+    Journal,
+    /// The [`UndoHistory`] at the time of the snapshot.
+    History,
+}
+
+/// Autosave files in `dir` with their kind and generation, oldest first.
+fn list(dir: &Path) -> Vec<(FileKind, u64, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let (kind, generation) = if let Some(rest) = name.strip_prefix(SNAPSHOT_PREFIX) {
+                (FileKind::Snapshot, rest.strip_suffix(SNAPSHOT_SUFFIX)?)
+            } else if let Some(rest) = name.strip_prefix(JOURNAL_PREFIX) {
+                (FileKind::Journal, rest.strip_suffix(JOURNAL_SUFFIX)?)
+            } else {
+                let rest = name.strip_prefix(HISTORY_PREFIX)?;
+                (FileKind::History, rest.strip_suffix(HISTORY_SUFFIX)?)
+            };
+            Some((kind, generation.parse().ok()?, path))
+        })
+        .collect();
+    files.sort_by_key(|(_, generation, _)| *generation);
+    files
+}
+
+/// Append-only log of [`HistoryOp`]s since the latest snapshot.
+///
+/// Each record is a little-endian `u32` length followed by an lz4 block
+/// holding the op as JSON. Records are written as soon as they happen, so
+/// at most the record being written during a crash is lost.
+pub struct Journal {
+    file: Mutex<Option<File>>,
+}
+
+impl Journal {
+    fn new() -> Self {
+        Self {
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Direct further records to a fresh file at `path`.
+    fn rotate(&self, path: &Path) -> Result {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| LunarisError::FileWriteError {
+                path: path.to_path_buf(),
+                reason: e.to_string(),
+            })?;
+        *self.file.lock() = Some(file);
+        Ok(())
+    }
+
+    fn close(&self) {
+        *self.file.lock() = None;
+    }
+
+    fn encode(op: &HistoryOp) -> Result<Vec<u8>> {
+        let json = serde_json::to_vec(op).map_err(|e| LunarisError::FailedCompress {
+            what: e.to_string(),
+        })?;
+        let block = lz4_flex::block::compress_prepend_size(&json);
+        let len = u32::try_from(block.len()).map_err(|_| LunarisError::FailedCompress {
+            what: format!("journal record of {} bytes", block.len()),
+        })?;
+        let mut frame = Vec::with_capacity(4 + block.len());
+        frame.extend_from_slice(&len.to_le_bytes());
+        frame.extend_from_slice(&block);
+        Ok(frame)
+    }
+
+    /// Decode the records of a journal file. The second value is `true` if
+    /// the file ended in a partial or corrupt record.
+    fn read(bytes: &[u8]) -> (Vec<HistoryOp>, bool) {
+        let mut ops = Vec::new();
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let Some((len, tail)) = rest.split_first_chunk::<4>() else {
+                return (ops, true);
+            };
+            let len = u32::from_le_bytes(*len) as usize;
+            let Some((block, tail)) = tail.split_at_checked(len) else {
+                return (ops, true);
+            };
+            let op = lz4_flex::block::decompress_size_prepended(block)
+                .ok()
+                .and_then(|json| serde_json::from_slice(&json).ok());
+            match op {
+                Some(op) => ops.push(op),
+                None => return (ops, true),
+            }
+            rest = tail;
+        }
+        (ops, false)
+    }
+}
+
+impl HistoryJournal for Journal {
+    fn record(&self, op: &HistoryOp) {
+        let mut file = self.file.lock();
+        let Some(file) = file.as_mut() else {
+            return;
+        };
+        match Self::encode(op) {
+            Ok(frame) => {
+                if let Err(e) = file.write_all(&frame) {
+                    warn!("Failed to write history journal: {e}");
+                }
+            }
+            Err(e) => warn!("Failed to encode history op: {e}"),
+        }
+    }
+}
+
+/// Periodic snapshots of the project plus a journal of the edits made
+/// since, written to one directory.
+///
+/// Call [`recover`] before [`Autosave::start`]: starting discards whatever
+/// an earlier session left behind.
+#[derive(Resource)]
+pub struct Autosave {
+    dir: PathBuf,
+    pub interval: Duration,
+    pub compression: ProjectCompression,
+    generation: u64,
+    last: Option<Instant>,
+    journal: Arc<Journal>,
+    in_flight: Arc<AtomicBool>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl Autosave {
+    /// Insert the autosave resource and attach its journal to the world's
+    /// [`UndoHistory`], creating the history if needed. Existing autosave
+    /// files in `dir` are deleted.
+    pub fn start(world: &mut World, dir: impl Into<PathBuf>, interval: Duration) -> Result {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| LunarisError::FileWriteError {
+            path: dir.clone(),
+            reason: e.to_string(),
+        })?;
+        discard(&dir)?;
+        let journal = Arc::new(Journal::new());
+        if !world.contains_resource::<UndoHistory>() {
+            world.init_resource::<UndoHistory>();
+        }
+        world
+            .resource_mut::<UndoHistory>()
+            .set_journal(Some(journal.clone()));
+        world.insert_resource(Self {
+            dir,
+            interval,
+            compression: ProjectCompression::default(),
+            generation: 0,
+            last: None,
+            journal,
+            in_flight: Arc::new(AtomicBool::new(false)),
+            last_error: Arc::new(Mutex::new(None)),
+        });
+        Ok(())
+    }
+
+    /// Detach from the history and delete every autosave file. Call on a
+    /// clean exit so the next start has nothing to recover.
+    pub fn stop(world: &mut World) -> Result {
+        let Some(autosave) = world.remove_resource::<Self>() else {
+            return Ok(());
+        };
+        if let Some(mut history) = world.get_resource_mut::<UndoHistory>() {
+            history.set_journal(None);
+        }
+        autosave.journal.close();
+        discard(&autosave.dir)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Error of the most recent failed snapshot, cleared by the next
+    /// successful one.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().clone()
+    }
+
+    /// Snapshot the project if `interval` has passed since the last one.
+    /// Call once per frame.
+    pub fn tick(world: &mut World, orch: &dyn DynOrchestrator) -> Result {
+        let Some(autosave) = world.get_resource::<Self>() else {
+            return Err(LunarisError::Uninit {
+                resource: "Autosave".to_string(),
+            });
+        };
+        if autosave
+            .last
+            .is_some_and(|last| last.elapsed() < autosave.interval)
+        {
+            return Ok(());
+        }
+        Self::snapshot(world, orch)
+    }
+
+    /// Snapshot the project now, unless a snapshot is still being written.
+    ///
+    /// The world is captured and the journal rotated right away; encoding
+    /// and writing run as a [`Priority::Background`] [`AsyncJob`]. Older
+    /// files are deleted once the new snapshot is on disk.
+    pub fn snapshot(world: &mut World, orch: &dyn DynOrchestrator) -> Result {
+        let Some(autosave) = world.get_resource::<Self>() else {
+            return Err(LunarisError::Uninit {
+                resource: "Autosave".to_string(),
+            });
+        };
+        if autosave.in_flight.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+        let generation = autosave.generation + 1;
+        let document = ProjectDocument::capture(world);
+        let history = match world
+            .get_resource::<UndoHistory>()
+            .map(UndoHistory::to_bytes)
+            .transpose()
+        {
+            Ok(history) => history,
+            Err(e) => {
+                world
+                    .resource::<Self>()
+                    .in_flight
+                    .store(false, Ordering::Release);
+                return Err(e);
+            }
+        };
+
+        let mut autosave = world.resource_mut::<Self>();
+        autosave.generation = generation;
+        autosave.last = Some(Instant::now());
+        if let Err(e) = autosave
+            .journal
+            .rotate(&journal_path(&autosave.dir, generation))
+        {
+            autosave.in_flight.store(false, Ordering::Release);
+            return Err(e);
+        }
+
+        let dir = autosave.dir.clone();
+        let compression = autosave.compression;
+        let in_flight = autosave.in_flight.clone();
+        let last_error = autosave.last_error.clone();
+        let job = AsyncJob::new(move || async move {
+            let result = write_snapshot(&dir, generation, &document, history, compression);
+            match result {
+                Ok(()) => *last_error.lock() = None,
+                Err(e) => {
+                    error!("Autosave failed: {e}");
+                    *last_error.lock() = Some(e.to_string());
+                }
+            }
+            in_flight.store(false, Ordering::Release);
+        })
+        .with_priority(Priority::Background);
+        let priority = job.priority;
+        let result = orch.submit_async_boxed(Box::pin(job.exec()), priority);
+        if result.is_err() {
+            world
+                .resource::<Self>()
+                .in_flight
+                .store(false, Ordering::Release);
+        }
+        result
+    }
+}
+
+fn write_snapshot(
+    dir: &Path,
+    generation: u64,
+    document: &ProjectDocument,
+    history: Option<Vec<u8>>,
+    compression: ProjectCompression,
+) -> Result {
+    // The history goes first so that a snapshot on disk always has its
+    // history next to it.
+    if let Some(history) = history {
+        write_atomic(&history_path(dir, generation), &history)?;
+    }
+    write_atomic(
+        &snapshot_path(dir, generation),
+        &document.to_bytes(compression)?,
+    )?;
+
+    for (_, older, path) in list(dir) {
+        if older < generation
+            && let Err(e) = std::fs::remove_file(&path)
+        {
+            warn!("Failed to remove old autosave file {path:?}: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Write `bytes` to a temporary file next to `path` and move it into place.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result {
+    let write_error = |e: std::io::Error| LunarisError::FileWriteError {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    };
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::fs::write(&temp, bytes).map_err(write_error)?;
+    std::fs::rename(&temp, path).map_err(write_error)
+}
+
+/// Whether `dir` holds a snapshot that [`recover`] could restore.
+pub fn has_recovery(dir: impl AsRef<Path>) -> bool {
+    list(dir.as_ref())
+        .iter()
+        .any(|(kind, _, _)| *kind == FileKind::Snapshot)
+}
+
+/// Delete every autosave file in `dir`.
+pub fn discard(dir: impl AsRef<Path>) -> Result {
+    for (_, _, path) in list(dir.as_ref()) {
+        std::fs::remove_file(&path).map_err(|e| LunarisError::FileWriteError {
+            path: path.clone(),
+            reason: e.to_string(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Outcome of [`recover`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Generation of the snapshot that was loaded.
+    pub generation: u64,
+    /// Number of journaled operations replayed on top of it.
+    pub replayed: usize,
+    /// Whether a journal ended in a partial record, i.e. the crash happened
+    /// while it was being written.
+    pub truncated: bool,
+}
+
+/// Restore the newest snapshot in `dir` into `world`, along with the undo
+/// history at that time, and replay the journaled edits made after it.
+/// Returns `None` if there is nothing to recover.
+///
+/// Token types of plugins must be [registered](UndoHistory::register) in
+/// the world's history beforehand so their steps can be remapped and
+/// replayed.
+///
+/// Replay stops at the first operation that fails; the world then reflects
+/// every operation before it.
+pub fn recover(world: &mut World, dir: impl AsRef<Path>) -> Result<Option<RecoveryReport>> {
+    let files = list(dir.as_ref());
+    let Some((_, generation, path)) = files
+        .iter()
+        .rev()
+        .find(|(kind, _, _)| *kind == FileKind::Snapshot)
+    else {
+        return Ok(None);
+    };
+    let bytes = std::fs::read(path).map_err(|e| LunarisError::FileReadError {
+        path: path.clone(),
+        reason: e.to_string(),
+    })?;
+    let mut map: HashMap<u64, Entity> = ProjectDocument::from_bytes(&bytes)?.restore(world)?;
+
+    let mut history = world.remove_resource::<UndoHistory>().unwrap_or_default();
+    let journal = history.journal().cloned();
+    history.set_journal(None);
+    history.clear();
+    let saved = files
+        .iter()
+        .find(|(kind, g, _)| *kind == FileKind::History && g == generation);
+    if let Some((_, _, path)) = saved {
+        let loaded = std::fs::read(path)
+            .map_err(|e| LunarisError::FileReadError {
+                path: path.clone(),
+                reason: e.to_string(),
+            })
+            .and_then(|bytes| history.load_bytes(&bytes))
+            .and_then(|()| {
+                history.remap_stacks(&mut |entity: Entity| {
+                    *map.entry(entity.to_bits())
+                        .or_insert_with(|| world.spawn_empty().id())
+                })
+            });
+        if let Err(e) = loaded {
+            history.set_journal(journal);
+            world.insert_resource(history);
+            return Err(e);
+        }
+    }
+
+    let mut report = RecoveryReport {
+        generation: *generation,
+        replayed: 0,
+        truncated: false,
+    };
+    let mut result = Ok(());
+    'journals: for (_, _, path) in files
+        .iter()
+        .filter(|(kind, g, _)| *kind == FileKind::Journal && g >= generation)
+    {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                result = Err(LunarisError::FileReadError {
+                    path: path.clone(),
+                    reason: e.to_string(),
+                });
+                break;
+            }
+        };
+        let (ops, truncated) = Journal::read(&bytes);
+        report.truncated |= truncated;
+        for mut op in ops {
+            if let HistoryOp::Commit(transaction) = &mut op {
+                let mut respawn = |entity: Entity| {
+                    *map.entry(entity.to_bits())
+                        .or_insert_with(|| world.spawn_empty().id())
+                };
+                if let Err(e) = history.remap_entities(transaction, &mut respawn) {
+                    result = Err(e);
+                    break 'journals;
+                }
+            }
+            if let Err(e) = history.replay_op(world, op) {
+                result = Err(e);
+                break 'journals;
+            }
+            report.replayed += 1;
+        }
+        if truncated {
+            break;
+        }
+    }
+
+    history.set_journal(journal);
+    world.insert_resource(history);
+    result.map(|()| Some(report))
+}
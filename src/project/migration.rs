@@ -0,0 +1,181 @@
+use std::{
+    collections::{BTreeMap, btree_map::Entry},
+    sync::LazyLock,
+};
+
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{prelude::*, project::PROJECT_VERSION};
+
+/// Scope of the core document format, versioned by [`PROJECT_VERSION`].
+pub const CORE_SCOPE: &str = "core";
+
+/// One upgrade step from version `from` to `from + 1` of `scope`.
+///
+/// [`CORE_SCOPE`] steps receive the whole document. Any other scope is a
+/// plugin name: its steps run once for every saved [`Properties`] map,
+/// receiving the JSON object of that map, so a plugin only ever rewrites
+/// its own keys. Plugin versions start at `0` and are bumped by
+/// registering the next step.
+///
+/// Register with [`submit_raw!`](crate::submit_raw), or add it to a
+/// [`MigrationRegistry`] by hand.
+///
+/// [`Properties`]: crate::timeline::elements::Properties
+#[derive(Clone, Copy)]
+pub struct MigrationRegistration {
+    pub scope: &'static str,
+    pub from: u32,
+    pub description: &'static str,
+    pub migrate: fn(&mut Value) -> Result,
+}
+
+inventory::collect!(MigrationRegistration);
+
+/// Upgrade steps indexed by scope and starting version.
+#[derive(Clone, Default)]
+pub struct MigrationRegistry {
+    scopes: BTreeMap<&'static str, BTreeMap<u32, MigrationRegistration>>,
+}
+
+static COLLECTED: LazyLock<MigrationRegistry> = LazyLock::new(|| {
+    let mut registry = MigrationRegistry::new();
+    for migration in inventory::iter::<MigrationRegistration> {
+        if let Err(e) = registry.register(*migration) {
+            warn!("Ignoring migration {:?}: {e}", migration.description);
+        }
+    }
+    registry
+});
+
+impl MigrationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every migration submitted through `inventory`.
+    pub fn collected() -> &'static Self {
+        &COLLECTED
+    }
+
+    /// Add a step. Each scope accepts one step per starting version.
+    pub fn register(&mut self, migration: MigrationRegistration) -> Result {
+        match self
+            .scopes
+            .entry(migration.scope)
+            .or_default()
+            .entry(migration.from)
+        {
+            Entry::Occupied(_) => Err(LunarisError::AlreadyExists {
+                item: format!(
+                    "{} migration from version {}",
+                    migration.scope, migration.from
+                ),
+            }),
+            Entry::Vacant(slot) => {
+                slot.insert(migration);
+                Ok(())
+            }
+        }
+    }
+
+    /// Version a plugin scope is at after all its registered steps.
+    pub fn current_version(&self, scope: &str) -> u32 {
+        self.scopes
+            .get(scope)
+            .and_then(|steps| steps.keys().next_back())
+            .map_or(0, |from| from + 1)
+    }
+
+    /// Current version of every plugin scope, as written into new files.
+    pub fn plugin_versions(&self) -> BTreeMap<String, u32> {
+        self.scopes
+            .keys()
+            .filter(|scope| **scope != CORE_SCOPE)
+            .map(|scope| (scope.to_string(), self.current_version(scope)))
+            .collect()
+    }
+
+    /// Bring a document of core format `version` up to date, then every
+    /// plugin scope it records.
+    pub fn migrate(&self, version: u32, document: &mut Value) -> Result {
+        if version > PROJECT_VERSION {
+            return Err(LunarisError::FailedSaveLoad {
+                reason: format!(
+                    "project format {version} is newer than supported format {PROJECT_VERSION}"
+                ),
+            });
+        }
+        for from in version..PROJECT_VERSION {
+            let step = self.step(CORE_SCOPE, from)?;
+            run(&step, document)?;
+        }
+
+        let recorded = document
+            .get("plugin_versions")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let mut versions = BTreeMap::new();
+        for scope in self.scopes.keys().filter(|s| **s != CORE_SCOPE) {
+            let current = self.current_version(scope);
+            let saved = recorded
+                .get(*scope)
+                .and_then(Value::as_u64)
+                .map_or(0, |v| v as u32);
+            if saved > current {
+                warn!("Project data of {scope} is version {saved}, newer than {current}");
+                versions.insert(scope.to_string(), saved);
+                continue;
+            }
+            for from in saved..current {
+                let step = self.step(scope, from)?;
+                for properties in properties_mut(document) {
+                    run(&step, properties)?;
+                }
+            }
+            versions.insert(scope.to_string(), current);
+        }
+
+        if let Some(object) = document.as_object_mut() {
+            let mut merged: serde_json::Map<String, Value> = recorded;
+            merged.extend(versions.into_iter().map(|(k, v)| (k, Value::from(v))));
+            object.insert("plugin_versions".to_string(), Value::Object(merged));
+        }
+        Ok(())
+    }
+
+    fn step(&self, scope: &str, from: u32) -> Result<MigrationRegistration> {
+        self.scopes
+            .get(scope)
+            .and_then(|steps| steps.get(&from))
+            .copied()
+            .ok_or_else(|| LunarisError::FailedSaveMigration {
+                reason: format!("no {scope} migration from version {from} to {}", from + 1),
+            })
+    }
+}
+
+fn run(step: &MigrationRegistration, value: &mut Value) -> Result {
+    (step.migrate)(value).map_err(|e| LunarisError::FailedSaveMigration {
+        reason: format!(
+            "{} step {} -> {} ({}) failed: {e}",
+            step.scope,
+            step.from,
+            step.from + 1,
+            step.description
+        ),
+    })
+}
+
+/// Every `properties` object of the document's entity records.
+fn properties_mut(document: &mut Value) -> impl Iterator<Item = &mut Value> {
+    document
+        .get_mut("entities")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(|record| record.get_mut("properties"))
+        .filter(|properties| properties.is_object())
+}
@@ -0,0 +1,460 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use bevy_ecs::{
+    entity::Entity,
+    resource::Resource,
+    world::{EntityRef, World},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{
+    history::UndoHistory,
+    prelude::*,
+    project::migration::MigrationRegistry,
+    timeline::{
+        CompoundClip, InSequence, Marker, Playhead, PlayheadTarget, Region, Sequence, Timebase,
+        TimelineSpan, Track, Transition, TransitionKind,
+        elements::{BindTo, Properties, Property, SourceOffset, TimeRemap, TimelineElement},
+        validate::validate,
+    },
+    util::graph::find_cycle,
+};
+
+pub mod autosave;
+pub mod migration;
+
+/// First bytes of every project file.
+pub const PROJECT_MAGIC: [u8; 8] = *b"LUNARIS\0";
+/// Format version written by this build.
+pub const PROJECT_VERSION: u32 = 1;
+
+/// Magic, little-endian version, compression flag.
+const HEADER_LEN: usize = PROJECT_MAGIC.len() + 4 + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectCompression {
+    None,
+    /// zstd at the given level.
+    Zstd(u8),
+}
+
+impl Default for ProjectCompression {
+    fn default() -> Self {
+        Self::Zstd(3)
+    }
+}
+
+impl ProjectCompression {
+    fn flag(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Zstd(_) => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElementRecord {
+    pub track_num: u64,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// [`PlayheadTarget`] with the sequence stored as its record id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TargetRecord {
+    Root,
+    Sequence(u64),
+    Media(PathBuf),
+}
+
+/// [`Transition`] with its elements stored as record ids.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransitionRecord {
+    pub outgoing: u64,
+    pub incoming: u64,
+    pub start: u64,
+    pub end: u64,
+    pub kind: TransitionKind,
+}
+
+/// Saved components of one entity. `id` is the entity's bits at save time
+/// and only identifies the record inside its document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityRecord {
+    pub id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<ElementRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_offset: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_remap: Option<TimeRemap>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<Properties>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind_to: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<Track>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub playhead: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub playhead_target: Option<TargetRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Marker>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<Region>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<Sequence>,
+    /// Record id of the [`Sequence`] holding this track or element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_sequence: Option<u64>,
+    /// Record id of the [`Sequence`] this compound clip plays.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compound: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transition: Option<TransitionRecord>,
+}
+
+/// Timeline state of a [`World`] in its on-disk shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDocument {
+    pub tps: u64,
+    /// Sorted by `id`, so saving the same world twice gives the same bytes.
+    pub entities: Vec<EntityRecord>,
+    /// Format version of each plugin's property keys. See
+    /// [`MigrationRegistration`](migration::MigrationRegistration).
+    #[serde(default)]
+    pub plugin_versions: BTreeMap<String, u32>,
+}
+
+/// Plugin versions recorded by the last project restored into the world.
+///
+/// Saving keeps the higher of these and the loaded plugins' versions, so a
+/// host that lacks a plugin or has an older one does not make its
+/// migrations run again on the next load.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LoadedPluginVersions(pub BTreeMap<String, u32>);
+
+fn is_saved(entity: &EntityRef) -> bool {
+    entity.contains::<TimelineElement>()
+        || entity.contains::<SourceOffset>()
+        || entity.contains::<TimeRemap>()
+        || entity.contains::<Properties>()
+        || entity.contains::<BindTo>()
+        || entity.contains::<Track>()
+        || entity.contains::<Playhead>()
+        || entity.contains::<PlayheadTarget>()
+        || entity.contains::<Marker>()
+        || entity.contains::<Region>()
+        || entity.contains::<Sequence>()
+        || entity.contains::<InSequence>()
+        || entity.contains::<CompoundClip>()
+        || entity.contains::<Transition>()
+}
+
+fn load_error(reason: impl Into<String>) -> LunarisError {
+    LunarisError::FailedSaveLoad {
+        reason: reason.into(),
+    }
+}
+
+impl ProjectDocument {
+    /// Snapshot the timeline state of `world`. Plugin versions are those of
+    /// the loaded plugins, raised to any in [`LoadedPluginVersions`].
+    pub fn capture(world: &World) -> Self {
+        let mut entities: Vec<EntityRecord> = world
+            .iter_entities()
+            .filter(is_saved)
+            .map(|entity| EntityRecord {
+                id: entity.id().to_bits(),
+                element: entity.get::<TimelineElement>().map(|e| ElementRecord {
+                    track_num: e.track_num,
+                    start: e.position.start(),
+                    end: e.position.end(),
+                }),
+                source_offset: entity.get::<SourceOffset>().map(|s| s.ticks),
+                time_remap: entity.get::<TimeRemap>().cloned(),
+                properties: entity.get::<Properties>().cloned(),
+                bind_to: entity.get::<BindTo>().map(|b| b.id.to_bits()),
+                track: entity.get::<Track>().cloned(),
+                playhead: entity.get::<Playhead>().map(|p| p.current),
+                playhead_target: entity.get::<PlayheadTarget>().map(|t| match t {
+                    PlayheadTarget::Root => TargetRecord::Root,
+                    PlayheadTarget::Sequence(s) => TargetRecord::Sequence(s.to_bits()),
+                    PlayheadTarget::Media(path) => TargetRecord::Media(path.clone()),
+                }),
+                marker: entity.get::<Marker>().cloned(),
+                region: entity.get::<Region>().cloned(),
+                sequence: entity.get::<Sequence>().cloned(),
+                in_sequence: entity.get::<InSequence>().map(|s| s.sequence.to_bits()),
+                compound: entity.get::<CompoundClip>().map(|c| c.sequence.to_bits()),
+                transition: entity.get::<Transition>().map(|t| TransitionRecord {
+                    outgoing: t.outgoing.to_bits(),
+                    incoming: t.incoming.to_bits(),
+                    start: t.span.start(),
+                    end: t.span.end(),
+                    kind: t.kind.clone(),
+                }),
+            })
+            .collect();
+        entities.sort_by_key(|record| record.id);
+        let mut plugin_versions = MigrationRegistry::collected().plugin_versions();
+        if let Some(loaded) = world.get_resource::<LoadedPluginVersions>() {
+            for (scope, version) in &loaded.0 {
+                let current = plugin_versions.entry(scope.clone()).or_default();
+                *current = (*current).max(*version);
+            }
+        }
+        Self {
+            tps: Timebase::of(world).tps(),
+            entities,
+            plugin_versions,
+        }
+    }
+
+    /// Replace the timeline state of `world` with this document.
+    ///
+    /// Every entity is spawned anew; references between saved entities are
+    /// remapped. Binds, entity properties and transitions referring to an
+    /// entity that was not saved point at [`Entity::PLACEHOLDER`], so
+    /// [`validate`] reports them as dangling; other such references are
+    /// dropped. Returns the new entity for each record id. Nothing is
+    /// changed if the document is invalid; issues [`validate`] finds in
+    /// a valid one are logged, not fixed.
+    pub fn restore(self, world: &mut World) -> Result<HashMap<u64, Entity>> {
+        let timebase = Timebase::new(self.tps)?;
+        let mut spans = Vec::with_capacity(self.entities.len());
+        let mut track_indices = HashMap::new();
+        let mut nesting: HashMap<u64, Vec<u64>> = HashMap::new();
+        let mut binds: HashMap<u64, Vec<u64>> = HashMap::new();
+        for record in &self.entities {
+            spans.push((
+                match record.element {
+                    Some(e) => Some(TimelineSpan::new(e.start, e.end)?),
+                    None => None,
+                },
+                match &record.transition {
+                    Some(t) => Some(TimelineSpan::new(t.start, t.end)?),
+                    None => None,
+                },
+            ));
+            if let Some(track) = &record.track
+                && track_indices
+                    .insert((record.in_sequence, track.index()), record.id)
+                    .is_some()
+            {
+                return Err(load_error(format!(
+                    "two tracks share index {}",
+                    track.index()
+                )));
+            }
+            if let (Some(parent), Some(child)) = (record.in_sequence, record.compound) {
+                nesting.entry(parent).or_default().push(child);
+            }
+            if let Some(parent) = record.bind_to {
+                binds.insert(record.id, vec![parent]);
+            }
+        }
+        if let Some(cycle) = find_cycle(&nesting).or_else(|| find_cycle(&binds)) {
+            return Err(LunarisError::CycleDetected {
+                path: cycle.into_iter().map(|id| id.to_string()).collect(),
+            });
+        }
+
+        let stale: Vec<Entity> = world
+            .iter_entities()
+            .filter(is_saved)
+            .map(|entity| entity.id())
+            .collect();
+        for entity in stale {
+            world.despawn(entity);
+        }
+
+        let map: HashMap<u64, Entity> = self
+            .entities
+            .iter()
+            .map(|record| (record.id, world.spawn_empty().id()))
+            .collect();
+        // References to entities that were not saved must not resolve to
+        // whatever entity now has the same id.
+        let remap = |bits: u64| -> Option<Entity> {
+            let entity = map.get(&bits).copied();
+            if entity.is_none() {
+                warn!("Project references entity {bits} that was not saved");
+            }
+            entity
+        };
+        let dangling = |bits: u64| remap(bits).unwrap_or(Entity::PLACEHOLDER);
+
+        for (record, (span, transition_span)) in self.entities.into_iter().zip(spans) {
+            let mut entity = world.entity_mut(map[&record.id]);
+            if let (Some(element), Some(position)) = (record.element, span) {
+                entity.insert(TimelineElement {
+                    track_num: element.track_num,
+                    position,
+                });
+            }
+            if let Some(ticks) = record.source_offset {
+                entity.insert(SourceOffset { ticks });
+            }
+            if let Some(remap) = record.time_remap {
+                entity.insert(remap);
+            }
+            if let Some(mut properties) = record.properties {
+                for value in properties.properties.values_mut() {
+                    if let Property::Entity(target) = value {
+                        *target = dangling(target.to_bits());
+                    }
+                }
+                entity.insert(properties);
+            }
+            if let Some(bits) = record.bind_to {
+                entity.insert(BindTo { id: dangling(bits) });
+            }
+            if let Some(track) = record.track {
+                entity.insert(track);
+            }
+            if let Some(current) = record.playhead {
+                entity.insert(Playhead { current });
+            }
+            match record.playhead_target {
+                Some(TargetRecord::Root) => {
+                    entity.insert(PlayheadTarget::Root);
+                }
+                Some(TargetRecord::Sequence(bits)) => {
+                    if let Some(sequence) = remap(bits) {
+                        entity.insert(PlayheadTarget::Sequence(sequence));
+                    }
+                }
+                Some(TargetRecord::Media(path)) => {
+                    entity.insert(PlayheadTarget::Media(path));
+                }
+                None => {}
+            }
+            if let Some(marker) = record.marker {
+                entity.insert(marker);
+            }
+            if let Some(region) = record.region {
+                entity.insert(region);
+            }
+            if let Some(sequence) = record.sequence {
+                entity.insert(sequence);
+            }
+            if let Some(sequence) = record.in_sequence.and_then(remap) {
+                entity.insert(InSequence { sequence });
+            }
+            if let Some(sequence) = record.compound.and_then(remap) {
+                entity.insert(CompoundClip { sequence });
+            }
+            if let (Some(t), Some(span)) = (record.transition, transition_span) {
+                let (outgoing, incoming) = (dangling(t.outgoing), dangling(t.incoming));
+                entity.insert(Transition::new(outgoing, incoming, span, t.kind));
+            }
+        }
+        world.insert_resource(timebase);
+        world.insert_resource(LoadedPluginVersions(self.plugin_versions));
+        for issue in validate(world) {
+            warn!("Loaded project: {issue}");
+        }
+        Ok(map)
+    }
+
+    /// Encode with the project header.
+    pub fn to_bytes(&self, compression: ProjectCompression) -> Result<Vec<u8>> {
+        let json = serde_json::to_vec(self).map_err(|e| LunarisError::InvalidArgument {
+            name: "project".to_string(),
+            reason: Some(e.to_string()),
+        })?;
+        let payload = match compression {
+            ProjectCompression::None => json,
+            ProjectCompression::Zstd(level) => {
+                zstd::encode_all(&*json, level as i32).map_err(|e| {
+                    LunarisError::FailedCompress {
+                        what: e.to_string(),
+                    }
+                })?
+            }
+        };
+        let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+        bytes.extend_from_slice(&PROJECT_MAGIC);
+        bytes.extend_from_slice(&PROJECT_VERSION.to_le_bytes());
+        bytes.push(compression.flag());
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    /// Decode a file written by [`to_bytes`](Self::to_bytes), upgrading it
+    /// with every registered migration.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_bytes_with(bytes, MigrationRegistry::collected())
+    }
+
+    /// Like [`from_bytes`](Self::from_bytes) with an explicit registry.
+    pub fn from_bytes_with(bytes: &[u8], migrations: &MigrationRegistry) -> Result<Self> {
+        let (version, mut value) = read_payload(bytes)?;
+        migrations.migrate(version, &mut value)?;
+        serde_json::from_value(value).map_err(|e| load_error(e.to_string()))
+    }
+}
+
+/// Split off the header and return the format version and the decoded
+/// JSON payload.
+fn read_payload(bytes: &[u8]) -> Result<(u32, Value)> {
+    if bytes.len() < HEADER_LEN || bytes[..PROJECT_MAGIC.len()] != PROJECT_MAGIC {
+        return Err(load_error("not a lunaris project file"));
+    }
+    let (header, payload) = bytes.split_at(HEADER_LEN);
+    let [.., v0, v1, v2, v3, flag] = *header else {
+        unreachable!("header is HEADER_LEN bytes")
+    };
+    let version = u32::from_le_bytes([v0, v1, v2, v3]);
+    let json = match flag {
+        0 => payload.to_vec(),
+        1 => zstd::decode_all(payload).map_err(|e| LunarisError::FailedDecompress {
+            what: e.to_string(),
+        })?,
+        flag => return Err(load_error(format!("unknown compression flag {flag}"))),
+    };
+    let value = serde_json::from_slice(&json).map_err(|e| load_error(e.to_string()))?;
+    Ok((version, value))
+}
+
+/// Write the timeline state of `world` to `path`.
+///
+/// The file is written next to `path` first and then renamed over it, so a
+/// crash mid-save never leaves a truncated project behind.
+pub fn save(world: &World, path: impl AsRef<Path>, compression: ProjectCompression) -> Result {
+    let path = path.as_ref();
+    let bytes = ProjectDocument::capture(world).to_bytes(compression)?;
+    let write_error = |e: std::io::Error| LunarisError::FileWriteError {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    };
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::fs::write(&temp, bytes).map_err(write_error)?;
+    std::fs::rename(&temp, path).map_err(write_error)
+}
+
+/// Replace the timeline state of `world` with the project at `path`.
+/// Returns the new entity for each saved entity id.
+///
+/// The [`UndoHistory`] is cleared, since its steps refer to the replaced
+/// entities.
+pub fn load(world: &mut World, path: impl AsRef<Path>) -> Result<HashMap<u64, Entity>> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| LunarisError::FileReadError {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?;
+    let map = ProjectDocument::from_bytes(&bytes)?.restore(world)?;
+    if let Some(mut history) = world.get_resource_mut::<UndoHistory>() {
+        history.clear();
+    }
+    Ok(map)
+}
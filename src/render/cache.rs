@@ -130,11 +130,11 @@ impl TieredCache {
                     self.promote(entity)?;
                     changed = true;
                 }
-            } else if self.med.len() < med_cap {
-                if let Some((entity, _)) = low_snapshot.into_iter().min_by(|a, b| a.1.cmp(&b.1)) {
-                    self.promote(entity)?;
-                    changed = true;
-                }
+            } else if self.med.len() < med_cap
+                && let Some((entity, _)) = low_snapshot.into_iter().min_by(|a, b| a.1.cmp(&b.1))
+            {
+                self.promote(entity)?;
+                changed = true;
             }
 
             if !changed {
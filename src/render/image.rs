@@ -185,6 +185,72 @@ impl RawImage {
         Self::from_bytes(self.format, self.width, self.height, data)
     }
 
+    /// Crossfade from `self` to `other`. `progress` runs from `0.0`, all
+    /// `self`, to `1.0`, all `other`. Blends the stored values, so sRGB
+    /// images fade in gamma space.
+    pub fn dissolve(&self, other: &Self, progress: f64) -> Result<Self> {
+        self.ensure_geometry(other)?;
+        let t = progress.clamp(0.0, 1.0);
+        let data: Vec<u8> = self
+            .as_bytes()
+            .iter()
+            .zip(other.as_bytes())
+            .map(|(a, b)| lerp(*a, *b, t))
+            .collect();
+        Self::from_bytes(self.format, self.width, self.height, data)
+    }
+
+    /// Fade `self` out to `color` over the first half of `progress`, then
+    /// fade `other` in from it. `color` is RGBA; gray images use its luma.
+    pub fn dip_to_color(&self, other: &Self, progress: f64, color: [u8; 4]) -> Result<Self> {
+        self.ensure_geometry(other)?;
+        let t = progress.clamp(0.0, 1.0);
+        let fill = match self.format {
+            PixelFormat::Gray8 => vec![luma(color)],
+            PixelFormat::Rgba8Unorm | PixelFormat::Rgba8UnormSrgb => color.to_vec(),
+        };
+        let (from, amount) = if t < 0.5 {
+            (self, t * 2.0)
+        } else {
+            (other, (1.0 - t) * 2.0)
+        };
+        let data: Vec<u8> = from
+            .as_bytes()
+            .iter()
+            .zip(fill.iter().cycle())
+            .map(|(a, c)| lerp(*a, *c, amount))
+            .collect();
+        Self::from_bytes(self.format, self.width, self.height, data)
+    }
+
+    /// Hard edge sweeping across the frame, replacing `self` with `other`.
+    ///
+    /// `angle` is the direction the edge travels, in degrees: `0.0` moves
+    /// left to right, `90.0` top to bottom.
+    pub fn wipe(&self, other: &Self, progress: f64, angle: f64) -> Result<Self> {
+        self.ensure_geometry(other)?;
+        let t = progress.clamp(0.0, 1.0);
+        let (sin, cos) = angle.to_radians().sin_cos();
+        // Projections of the frame corners span this far either side of the
+        // centre, so `t` maps onto the whole frame whatever the angle.
+        let reach = (cos.abs() + sin.abs()) / 2.0;
+        let bpp = self.bytes_per_pixel();
+        let (w, h) = (self.width as f64, self.height as f64);
+        let mut data = self.as_bytes().to_vec();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let u = (x as f64 + 0.5) / w - 0.5;
+                let v = (y as f64 + 0.5) / h - 0.5;
+                let along = (u * cos + v * sin + reach) / (2.0 * reach);
+                if along < t {
+                    let at = (y as usize * self.width as usize + x as usize) * bpp;
+                    data[at..at + bpp].copy_from_slice(&other.as_bytes()[at..at + bpp]);
+                }
+            }
+        }
+        Self::from_bytes(self.format, self.width, self.height, data)
+    }
+
     /// Downsample by 2x using a simple box filter. For odd dimensions the
     /// remaining row/column is averaged with the available neighbours.
     pub fn size_down(&self) -> Self {
@@ -343,6 +409,16 @@ impl CompressedImage {
     }
 }
 
+#[inline]
+fn lerp(a: u8, b: u8, t: f64) -> u8 {
+    (a as f64 + (b as f64 - a as f64) * t).round() as u8
+}
+
+/// Rec. 709 luma of an RGBA color.
+fn luma(color: [u8; 4]) -> u8 {
+    (0.2126 * color[0] as f64 + 0.7152 * color[1] as f64 + 0.0722 * color[2] as f64).round() as u8
+}
+
 fn read_texture_into_raw(texture: &Texture) -> RawImage {
     assert_eq!(
         texture.dimension(),
@@ -370,7 +446,7 @@ fn read_texture_into_raw(texture: &Texture) -> RawImage {
     let padded_bytes_per_row = if bytes_per_row == 0 {
         alignment
     } else {
-        ((bytes_per_row + alignment - 1) / alignment) * alignment
+        bytes_per_row.div_ceil(alignment) * alignment
     };
     let padded_bytes_per_row_u32 =
         u32::try_from(padded_bytes_per_row).expect("row stride exceeds u32::MAX");
@@ -143,13 +143,14 @@ where
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum Priority {
     /// Immediately launch task. Orchestrator pops this first as soon as possible.
     Immediate,
     /// Blocks the generation of current frame until this task is complete
     VideoFrame,
     /// Normal priority. Will execute task whenever other tasks are open.
+    #[default]
     Normal,
     /// Deferred execution. Lowest priority.
     Deferred,
@@ -157,12 +158,6 @@ pub enum Priority {
     Background,
 }
 
-impl Default for Priority {
-    fn default() -> Self {
-        Self::Normal
-    }
-}
-
 impl<F: FnOnce() + Send + 'static> Job<F> {
     pub fn new(c: F) -> Self {
         Self {
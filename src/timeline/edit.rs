@@ -0,0 +1,844 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy_ecs::{entity::Entity, event::Event, system::Command, world::World};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    history::UndoHistory,
+    prelude::*,
+    timeline::{
+        TimelineSpan, Track, Transition, TransitionKind,
+        elements::{Properties, SourceOffset, TimeRemap, TimelineElement},
+        hierarchy::BindIndex,
+        sequence::{CompoundClip, InSequence, sequence_of},
+    },
+};
+
+/// Standard NLE edits on [`TimelineElement`]s.
+///
+/// Each edit only touches elements on its own track and either applies
+/// completely or leaves the world untouched. Edits on locked tracks are
+/// refused. Track numbers refer to the sequence of the edited element;
+/// [`Lift`](Self::Lift) and [`Extract`](Self::Extract) work on the root
+/// timeline.
+///
+/// A [`Transition`] moves along when both of its elements move its cut by
+/// the same distance. It is dropped when either element is split or
+/// removed, or when the cut no longer fits its span.
+///
+/// Elements [bound](crate::timeline::elements::BindTo) to a changed element
+/// stay in sync with its content: they move along when it moves or when a
+/// trim shifts its source frames, and stay put when a trim keeps them in
+/// place. Slipping an element leaves its children alone.
+#[derive(Debug, Clone)]
+pub enum TimelineEdit {
+    /// Cut `element` in two at `at`. The right half is a new entity carrying
+    /// a copy of the element's [`Properties`].
+    Razor { element: Entity, at: u64 },
+    /// Remove `element` and pull everything after it left to close the gap.
+    RippleDelete { element: Entity },
+    /// Move the cut between two adjacent elements to `to`, trimming both.
+    Roll {
+        outgoing: Entity,
+        incoming: Entity,
+        to: u64,
+    },
+    /// Change which part of the source `element` shows, without moving it.
+    Slip { element: Entity, delta: i64 },
+    /// Move `element` by `delta`, trimming the adjacent elements to match.
+    Slide { element: Entity, delta: i64 },
+    /// Place `element` at `at` on `track`, pushing later material right.
+    Insert {
+        element: Entity,
+        track: u64,
+        at: u64,
+        duration: u64,
+    },
+    /// Place `element` over `span` on `track`, replacing what was there.
+    Overwrite {
+        element: Entity,
+        track: u64,
+        span: TimelineSpan,
+    },
+    /// Remove the material in `span` on `track`, leaving a gap.
+    Lift { track: u64, span: TimelineSpan },
+    /// Remove the material in `span` on `track` and close the gap.
+    Extract { track: u64, span: TimelineSpan },
+}
+
+impl TimelineEdit {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Razor { .. } => "Razor",
+            Self::RippleDelete { .. } => "Ripple Delete",
+            Self::Roll { .. } => "Roll",
+            Self::Slip { .. } => "Slip",
+            Self::Slide { .. } => "Slide",
+            Self::Insert { .. } => "Insert",
+            Self::Overwrite { .. } => "Overwrite",
+            Self::Lift { .. } => "Lift",
+            Self::Extract { .. } => "Extract",
+        }
+    }
+
+    /// Perform the edit, returning the token that reverts it.
+    pub fn execute(self, world: &mut World) -> Result<TimelineEditToken> {
+        let label = self.name();
+        let mut session = EditSession::new(world);
+        match session
+            .run(self)
+            .and_then(|()| session.carry_children())
+            .and_then(|()| session.sync_transitions())
+        {
+            Ok(()) => Ok(session.finish(label)),
+            Err(e) => {
+                session.rollback();
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Command for TimelineEdit {
+    /// Performs the edit, records it in the [`UndoHistory`] if the world has
+    /// one, and triggers the resulting [`TimelineEditToken`].
+    fn apply(self, world: &mut World) {
+        let name = self.name();
+        let token = match self.execute(world) {
+            Ok(token) => token,
+            Err(e) => {
+                warn!("{name} edit failed: {e}");
+                return;
+            }
+        };
+        if let Some(mut history) = world.get_resource_mut::<UndoHistory>()
+            && let Err(e) = history.push(token.clone())
+        {
+            warn!("Failed to record {name} edit: {e}");
+        }
+        world.trigger(token);
+    }
+}
+
+/// Placement of an element on the timeline, as recorded in undo tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElementState {
+    pub track_num: u64,
+    pub start: u64,
+    pub end: u64,
+    pub source_offset: u64,
+}
+
+impl ElementState {
+    fn span(&self) -> TimelineSpan {
+        TimelineSpan::new_unchecked(self.start, self.end)
+    }
+
+    fn with_span(self, span: TimelineSpan) -> Self {
+        Self {
+            start: span.start(),
+            end: span.end(),
+            ..self
+        }
+    }
+
+    /// Same element with its start trimmed to `start`, keeping the source
+    /// frames that stay on screen where they were under `remap`.
+    fn trim_start(self, start: u64, remap: Option<&TimeRemap>) -> Result<Self> {
+        let source_offset = match remap {
+            Some(remap) => remap.source_tick(self.start, self.source_offset, start),
+            None => {
+                u64::try_from(self.source_offset as i128 + start as i128 - self.start as i128).ok()
+            }
+        }
+        .ok_or_else(|| LunarisError::InvalidArgument {
+            name: "start".to_string(),
+            reason: Some(format!(
+                "trimming to {start} runs past the start of the source"
+            )),
+        })?;
+        Ok(Self {
+            start,
+            source_offset,
+            ..self
+        })
+    }
+}
+
+/// Where an element spawned by an edit came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitFrom {
+    /// [`Entity::to_bits`] of the element it was cut off.
+    pub template: u64,
+    /// Placement it was spawned at, before the rest of the edit moved it.
+    pub state: ElementState,
+}
+
+/// One element's placement before and after an edit. `None` means the
+/// element was not on the timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElementChange {
+    /// [`Entity::to_bits`] of the element.
+    pub entity: u64,
+    pub before: Option<ElementState>,
+    pub after: Option<ElementState>,
+    /// Set for the halves that splits spawn, so that redoing the edit on an
+    /// empty entity, e.g. when replaying it after crash recovery, can copy
+    /// the template's components again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_from: Option<SplitFrom>,
+}
+
+/// A [`Transition`] an edit moved or dropped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransitionChange {
+    /// [`Entity::to_bits`] of the transition.
+    pub entity: u64,
+    /// [`Entity::to_bits`] of the outgoing element.
+    pub outgoing: u64,
+    /// [`Entity::to_bits`] of the incoming element.
+    pub incoming: u64,
+    pub kind: TransitionKind,
+    pub before: TimelineSpan,
+    /// `None` if the edit dropped the transition.
+    pub after: Option<TimelineSpan>,
+}
+
+/// Undo token produced by a [`TimelineEdit`].
+///
+/// Elements removed by an edit only lose their [`TimelineElement`], and
+/// dropped transitions their [`Transition`]; the entities and their other
+/// components stay alive so that undo can put them back.
+#[derive(Event, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEditToken {
+    pub label: String,
+    pub changes: Vec<ElementChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<TransitionChange>,
+}
+
+impl TimelineEditToken {
+    /// Restore every element to its state before the edit.
+    pub fn undo(&self, world: &mut World) -> Result {
+        for change in &self.changes {
+            write_state(world, Entity::from_bits(change.entity), change.before)?;
+        }
+        for change in &self.transitions {
+            write_transition(world, change, Some(change.before))?;
+        }
+        Ok(())
+    }
+
+    /// Re-apply the edit after an [`undo`](Self::undo).
+    pub fn redo(&self, world: &mut World) -> Result {
+        for change in &self.changes {
+            let entity = Entity::from_bits(change.entity);
+            if let Some(split) = change.split_from
+                && world
+                    .inspect_entity(entity)
+                    .is_ok_and(|mut components| components.next().is_none())
+            {
+                copy_split_components(world, Entity::from_bits(split.template), entity);
+                write_state(world, entity, Some(split.state))?;
+            }
+            write_state(world, entity, change.after)?;
+        }
+        for change in &self.transitions {
+            write_transition(world, change, change.after)?;
+        }
+        Ok(())
+    }
+}
+
+/// Give the transition of `change` the span `span`, or remove it for `None`.
+fn write_transition(
+    world: &mut World,
+    change: &TransitionChange,
+    span: Option<TimelineSpan>,
+) -> Result {
+    let entity = Entity::from_bits(change.entity);
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .map_err(|_| LunarisError::NotFound {
+            item: format!("Transition for Entity: {entity}"),
+        })?;
+    match span {
+        Some(span) => {
+            entity_mut.insert(Transition::new(
+                Entity::from_bits(change.outgoing),
+                Entity::from_bits(change.incoming),
+                span,
+                change.kind.clone(),
+            ));
+        }
+        None => {
+            entity_mut.remove::<Transition>();
+        }
+    }
+    Ok(())
+}
+
+/// Copy the properties, time remap, sequence and compound clip target of
+/// `template` onto `entity`.
+fn copy_split_components(world: &mut World, template: Entity, entity: Entity) {
+    let properties = world.get::<Properties>(template).cloned();
+    let remap = world.get::<TimeRemap>(template).cloned();
+    let parent = world.get::<InSequence>(template).copied();
+    let compound = world.get::<CompoundClip>(template).copied();
+    let mut entity = world.entity_mut(entity);
+    if let Some(properties) = properties {
+        entity.insert(properties);
+    }
+    if let Some(remap) = remap {
+        entity.insert(remap);
+    }
+    if let Some(parent) = parent {
+        entity.insert(parent);
+    }
+    if let Some(compound) = compound {
+        entity.insert(compound);
+    }
+}
+
+pub(crate) fn read_state(world: &World, entity: Entity) -> Option<ElementState> {
+    let element = world.get::<TimelineElement>(entity)?;
+    Some(ElementState {
+        track_num: element.track_num,
+        start: element.position.start(),
+        end: element.position.end(),
+        source_offset: world.get::<SourceOffset>(entity).map_or(0, |s| s.ticks),
+    })
+}
+
+/// Keyframes ride along when an element moves without changing length.
+pub(crate) fn write_state(
+    world: &mut World,
+    entity: Entity,
+    state: Option<ElementState>,
+) -> Result {
+    let current = read_state(world, entity);
+    if current == state {
+        return Ok(());
+    }
+    if let (Some(current), Some(state)) = (current, state)
+        && state.end.wrapping_sub(state.start) == current.end.wrapping_sub(current.start)
+        && state.start != current.start
+    {
+        let delta = shift_by(state.start as i128 - current.start as i128, "start")?;
+        if let Some(mut properties) = world.get_mut::<Properties>(entity) {
+            properties.shift_curves(delta);
+        }
+        if let Some(mut remap) = world.get_mut::<TimeRemap>(entity)
+            && let TimeRemap::Ramp(curve) = &mut *remap
+        {
+            curve.shift(delta);
+        }
+    }
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .map_err(|_| LunarisError::NotFound {
+            item: format!("Timeline element for Entity: {entity}"),
+        })?;
+    match state {
+        Some(state) => {
+            entity_mut.insert((
+                TimelineElement {
+                    track_num: state.track_num,
+                    position: TimelineSpan::new(state.start, state.end)?,
+                },
+                SourceOffset {
+                    ticks: state.source_offset,
+                },
+            ));
+        }
+        None => {
+            entity_mut.remove::<(TimelineElement, SourceOffset)>();
+        }
+    }
+    Ok(())
+}
+
+/// `ticks` as a distance to move elements by, failing when it does not fit.
+fn shift_by(ticks: i128, name: &str) -> Result<i64> {
+    i64::try_from(ticks).map_err(|_| LunarisError::InvalidArgument {
+        name: name.to_string(),
+        reason: Some(format!("cannot move elements by {ticks} ticks")),
+    })
+}
+
+/// Records every placement it changes so the edit can be turned into a
+/// token, or rolled back if a later step fails.
+struct EditSession<'w> {
+    world: &'w mut World,
+    changes: Vec<(Entity, Option<ElementState>, Option<ElementState>)>,
+    touched: HashMap<Entity, usize>,
+    spawned: HashMap<Entity, SplitFrom>,
+    transitions: Vec<TransitionChange>,
+    /// Sequence whose tracks the edit works on.
+    sequence: Option<Entity>,
+}
+
+impl<'w> EditSession<'w> {
+    fn new(world: &'w mut World) -> Self {
+        Self {
+            world,
+            changes: Vec::new(),
+            touched: HashMap::new(),
+            spawned: HashMap::new(),
+            transitions: Vec::new(),
+            sequence: None,
+        }
+    }
+
+    fn run(&mut self, edit: TimelineEdit) -> Result {
+        self.sequence = match &edit {
+            TimelineEdit::Razor { element, .. }
+            | TimelineEdit::RippleDelete { element }
+            | TimelineEdit::Slip { element, .. }
+            | TimelineEdit::Slide { element, .. }
+            | TimelineEdit::Insert { element, .. }
+            | TimelineEdit::Overwrite { element, .. } => sequence_of(self.world, *element),
+            TimelineEdit::Roll { outgoing, .. } => sequence_of(self.world, *outgoing),
+            TimelineEdit::Lift { .. } | TimelineEdit::Extract { .. } => None,
+        };
+        match edit {
+            TimelineEdit::Razor { element, at } => {
+                let state = self.state(element)?;
+                self.check_unlocked(state.track_num)?;
+                self.split(element, state, at)?;
+            }
+            TimelineEdit::RippleDelete { element } => {
+                let state = self.state(element)?;
+                self.check_unlocked(state.track_num)?;
+                let duration = shift_by(state.span().duration() as i128, "element")?;
+                self.set(element, None)?;
+                self.shift_from(state.track_num, state.end, -duration, None)?;
+            }
+            TimelineEdit::Roll {
+                outgoing,
+                incoming,
+                to,
+            } => {
+                let left = self.state(outgoing)?;
+                let right = self.state(incoming)?;
+                if left.track_num != right.track_num
+                    || left.end != right.start
+                    || sequence_of(self.world, incoming) != self.sequence
+                {
+                    return Err(LunarisError::InvalidArgument {
+                        name: "incoming".to_string(),
+                        reason: Some(format!("{incoming} does not directly follow {outgoing}")),
+                    });
+                }
+                if to <= left.start || to >= right.end {
+                    return Err(LunarisError::InvalidArgument {
+                        name: "to".to_string(),
+                        reason: Some(format!("rolling to {to} would empty an element")),
+                    });
+                }
+                self.check_unlocked(left.track_num)?;
+                self.set(outgoing, Some(ElementState { end: to, ..left }))?;
+                let right = self.trim_start(incoming, right, to)?;
+                self.set(incoming, Some(right))?;
+            }
+            TimelineEdit::Slip { element, delta } => {
+                let state = self.state(element)?;
+                self.check_unlocked(state.track_num)?;
+                let source_offset =
+                    state
+                        .source_offset
+                        .checked_add_signed(delta)
+                        .ok_or_else(|| LunarisError::InvalidArgument {
+                            name: "delta".to_string(),
+                            reason: Some(format!("slipping by {delta} runs past the source")),
+                        })?;
+                self.set(
+                    element,
+                    Some(ElementState {
+                        source_offset,
+                        ..state
+                    }),
+                )?;
+            }
+            TimelineEdit::Slide { element, delta } => self.slide(element, delta)?,
+            TimelineEdit::Insert {
+                element,
+                track,
+                at,
+                duration,
+            } => {
+                self.check_unlocked(track)?;
+                let span = TimelineSpan::with_duration(at, duration)?;
+                let delta = shift_by(duration as i128, "duration")?;
+                if let Some((straddling, state)) = self
+                    .clips_on(track, Some(element))
+                    .into_iter()
+                    .find(|(_, state)| state.start < at && at < state.end)
+                {
+                    self.split(straddling, state, at)?;
+                }
+                self.shift_from(track, at, delta, Some(element))?;
+                self.place(element, track, span)?;
+            }
+            TimelineEdit::Overwrite {
+                element,
+                track,
+                span,
+            } => {
+                self.check_unlocked(track)?;
+                self.clear(track, span, Some(element))?;
+                self.place(element, track, span)?;
+            }
+            TimelineEdit::Lift { track, span } => {
+                self.check_unlocked(track)?;
+                self.clear(track, span, None)?;
+            }
+            TimelineEdit::Extract { track, span } => {
+                self.check_unlocked(track)?;
+                self.clear(track, span, None)?;
+                let duration = shift_by(span.duration() as i128, "span")?;
+                self.shift_from(track, span.end(), -duration, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Keep the bound children of every changed element, all the way down,
+    /// in sync with the element's content. Children the edit changed itself
+    /// are left as they are.
+    fn carry_children(&mut self) -> Result {
+        let index = BindIndex::build(self.world);
+        let mut queue: VecDeque<Entity> = self.changes.iter().map(|(e, ..)| *e).collect();
+        let mut seen: HashSet<Entity> = queue.iter().copied().collect();
+        while let Some(parent) = queue.pop_front() {
+            let (_, before, after) = self.changes[self.touched[&parent]];
+            let (Some(before), Some(after)) = (before, after) else {
+                continue;
+            };
+            if before.start == after.start {
+                continue;
+            }
+            // How far the source frames of the parent moved on the timeline.
+            let delta = shift_by(
+                (after.start as i128 - before.start as i128)
+                    - (after.source_offset as i128 - before.source_offset as i128),
+                "start",
+            )?;
+            if delta == 0 {
+                continue;
+            }
+            for &child in index.children(parent) {
+                if !seen.insert(child) {
+                    continue;
+                }
+                let Some(state) = read_state(self.world, child) else {
+                    continue;
+                };
+                self.check_unlocked_in(sequence_of(self.world, child), state.track_num)?;
+                self.set(child, Some(state.with_span(state.span().shift(delta)?)))?;
+                queue.push_back(child);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move or drop the transitions of every changed element, see
+    /// [`TimelineEdit`].
+    fn sync_transitions(&mut self) -> Result {
+        let Some(mut query) = self.world.try_query::<(Entity, &Transition)>() else {
+            return Ok(());
+        };
+        let affected: Vec<(Entity, Transition)> = query
+            .iter(self.world)
+            .filter(|(_, t)| {
+                self.touched.contains_key(&t.outgoing) || self.touched.contains_key(&t.incoming)
+            })
+            .map(|(entity, t)| (entity, t.clone()))
+            .collect();
+        for (entity, transition) in affected {
+            let after = self.moved_span(&transition);
+            if after == Some(transition.span) {
+                continue;
+            }
+            let change = TransitionChange {
+                entity: entity.to_bits(),
+                outgoing: transition.outgoing.to_bits(),
+                incoming: transition.incoming.to_bits(),
+                kind: transition.kind,
+                before: transition.span,
+                after,
+            };
+            write_transition(self.world, &change, after)?;
+            self.transitions.push(change);
+        }
+        Ok(())
+    }
+
+    /// Span of `transition` after the edit, or `None` if it has to go.
+    fn moved_span(&self, transition: &Transition) -> Option<TimelineSpan> {
+        let split = |element: Entity| {
+            self.spawned
+                .values()
+                .any(|split| split.template == element.to_bits())
+        };
+        if split(transition.outgoing) || split(transition.incoming) {
+            return None;
+        }
+        // How far the edge of `element` at the cut moved.
+        let moved =
+            |element: Entity, edge: fn(ElementState) -> u64| match self.touched.get(&element) {
+                Some(&i) => {
+                    let (_, before, after) = self.changes[i];
+                    Some(edge(after?) as i128 - edge(before?) as i128)
+                }
+                None => Some(0),
+            };
+        let delta = moved(transition.outgoing, |state| state.end)?;
+        if moved(transition.incoming, |state| state.start)? != delta {
+            return None;
+        }
+        let span = transition.span.shift(i64::try_from(delta).ok()?).ok()?;
+        Transition {
+            span,
+            ..transition.clone()
+        }
+        .check(self.world)
+        .ok()?;
+        Some(span)
+    }
+
+    fn slide(&mut self, element: Entity, delta: i64) -> Result {
+        let state = self.state(element)?;
+        self.check_unlocked(state.track_num)?;
+        let span = state.span().shift(delta)?;
+        let others = self.clips_on(state.track_num, Some(element));
+        let left = others.iter().find(|(_, other)| other.end == state.start);
+        let right = others.iter().find(|(_, other)| other.start == state.end);
+
+        if let Some(&(left, left_state)) = left {
+            if span.start() <= left_state.start {
+                return Err(LunarisError::InvalidArgument {
+                    name: "delta".to_string(),
+                    reason: Some(format!("sliding by {delta} would empty {left}")),
+                });
+            }
+            self.set(
+                left,
+                Some(ElementState {
+                    end: span.start(),
+                    ..left_state
+                }),
+            )?;
+        }
+        if let Some(&(right, right_state)) = right {
+            if span.end() >= right_state.end {
+                return Err(LunarisError::InvalidArgument {
+                    name: "delta".to_string(),
+                    reason: Some(format!("sliding by {delta} would empty {right}")),
+                });
+            }
+            let trimmed = self.trim_start(right, right_state, span.end())?;
+            self.set(right, Some(trimmed))?;
+        }
+
+        let neighbours = [left.map(|(e, _)| *e), right.map(|(e, _)| *e)];
+        if let Some((blocking, _)) = others
+            .iter()
+            .filter(|(other, _)| !neighbours.contains(&Some(*other)))
+            .find(|(_, other)| other.span().intersects(&span))
+        {
+            return Err(LunarisError::InvalidArgument {
+                name: "delta".to_string(),
+                reason: Some(format!("sliding by {delta} would overlap {blocking}")),
+            });
+        }
+        self.set(element, Some(state.with_span(span)))
+    }
+
+    /// Cut `element` at `at`, spawning the right half.
+    fn split(&mut self, element: Entity, state: ElementState, at: u64) -> Result<Entity> {
+        let (left, _) = state.span().split_at(at)?;
+        self.set(element, Some(state.with_span(left)))?;
+        let right = self.trim_start(element, state, at)?;
+        self.spawn_from(element, right)
+    }
+
+    /// Remove everything in `span` on `track`, trimming or splitting the
+    /// elements that stick out of it.
+    fn clear(&mut self, track: u64, span: TimelineSpan, except: Option<Entity>) -> Result {
+        for (element, state) in self.clips_on(track, except) {
+            let current = state.span();
+            if !current.intersects(&span) {
+                continue;
+            }
+            let keeps_head = current.start() < span.start();
+            let keeps_tail = current.end() > span.end();
+            match (keeps_head, keeps_tail) {
+                (false, false) => self.set(element, None)?,
+                (true, false) => self.set(
+                    element,
+                    Some(ElementState {
+                        end: span.start(),
+                        ..state
+                    }),
+                )?,
+                (false, true) => {
+                    let tail = self.trim_start(element, state, span.end())?;
+                    self.set(element, Some(tail))?;
+                }
+                (true, true) => {
+                    self.set(
+                        element,
+                        Some(ElementState {
+                            end: span.start(),
+                            ..state
+                        }),
+                    )?;
+                    let tail = self.trim_start(element, state, span.end())?;
+                    self.spawn_from(element, tail)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Move every element on `track` starting at or after `from` by `delta`.
+    fn shift_from(&mut self, track: u64, from: u64, delta: i64, except: Option<Entity>) -> Result {
+        for (element, state) in self.clips_on(track, except) {
+            if state.start >= from {
+                let span = state.span().shift(delta)?;
+                self.set(element, Some(state.with_span(span)))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn place(&mut self, element: Entity, track: u64, span: TimelineSpan) -> Result {
+        let source_offset = self
+            .world
+            .get::<SourceOffset>(element)
+            .map_or(0, |s| s.ticks);
+        self.set(
+            element,
+            Some(ElementState {
+                track_num: track,
+                start: span.start(),
+                end: span.end(),
+                source_offset,
+            }),
+        )
+    }
+
+    fn trim_start(&self, element: Entity, state: ElementState, start: u64) -> Result<ElementState> {
+        state.trim_start(start, self.world.get::<TimeRemap>(element))
+    }
+
+    fn state(&self, element: Entity) -> Result<ElementState> {
+        read_state(self.world, element).ok_or_else(|| LunarisError::NotFound {
+            item: format!("Timeline element for Entity: {element}"),
+        })
+    }
+
+    /// Elements on `track` of the edited sequence, ordered by start.
+    fn clips_on(&mut self, track: u64, except: Option<Entity>) -> Vec<(Entity, ElementState)> {
+        let mut query = self
+            .world
+            .query::<(Entity, &TimelineElement, Option<&InSequence>)>();
+        let mut entities: Vec<Entity> = query
+            .iter(self.world)
+            .filter(|(entity, element, parent)| {
+                element.track_num == track
+                    && parent.map(|p| p.sequence) == self.sequence
+                    && Some(*entity) != except
+            })
+            .map(|(entity, ..)| entity)
+            .collect();
+        entities.sort();
+        let mut clips: Vec<_> = entities
+            .into_iter()
+            .filter_map(|entity| Some((entity, read_state(self.world, entity)?)))
+            .collect();
+        clips.sort_by_key(|(_, state)| state.start);
+        clips
+    }
+
+    fn check_unlocked(&self, track: u64) -> Result {
+        self.check_unlocked_in(self.sequence, track)
+    }
+
+    fn check_unlocked_in(&self, sequence: Option<Entity>, track: u64) -> Result {
+        let locked = Track::find_in(self.world, sequence, track)
+            .and_then(|entity| self.world.get::<Track>(entity))
+            .is_some_and(|track| track.locked);
+        if locked {
+            return Err(LunarisError::PermissionDenied {
+                operation: format!("edit locked track {track}"),
+            });
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, element: Entity, state: Option<ElementState>) -> Result {
+        let before = read_state(self.world, element);
+        write_state(self.world, element, state)?;
+        match self.touched.get(&element) {
+            Some(&i) => self.changes[i].2 = state,
+            None => {
+                self.touched.insert(element, self.changes.len());
+                self.changes.push((element, before, state));
+            }
+        }
+        Ok(())
+    }
+
+    /// Spawn a new element placed at `state`, copying the properties, time
+    /// remap, sequence and compound clip target of `template`.
+    fn spawn_from(&mut self, template: Entity, state: ElementState) -> Result<Entity> {
+        let entity = self.world.spawn_empty().id();
+        copy_split_components(self.world, template, entity);
+        self.spawned.insert(
+            entity,
+            SplitFrom {
+                template: template.to_bits(),
+                state,
+            },
+        );
+        self.set(entity, Some(state))?;
+        Ok(entity)
+    }
+
+    fn finish(self, label: &str) -> TimelineEditToken {
+        TimelineEditToken {
+            label: label.to_string(),
+            changes: self
+                .changes
+                .into_iter()
+                .filter(|(_, before, after)| before != after)
+                .map(|(entity, before, after)| ElementChange {
+                    entity: entity.to_bits(),
+                    before,
+                    after,
+                    split_from: self.spawned.get(&entity).copied(),
+                })
+                .collect(),
+            transitions: self.transitions,
+        }
+    }
+
+    fn rollback(self) {
+        for change in self.transitions.iter().rev() {
+            if let Err(e) = write_transition(self.world, change, Some(change.before)) {
+                warn!("Failed to roll back transition {}: {e}", change.entity);
+            }
+        }
+        for (entity, before, _) in self.changes.into_iter().rev() {
+            if let Err(e) = write_state(self.world, entity, before) {
+                warn!("Failed to roll back edit on {entity}: {e}");
+            }
+        }
+        for entity in self.spawned.into_keys() {
+            self.world.despawn(entity);
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{prelude::*, timeline::elements::serialize::finite};
+
+/// Value held by a [`Keyframe`]. Every key of a [`Curve`] holds the same
+/// variant.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CurveValue {
+    Float(#[serde(serialize_with = "finite")] f64),
+    Vec2(#[serde(serialize_with = "finite")] [f64; 2]),
+    /// Linear RGBA.
+    Color(#[serde(serialize_with = "finite")] [f32; 4]),
+}
+
+impl CurveValue {
+    pub fn get_variant_name(&self) -> &'static str {
+        match self {
+            Self::Float(_) => "Float",
+            Self::Vec2(_) => "Vec2",
+            Self::Color(_) => "Color",
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_vec2(&self) -> Option<[f64; 2]> {
+        match self {
+            Self::Vec2(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_color(&self) -> Option<[f32; 4]> {
+        match self {
+            Self::Color(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Blend towards `other` by `t`. Both values must be the same variant.
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        match (self, other) {
+            (Self::Float(a), Self::Float(b)) => Self::Float(a + (b - a) * t),
+            (Self::Vec2(a), Self::Vec2(b)) => {
+                Self::Vec2([a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t])
+            }
+            (Self::Color(a), Self::Color(b)) => {
+                let t = t as f32;
+                Self::Color(std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t))
+            }
+            _ => *self,
+        }
+    }
+}
+
+/// How a curve travels from one key to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum Interpolation {
+    /// Keep the value until the next key.
+    Hold,
+    #[default]
+    Linear,
+    /// CSS-style `cubic-bezier(x1, y1, x2, y2)` easing.
+    CubicBezier {
+        #[serde(serialize_with = "finite")]
+        x1: f64,
+        #[serde(serialize_with = "finite")]
+        y1: f64,
+        #[serde(serialize_with = "finite")]
+        x2: f64,
+        #[serde(serialize_with = "finite")]
+        y2: f64,
+    },
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Interpolation {
+    /// Map linear progress `t` in `[0, 1]` to eased progress.
+    pub fn ease(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Self::Hold => 0.0,
+            Self::Linear => t,
+            Self::CubicBezier { x1, y1, x2, y2 } => cubic_bezier(x1, y1, x2, y2, t),
+            Self::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Self::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Self::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+        }
+    }
+}
+
+/// Evaluate the y of the bezier through `(0,0)`, `(x1,y1)`, `(x2,y2)`,
+/// `(1,1)` at the point whose x is `x`.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    let x1 = x1.clamp(0.0, 1.0);
+    let x2 = x2.clamp(0.0, 1.0);
+    let sample = |a: f64, b: f64, s: f64| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * a + 3.0 * inv * s * s * b + s * s * s
+    };
+    let slope = |a: f64, b: f64, s: f64| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * a + 6.0 * inv * s * (b - a) + 3.0 * s * s * (1.0 - b)
+    };
+
+    // Newton first; fall back to bisection where the slope is too flat.
+    let mut s = x;
+    for _ in 0..8 {
+        let error = sample(x1, x2, s) - x;
+        if error.abs() < 1e-9 {
+            return sample(y1, y2, s);
+        }
+        let d = slope(x1, x2, s);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        s = (s - error / d).clamp(0.0, 1.0);
+    }
+    let (mut lo, mut hi) = (0.0, 1.0);
+    s = x;
+    for _ in 0..64 {
+        let value = sample(x1, x2, s);
+        if (value - x).abs() < 1e-9 {
+            break;
+        }
+        if value < x {
+            lo = s;
+        } else {
+            hi = s;
+        }
+        s = (lo + hi) / 2.0;
+    }
+    sample(y1, y2, s)
+}
+
+/// A key on a [`Curve`]. `interpolation` shapes the segment towards the
+/// next key.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// Timeline tick of the key.
+    pub tick: u64,
+    pub value: CurveValue,
+    pub interpolation: Interpolation,
+}
+
+impl Keyframe {
+    pub fn new(tick: u64, value: CurveValue) -> Self {
+        Self {
+            tick,
+            value,
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+}
+
+/// Animated parameter: keyframes sorted by tick, all of one value type.
+///
+/// Before the first key the curve holds the first value, after the last key
+/// it holds the last one.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(try_from = "CurveKeys")]
+pub struct Curve {
+    keys: Vec<Keyframe>,
+}
+
+/// Serialized form of a [`Curve`], validated through [`Curve::from_keys`]
+/// on load.
+#[derive(Deserialize)]
+struct CurveKeys {
+    keys: Vec<Keyframe>,
+}
+
+impl TryFrom<CurveKeys> for Curve {
+    type Error = LunarisError;
+
+    fn try_from(raw: CurveKeys) -> Result<Self> {
+        Self::from_keys(raw.keys)
+    }
+}
+
+impl Curve {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Curve that holds `value` forever.
+    pub fn constant(value: CurveValue) -> Self {
+        Self {
+            keys: vec![Keyframe::new(0, value)],
+        }
+    }
+
+    /// Build a curve from keys in any order.
+    pub fn from_keys(keys: impl IntoIterator<Item = Keyframe>) -> Result<Self> {
+        let mut curve = Self::new();
+        for key in keys {
+            curve.insert(key)?;
+        }
+        Ok(curve)
+    }
+
+    pub fn keys(&self) -> &[Keyframe] {
+        &self.keys
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Variant name of the values on this curve, if it has any keys.
+    pub fn value_variant_name(&self) -> Option<&'static str> {
+        self.keys.first().map(|key| key.value.get_variant_name())
+    }
+
+    /// Add a key, replacing any key already at the same tick.
+    pub fn insert(&mut self, key: Keyframe) -> Result {
+        if let Some(expected) = self.value_variant_name()
+            && expected != key.value.get_variant_name()
+        {
+            return Err(LunarisError::PropertyTypeMismatch {
+                expected_variant: expected.to_string(),
+                variant: key.value.get_variant_name().to_string(),
+            });
+        }
+        match self.keys.binary_search_by_key(&key.tick, |k| k.tick) {
+            Ok(i) => self.keys[i] = key,
+            Err(i) => self.keys.insert(i, key),
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, tick: u64) -> Option<Keyframe> {
+        let i = self.keys.binary_search_by_key(&tick, |k| k.tick).ok()?;
+        Some(self.keys.remove(i))
+    }
+
+    /// Value of the curve at `tick`, or `None` if it has no keys.
+    pub fn evaluate(&self, tick: u64) -> Option<CurveValue> {
+        let next = self.keys.partition_point(|k| k.tick <= tick);
+        let Some(prev) = next.checked_sub(1).map(|i| &self.keys[i]) else {
+            return self.keys.first().map(|k| k.value);
+        };
+        let Some(next) = self.keys.get(next) else {
+            return Some(prev.value);
+        };
+        if prev.interpolation == Interpolation::Hold {
+            return Some(prev.value);
+        }
+        let t = (tick - prev.tick) as f64 / (next.tick - prev.tick) as f64;
+        Some(prev.value.lerp(&next.value, prev.interpolation.ease(t)))
+    }
+
+    /// Move every key by `delta` ticks, clamping at the ends of the timeline.
+    pub fn shift(&mut self, delta: i64) {
+        self.map_ticks(|tick| tick.saturating_add_signed(delta));
+    }
+
+    /// Move every key through the monotonic `f`. Keys that land on the same
+    /// tick collapse into the later one.
+    pub(crate) fn map_ticks(&mut self, mut f: impl FnMut(u64) -> u64) {
+        for key in &mut self.keys {
+            key.tick = f(key.tick);
+        }
+        self.keys.reverse();
+        self.keys.dedup_by_key(|k| k.tick);
+        self.keys.reverse();
+    }
+}
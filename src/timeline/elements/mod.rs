@@ -4,14 +4,42 @@ use crate::{render::RawImage, timeline::TimelineSpan, util::error::Result};
 
 use bevy_ecs::{component::Component, entity::Entity};
 
-#[derive(Component, Debug)]
+pub mod curve;
+pub mod remap;
+pub mod schema;
+pub mod serialize;
+pub mod value;
+
+pub use curve::{Curve, CurveValue, Interpolation, Keyframe};
+pub use lunaris_api_derive::PropertySet;
+pub use remap::TimeRemap;
+pub use schema::{PropertyField, PropertyKind, PropertySchema, SchemaViolation, UiHint};
+pub use serialize::{CustomPropertyRegistration, OpaqueCustom};
+pub use value::{PropertySet, PropertyValue};
+
+#[derive(Component, Debug, Clone)]
 pub struct TimelineElement {
     /// Track number of Timeline Element, or in other words, the Z-index.
+    /// Matches the [`Track::index`](crate::timeline::Track::index) of the track it sits on.
     pub track_num: u64,
     pub position: TimelineSpan,
 }
 
-#[derive(Component, Debug)]
+/// Position in the source media at the start of an element, in ticks.
+/// Elements without one start at the beginning of their source.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceOffset {
+    pub ticks: u64,
+}
+
+/// Makes this entity a child of `id`.
+///
+/// Bound elements move with their parent's content when a [`TimelineEdit`]
+/// moves or trims it, and their transform is relative to the parent's. See
+/// [`hierarchy`](crate::timeline::hierarchy).
+///
+/// [`TimelineEdit`]: crate::timeline::TimelineEdit
+#[derive(Component, Debug, Clone, Copy)]
 pub struct BindTo {
     pub id: Entity,
 }
@@ -37,6 +65,27 @@ impl Properties {
     pub fn remove(&mut self, key: &str) -> Option<Property> {
         self.properties.remove(key)
     }
+
+    /// Numeric or animated property `key` as seen at `tick`. Curves are
+    /// evaluated, plain numbers are returned as [`CurveValue::Float`].
+    pub fn value_at(&self, key: &str, tick: u64) -> Option<CurveValue> {
+        match self.get(key)? {
+            Property::Curve(curve) => curve.evaluate(tick),
+            Property::Float(value) => Some(CurveValue::Float(*value)),
+            Property::Integer(value) => Some(CurveValue::Float(*value as f64)),
+            _ => None,
+        }
+    }
+
+    /// Move the keys of every [`Property::Curve`] by `delta` ticks, so that
+    /// animation follows an element that moved on the timeline.
+    pub fn shift_curves(&mut self, delta: i64) {
+        for value in self.properties.values_mut() {
+            if let Property::Curve(curve) = value {
+                curve.shift(delta);
+            }
+        }
+    }
 }
 
 impl From<Properties> for HashMap<String, Property> {
@@ -54,7 +103,11 @@ pub struct Renderable {
 pub enum Property {
     String(String),
     Integer(u64),
-    Curve(Vec<u64>),
+    /// A point in time, in ticks of the project [`Timebase`](crate::timeline::timebase::Timebase).
+    /// Rescaled along with the timeline when the timebase changes.
+    Ticks(u64),
+    /// Keyframed animation, evaluated at timeline ticks.
+    Curve(Curve),
     Float(f64),
     Entity(Entity),
     Path(PathBuf),
@@ -66,6 +119,7 @@ impl Property {
         match &self {
             Self::String(_) => "String",
             Self::Integer(_) => "Integer",
+            Self::Ticks(_) => "Ticks",
             Self::Curve(_) => "Curve",
             Self::Float(_) => "Float",
             Self::Entity(_) => "Entity",
@@ -90,6 +144,7 @@ impl PartialEq for Property {
         match (self, other) {
             (Self::String(a), Self::String(b)) => a == b,
             (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Ticks(a), Self::Ticks(b)) => a == b,
             (Self::Curve(a), Self::Curve(b)) => a == b,
             (Self::Float(a), Self::Float(b)) => a == b,
             (Self::Entity(a), Self::Entity(b)) => a == b,
@@ -0,0 +1,89 @@
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use serde::{Deserialize, Serialize};
+
+use crate::timeline::elements::{Curve, SourceOffset, TimelineElement, serialize::finite};
+
+/// Samples per key interval when integrating a speed ramp.
+const RAMP_SAMPLES: u32 = 64;
+
+/// How an element's source plays back. Elements without one play at normal
+/// speed.
+///
+/// Every mode is anchored at the element's start, where the source is at
+/// its [`SourceOffset`].
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TimeRemap {
+    /// Constant multiple of normal speed. Negative plays backwards from the
+    /// source offset, so a reversed clip's offset is where its source ends.
+    Speed(#[serde(serialize_with = "finite")] f64),
+    /// Hold the source offset for the whole element.
+    Freeze,
+    /// Speed keyed at timeline ticks, a [`Curve`] of
+    /// [`CurveValue::Float`](crate::timeline::elements::CurveValue::Float).
+    /// The keys move with the element like any other curve.
+    Ramp(Curve),
+}
+
+impl TimeRemap {
+    /// Reverse at normal speed.
+    pub const REVERSE: Self = Self::Speed(-1.0);
+
+    /// Source tick shown at timeline `tick` by an element starting at `start`
+    /// with source offset `offset`. `None` if that lies before the start of
+    /// the source.
+    pub fn source_tick(&self, start: u64, offset: u64, tick: u64) -> Option<u64> {
+        let consumed = match self {
+            Self::Speed(speed) => (tick as f64 - start as f64) * speed,
+            Self::Freeze => 0.0,
+            Self::Ramp(curve) if tick >= start => integrate(curve, start, tick),
+            Self::Ramp(curve) => -integrate(curve, tick, start),
+        };
+        let source = (offset as f64 + consumed).round();
+        (source >= 0.0).then_some(source.min(u64::MAX as f64) as u64)
+    }
+}
+
+/// Source ticks played by `curve` between timeline ticks `from` and `to`.
+///
+/// Each interval between keys is integrated with the midpoint rule, which is
+/// exact for linear and hold segments and close for eased ones.
+fn integrate(curve: &Curve, from: u64, to: u64) -> f64 {
+    let speed = |tick: f64| {
+        curve
+            .evaluate(tick.round() as u64)
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0)
+    };
+    let mut bounds = vec![from];
+    bounds.extend(
+        curve
+            .keys()
+            .iter()
+            .map(|k| k.tick)
+            .filter(|t| (from + 1..to).contains(t)),
+    );
+    bounds.push(to);
+    bounds
+        .windows(2)
+        .map(|pair| {
+            let (a, b) = (pair[0] as f64, pair[1] as f64);
+            let samples = (pair[1] - pair[0]).clamp(1, RAMP_SAMPLES as u64) as u32;
+            let step = (b - a) / samples as f64;
+            (0..samples)
+                .map(|i| speed(a + (i as f64 + 0.5) * step) * step)
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+/// Source tick `element` shows at `tick` of its sequence, with its
+/// [`TimeRemap`] applied. Positions before the start of the source clamp to
+/// `0`.
+pub fn source_tick(world: &World, element: Entity, tick: u64) -> Option<u64> {
+    let start = world.get::<TimelineElement>(element)?.position.start();
+    let offset = world.get::<SourceOffset>(element).map_or(0, |s| s.ticks);
+    Some(match world.get::<TimeRemap>(element) {
+        Some(remap) => remap.source_tick(start, offset, tick).unwrap_or(0),
+        None => offset.saturating_add(tick.saturating_sub(start)),
+    })
+}
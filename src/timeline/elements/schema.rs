@@ -0,0 +1,375 @@
+use std::fmt::Display;
+
+use crate::{
+    prelude::*,
+    timeline::elements::{Properties, Property},
+};
+
+/// Variant of a [`Property`], without its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropertyKind {
+    String,
+    Integer,
+    Ticks,
+    Curve,
+    Float,
+    Entity,
+    Path,
+    Custom,
+}
+
+impl PropertyKind {
+    /// Same name as [`Property::get_variant_name`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::String => "String",
+            Self::Integer => "Integer",
+            Self::Ticks => "Ticks",
+            Self::Curve => "Curve",
+            Self::Float => "Float",
+            Self::Entity => "Entity",
+            Self::Path => "Path",
+            Self::Custom => "Custom",
+        }
+    }
+}
+
+impl Property {
+    pub fn kind(&self) -> PropertyKind {
+        match self {
+            Self::String(_) => PropertyKind::String,
+            Self::Integer(_) => PropertyKind::Integer,
+            Self::Ticks(_) => PropertyKind::Ticks,
+            Self::Curve(_) => PropertyKind::Curve,
+            Self::Float(_) => PropertyKind::Float,
+            Self::Entity(_) => PropertyKind::Entity,
+            Self::Path(_) => PropertyKind::Path,
+            Self::Custom(_) => PropertyKind::Custom,
+        }
+    }
+}
+
+/// How an editor should present a field. Purely advisory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiHint {
+    /// Let the editor pick from the kind.
+    #[default]
+    Auto,
+    Slider,
+    Angle,
+    Color,
+    Text,
+    MultilineText,
+    FilePicker,
+    Dropdown,
+    /// Not shown in the inspector.
+    Hidden,
+}
+
+/// Declaration of one key of a [`PropertySchema`].
+#[derive(Debug, Clone)]
+pub struct PropertyField {
+    pub key: String,
+    pub kind: PropertyKind,
+    /// Inserted by [`PropertySchema::fill_defaults`] when the key is missing.
+    pub default: Option<Property>,
+    /// Inclusive bounds for numbers and for the keys of float curves.
+    pub range: Option<(f64, f64)>,
+    /// Allowed values of a string field. Empty allows any string.
+    pub choices: Vec<String>,
+    pub hint: UiHint,
+    /// Missing required fields without a default fail validation.
+    pub required: bool,
+    pub description: Option<String>,
+}
+
+impl PropertyField {
+    pub fn new(key: impl Into<String>, kind: PropertyKind) -> Self {
+        Self {
+            key: key.into(),
+            kind,
+            default: None,
+            range: None,
+            choices: Vec::new(),
+            hint: UiHint::Auto,
+            required: false,
+            description: None,
+        }
+    }
+
+    pub fn default_value(mut self, value: Property) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    pub fn choices<S: Into<String>>(mut self, choices: impl IntoIterator<Item = S>) -> Self {
+        self.choices = choices.into_iter().map(Into::into).collect();
+        if self.hint == UiHint::Auto {
+            self.hint = UiHint::Dropdown;
+        }
+        self
+    }
+
+    pub fn hint(mut self, hint: UiHint) -> Self {
+        self.hint = hint;
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Problems with `value` as the value of this field.
+    fn violations(&self, value: &Property) -> Vec<SchemaViolation> {
+        let violation = |problem| SchemaViolation {
+            key: self.key.clone(),
+            problem,
+        };
+        if value.kind() != self.kind {
+            return vec![violation(Problem::TypeMismatch {
+                expected: self.kind,
+                found: value.kind(),
+            })];
+        }
+
+        let mut found = Vec::new();
+        if let Some((min, max)) = self.range {
+            let numbers: Vec<f64> = match value {
+                Property::Integer(v) | Property::Ticks(v) => vec![*v as f64],
+                Property::Float(v) => vec![*v],
+                Property::Curve(curve) => curve
+                    .keys()
+                    .iter()
+                    .filter_map(|key| key.value.as_f64())
+                    .collect(),
+                _ => Vec::new(),
+            };
+            found.extend(
+                numbers
+                    .into_iter()
+                    .filter(|v| !(min..=max).contains(v))
+                    .map(|value| violation(Problem::OutOfRange { value, min, max })),
+            );
+        }
+        if let Property::String(value) = value
+            && !self.choices.is_empty()
+            && !self.choices.contains(value)
+        {
+            found.push(violation(Problem::InvalidChoice {
+                value: value.clone(),
+                choices: self.choices.clone(),
+            }));
+        }
+        found
+    }
+}
+
+/// What is wrong with a key of a [`Properties`] map.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    Missing,
+    /// The key is not declared and the schema does not allow extra keys.
+    Unknown,
+    TypeMismatch {
+        expected: PropertyKind,
+        found: PropertyKind,
+    },
+    OutOfRange {
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    InvalidChoice {
+        value: String,
+        choices: Vec<String>,
+    },
+}
+
+/// One failed check of [`PropertySchema::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    pub key: String,
+    pub problem: Problem,
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.problem {
+            Problem::Missing => write!(f, "{}: required property is missing", self.key),
+            Problem::Unknown => write!(f, "{}: unknown property", self.key),
+            Problem::TypeMismatch { expected, found } => write!(
+                f,
+                "{}: expected {}, found {}",
+                self.key,
+                expected.name(),
+                found.name()
+            ),
+            Problem::OutOfRange { value, min, max } => {
+                write!(f, "{}: {value} is outside {min}..={max}", self.key)
+            }
+            Problem::InvalidChoice { value, choices } => {
+                write!(f, "{}: {value:?} is not one of {choices:?}", self.key)
+            }
+        }
+    }
+}
+
+impl From<SchemaViolation> for LunarisError {
+    fn from(violation: SchemaViolation) -> Self {
+        match violation.problem {
+            Problem::TypeMismatch { expected, found } => LunarisError::PropertyTypeMismatch {
+                expected_variant: expected.name().to_string(),
+                variant: found.name().to_string(),
+            },
+            Problem::Missing => LunarisError::NotFound {
+                item: format!("property {}", violation.key),
+            },
+            _ => LunarisError::InvalidArgument {
+                reason: Some(violation.to_string()),
+                name: violation.key,
+            },
+        }
+    }
+}
+
+/// Properties a plugin expects on the elements it handles.
+///
+/// Unknown keys are reported so typos surface before render time; use
+/// [`allow_unknown`](Self::allow_unknown) for plugins that accept
+/// free-form extra keys.
+#[derive(Debug, Clone, Default)]
+pub struct PropertySchema {
+    fields: Vec<PropertyField>,
+    allow_unknown: bool,
+}
+
+impl PropertySchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schema that declares nothing and accepts anything.
+    pub fn any() -> Self {
+        Self::new().allow_unknown()
+    }
+
+    /// Add a field, replacing any earlier field with the same key.
+    pub fn field(mut self, field: PropertyField) -> Self {
+        self.fields.retain(|f| f.key != field.key);
+        self.fields.push(field);
+        self
+    }
+
+    pub fn allow_unknown(mut self) -> Self {
+        self.allow_unknown = true;
+        self
+    }
+
+    /// Fields in declaration order.
+    pub fn fields(&self) -> &[PropertyField] {
+        &self.fields
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PropertyField> {
+        self.fields.iter().find(|f| f.key == key)
+    }
+
+    /// Every problem with `properties`, ordered by field and then by key.
+    pub fn validate(&self, properties: &Properties) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        for field in &self.fields {
+            match properties.get(&field.key) {
+                Some(value) => violations.extend(field.violations(value)),
+                None if field.required && field.default.is_none() => {
+                    violations.push(SchemaViolation {
+                        key: field.key.clone(),
+                        problem: Problem::Missing,
+                    })
+                }
+                None => {}
+            }
+        }
+        if !self.allow_unknown {
+            let mut unknown: Vec<&String> = properties
+                .properties
+                .keys()
+                .filter(|key| self.get(key).is_none())
+                .collect();
+            unknown.sort();
+            violations.extend(unknown.into_iter().map(|key| SchemaViolation {
+                key: key.clone(),
+                problem: Problem::Unknown,
+            }));
+        }
+        violations
+    }
+
+    /// Like [`validate`](Self::validate), failing on the first problem.
+    pub fn check(&self, properties: &Properties) -> Result {
+        match self.validate(properties).into_iter().next() {
+            Some(violation) => Err(violation.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Check a single value against the field declared for `key`.
+    pub fn check_value(&self, key: &str, value: &Property) -> Result {
+        let Some(field) = self.get(key) else {
+            if self.allow_unknown {
+                return Ok(());
+            }
+            return Err(SchemaViolation {
+                key: key.to_string(),
+                problem: Problem::Unknown,
+            }
+            .into());
+        };
+        match field.violations(value).into_iter().next() {
+            Some(violation) => Err(violation.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Insert the default of every declared field missing from
+    /// `properties`. Existing values are left alone.
+    pub fn fill_defaults(&self, properties: &mut Properties) {
+        for field in &self.fields {
+            if let Some(default) = &field.default
+                && properties.get(&field.key).is_none()
+            {
+                properties.insert(field.key.clone(), default.clone());
+            }
+        }
+    }
+
+    /// [`fill_defaults`](Self::fill_defaults), then [`check`](Self::check).
+    pub fn prepare(&self, properties: &mut Properties) -> Result {
+        self.fill_defaults(properties);
+        self.check(properties)
+    }
+}
+
+impl Properties {
+    /// Insert `value` under `key` only if `schema` accepts it.
+    pub fn insert_checked(
+        &mut self,
+        schema: &PropertySchema,
+        key: impl Into<String>,
+        value: Property,
+    ) -> Result<Option<Property>> {
+        let key = key.into();
+        schema.check_value(&key, &value)?;
+        Ok(self.insert(key, value))
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+};
+
+use bevy_ecs::entity::Entity;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::DeserializeOwned};
+
+use crate::{
+    prelude::*,
+    timeline::elements::{Curve, Properties, Property},
+};
+
+pub type CustomValue = Arc<dyn Any + Send + Sync>;
+
+/// Registration of a type that may be stored in [`Property::Custom`].
+///
+/// Submit one with [`submit_raw!`](crate::submit_raw) so that values of the
+/// type can be saved and loaded. `name` is written to disk, so keep it
+/// stable and prefix it with the plugin name.
+///
+/// ```ignore
+/// lunaris_api::submit_raw!(CustomPropertyRegistration {
+///     name: "my_plugin.gradient",
+///     type_id: TypeId::of::<Gradient>,
+///     encode: encode_json::<Gradient>,
+///     decode: decode_json::<Gradient>,
+/// });
+/// ```
+pub struct CustomPropertyRegistration {
+    pub name: &'static str,
+    pub type_id: fn() -> TypeId,
+    pub encode: fn(&(dyn Any + Send + Sync)) -> Result<Vec<u8>>,
+    pub decode: fn(&[u8]) -> Result<CustomValue>,
+}
+
+inventory::collect!(CustomPropertyRegistration);
+
+/// Encoder for [`CustomPropertyRegistration`] storing `T` as JSON.
+pub fn encode_json<T: Serialize + 'static>(value: &(dyn Any + Send + Sync)) -> Result<Vec<u8>> {
+    let value = value
+        .downcast_ref::<T>()
+        .ok_or_else(|| LunarisError::PropertyTypeMismatch {
+            expected_variant: std::any::type_name::<T>().to_string(),
+            variant: "another custom type".to_string(),
+        })?;
+    serde_json::to_vec(value).map_err(|e| LunarisError::InvalidArgument {
+        name: std::any::type_name::<T>().to_string(),
+        reason: Some(e.to_string()),
+    })
+}
+
+/// Decoder for [`CustomPropertyRegistration`] reading `T` from JSON.
+pub fn decode_json<T: DeserializeOwned + Send + Sync + 'static>(
+    bytes: &[u8],
+) -> Result<CustomValue> {
+    let value: T = serde_json::from_slice(bytes).map_err(|e| LunarisError::FailedSaveLoad {
+        reason: format!("{}: {e}", std::any::type_name::<T>()),
+    })?;
+    Ok(Arc::new(value))
+}
+
+/// Floats, or arrays of them, checked by [`finite`].
+pub(crate) trait Finite: Serialize + std::fmt::Debug {
+    fn is_finite(&self) -> bool;
+}
+
+impl Finite for f64 {
+    fn is_finite(&self) -> bool {
+        f64::is_finite(*self)
+    }
+}
+
+impl Finite for f32 {
+    fn is_finite(&self) -> bool {
+        f32::is_finite(*self)
+    }
+}
+
+impl<T: Finite, const N: usize> Finite for [T; N]
+where
+    [T; N]: Serialize,
+{
+    fn is_finite(&self) -> bool {
+        self.iter().all(Finite::is_finite)
+    }
+}
+
+/// `serialize_with` for float fields. JSON writes NaN and infinities as
+/// `null`, which would not load again, so they fail the save instead.
+pub(crate) fn finite<T: Finite, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    if !value.is_finite() {
+        return Err(serde::ser::Error::custom(format!(
+            "{value:?} is not a finite number"
+        )));
+    }
+    value.serialize(serializer)
+}
+
+/// Custom value whose type was not registered when it was loaded. It is
+/// written back unchanged, so projects survive a trip through a host that
+/// lacks the plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpaqueCustom {
+    pub type_name: String,
+    pub bytes: Vec<u8>,
+}
+
+struct Registry {
+    by_name: HashMap<&'static str, &'static CustomPropertyRegistration>,
+    by_type: HashMap<TypeId, &'static CustomPropertyRegistration>,
+}
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(|| {
+    let mut registry = Registry {
+        by_name: HashMap::new(),
+        by_type: HashMap::new(),
+    };
+    for registration in inventory::iter::<CustomPropertyRegistration> {
+        registry.by_name.insert(registration.name, registration);
+        registry
+            .by_type
+            .insert((registration.type_id)(), registration);
+    }
+    registry
+});
+
+/// Whether custom values named `name` can be decoded.
+pub fn is_custom_registered(name: &str) -> bool {
+    REGISTRY.by_name.contains_key(name)
+}
+
+fn encode_custom(value: &CustomValue) -> Result<CustomRepr> {
+    let value: &(dyn Any + Send + Sync) = value.as_ref();
+    if let Some(opaque) = value.downcast_ref::<OpaqueCustom>() {
+        return Ok(CustomRepr {
+            type_name: opaque.type_name.clone(),
+            bytes: opaque.bytes.clone(),
+        });
+    }
+    let registration =
+        REGISTRY
+            .by_type
+            .get(&value.type_id())
+            .ok_or_else(|| LunarisError::NotFound {
+                item: "custom property registration for value".to_string(),
+            })?;
+    Ok(CustomRepr {
+        type_name: registration.name.to_string(),
+        bytes: (registration.encode)(value)?,
+    })
+}
+
+fn decode_custom(repr: CustomRepr) -> Result<CustomValue> {
+    match REGISTRY.by_name.get(repr.type_name.as_str()) {
+        Some(registration) => (registration.decode)(&repr.bytes),
+        None => Ok(Arc::new(OpaqueCustom {
+            type_name: repr.type_name,
+            bytes: repr.bytes,
+        })),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CustomRepr {
+    type_name: String,
+    bytes: Vec<u8>,
+}
+
+/// On-disk shape of [`Property`]. Entities are stored as their bits and
+/// must be remapped by whoever loads them into another world.
+#[derive(Serialize, Deserialize)]
+enum PropertyRepr {
+    String(String),
+    Integer(u64),
+    Ticks(u64),
+    Curve(Curve),
+    Float(#[serde(serialize_with = "finite")] f64),
+    Entity(u64),
+    Path(PathBuf),
+    Custom(CustomRepr),
+}
+
+impl Serialize for Property {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let repr = match self {
+            Self::String(v) => PropertyRepr::String(v.clone()),
+            Self::Integer(v) => PropertyRepr::Integer(*v),
+            Self::Ticks(v) => PropertyRepr::Ticks(*v),
+            Self::Curve(v) => PropertyRepr::Curve(v.clone()),
+            Self::Float(v) => PropertyRepr::Float(*v),
+            Self::Entity(v) => PropertyRepr::Entity(v.to_bits()),
+            Self::Path(v) => PropertyRepr::Path(v.clone()),
+            Self::Custom(v) => {
+                PropertyRepr::Custom(encode_custom(v).map_err(serde::ser::Error::custom)?)
+            }
+        };
+        repr.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Property {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(match PropertyRepr::deserialize(deserializer)? {
+            PropertyRepr::String(v) => Self::String(v),
+            PropertyRepr::Integer(v) => Self::Integer(v),
+            PropertyRepr::Ticks(v) => Self::Ticks(v),
+            PropertyRepr::Curve(v) => Self::Curve(v),
+            PropertyRepr::Float(v) => Self::Float(v),
+            PropertyRepr::Entity(bits) => Self::Entity(
+                Entity::try_from_bits(bits)
+                    .map_err(|_| serde::de::Error::custom(format!("invalid entity {bits}")))?,
+            ),
+            PropertyRepr::Path(v) => Self::Path(v),
+            PropertyRepr::Custom(repr) => {
+                Self::Custom(decode_custom(repr).map_err(serde::de::Error::custom)?)
+            }
+        })
+    }
+}
+
+/// Keys are written in sorted order so saved files diff cleanly.
+impl Serialize for Properties {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let sorted: BTreeMap<&String, &Property> = self.properties.iter().collect();
+        sorted.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Properties {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(Self {
+            properties: HashMap::deserialize(deserializer)?,
+        })
+    }
+}
@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+
+use bevy_ecs::entity::Entity;
+
+use crate::{
+    prelude::*,
+    timeline::elements::{Curve, Properties, Property, PropertyKind, PropertySchema},
+};
+
+/// A Rust type stored as one [`Property`] variant.
+pub trait PropertyValue: Sized {
+    const KIND: PropertyKind;
+
+    fn from_property(property: &Property) -> Result<Self>;
+    fn to_property(&self) -> Property;
+}
+
+/// A struct of parameters stored as [`Properties`].
+///
+/// Usually derived with `#[derive(PropertySet)]`; see the macro for the
+/// field attributes it accepts.
+pub trait PropertySet: Sized {
+    fn from_properties(properties: &Properties) -> Result<Self>;
+    fn to_properties(&self) -> Properties;
+
+    /// Schema matching [`from_properties`](Self::from_properties): fields
+    /// without a default are required.
+    fn schema() -> PropertySchema;
+}
+
+fn mismatch(expected: PropertyKind, found: &Property) -> LunarisError {
+    LunarisError::PropertyTypeMismatch {
+        expected_variant: expected.name().to_string(),
+        variant: found.get_variant_name().to_string(),
+    }
+}
+
+macro_rules! property_value {
+    ($ty:ty, $variant:ident, |$v:ident| $from:expr, |$s:ident| $to:expr) => {
+        impl PropertyValue for $ty {
+            const KIND: PropertyKind = PropertyKind::$variant;
+
+            fn from_property(property: &Property) -> Result<Self> {
+                match property {
+                    Property::$variant($v) => $from,
+                    other => Err(mismatch(Self::KIND, other)),
+                }
+            }
+
+            fn to_property(&self) -> Property {
+                let $s = self;
+                Property::$variant($to)
+            }
+        }
+    };
+}
+
+property_value!(f64, Float, |v| Ok(*v), |s| *s);
+property_value!(f32, Float, |v| Ok(*v as f32), |s| *s as f64);
+property_value!(u64, Integer, |v| Ok(*v), |s| *s);
+property_value!(
+    u32,
+    Integer,
+    |v| u32::try_from(*v).map_err(|_| LunarisError::InvalidArgument {
+        name: "property".to_string(),
+        reason: Some(format!("{v} does not fit in u32")),
+    }),
+    |s| *s as u64
+);
+property_value!(String, String, |v| Ok(v.clone()), |s| s.clone());
+property_value!(PathBuf, Path, |v| Ok(v.clone()), |s| s.clone());
+property_value!(Entity, Entity, |v| Ok(*v), |s| *s);
+property_value!(Curve, Curve, |v| Ok(v.clone()), |s| s.clone());
+
+impl Properties {
+    fn require(&self, key: &str) -> Result<&Property> {
+        self.get(key).ok_or_else(|| LunarisError::NotFound {
+            item: format!("property {key}"),
+        })
+    }
+
+    /// Value of `key` converted to `T`.
+    pub fn get_as<T: PropertyValue>(&self, key: &str) -> Result<T> {
+        T::from_property(self.require(key)?)
+    }
+
+    pub fn get_f64(&self, key: &str) -> Result<f64> {
+        self.get_as(key)
+    }
+
+    pub fn get_u64(&self, key: &str) -> Result<u64> {
+        self.get_as(key)
+    }
+
+    /// Value of a [`Property::Ticks`] key.
+    pub fn get_ticks(&self, key: &str) -> Result<u64> {
+        match self.require(key)? {
+            Property::Ticks(ticks) => Ok(*ticks),
+            other => Err(mismatch(PropertyKind::Ticks, other)),
+        }
+    }
+
+    pub fn get_string(&self, key: &str) -> Result<&str> {
+        match self.require(key)? {
+            Property::String(value) => Ok(value),
+            other => Err(mismatch(PropertyKind::String, other)),
+        }
+    }
+
+    pub fn get_path(&self, key: &str) -> Result<&Path> {
+        match self.require(key)? {
+            Property::Path(value) => Ok(value),
+            other => Err(mismatch(PropertyKind::Path, other)),
+        }
+    }
+
+    pub fn get_entity(&self, key: &str) -> Result<Entity> {
+        self.get_as(key)
+    }
+
+    pub fn get_curve(&self, key: &str) -> Result<&Curve> {
+        match self.require(key)? {
+            Property::Curve(value) => Ok(value),
+            other => Err(mismatch(PropertyKind::Curve, other)),
+        }
+    }
+
+    /// Value of a [`Property::Custom`] key holding a `T`.
+    pub fn get_custom<T: std::any::Any + Send + Sync>(&self, key: &str) -> Result<&T> {
+        let property = self.require(key)?;
+        property
+            .as_custom::<T>()
+            .ok_or_else(|| LunarisError::PropertyTypeMismatch {
+                expected_variant: format!("Custom({})", std::any::type_name::<T>()),
+                variant: property.get_variant_name().to_string(),
+            })
+    }
+
+    /// Read the whole map as a [`PropertySet`].
+    pub fn parse<T: PropertySet>(&self) -> Result<T> {
+        T::from_properties(self)
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy_ecs::{
+    entity::Entity,
+    query::Changed,
+    removal_detection::RemovedComponents,
+    resource::Resource,
+    system::{Query, ResMut},
+    world::World,
+};
+
+use crate::{
+    prelude::*,
+    timeline::elements::{BindTo, Properties},
+    util::graph::find_cycle,
+};
+
+/// Property holding an element's offset from its parent, a
+/// [`CurveValue::Vec2`](crate::timeline::elements::CurveValue::Vec2).
+pub const POSITION_KEY: &str = "position";
+/// Property holding an element's scale, a `Vec2` or a uniform `Float`.
+pub const SCALE_KEY: &str = "scale";
+/// Property holding an element's rotation in degrees, counter-clockwise.
+pub const ROTATION_KEY: &str = "rotation";
+
+/// Reverse index of [`BindTo`]: the children bound to each entity.
+///
+/// Kept in sync by [`sync_bind_index`], which the host should run once per
+/// frame alongside [`sync_timeline_index`](crate::timeline::index::sync_timeline_index).
+/// Use [`build`](Self::build) for an up-to-date copy outside of systems.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct BindIndex {
+    parents: HashMap<Entity, Entity>,
+    children: HashMap<Entity, Vec<Entity>>,
+}
+
+impl BindIndex {
+    /// Index every [`BindTo`] currently in `world`.
+    pub fn build(world: &World) -> Self {
+        let mut index = Self::default();
+        if let Some(mut query) = world.try_query::<(Entity, &BindTo)>() {
+            for (child, bind) in query.iter(world) {
+                index.insert(child, bind.id);
+            }
+        }
+        index
+    }
+
+    /// Entities bound directly to `parent`, in entity order.
+    pub fn children(&self, parent: Entity) -> &[Entity] {
+        self.children.get(&parent).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn parent(&self, child: Entity) -> Option<Entity> {
+        self.parents.get(&child).copied()
+    }
+
+    /// Everything bound to `root` directly or through other children,
+    /// nearest first. Stops at cycles instead of looping.
+    pub fn descendants(&self, root: Entity) -> Vec<Entity> {
+        let mut seen = HashSet::from([root]);
+        let mut out = Vec::new();
+        let mut next = 0;
+        out.extend(self.children(root).iter().filter(|c| seen.insert(**c)));
+        while let Some(&parent) = out.get(next) {
+            next += 1;
+            let children: Vec<Entity> = self
+                .children(parent)
+                .iter()
+                .copied()
+                .filter(|c| seen.insert(*c))
+                .collect();
+            out.extend(children);
+        }
+        out
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    fn insert(&mut self, child: Entity, parent: Entity) {
+        if self.parents.get(&child) == Some(&parent) {
+            return;
+        }
+        self.remove(child);
+        self.parents.insert(child, parent);
+        let siblings = self.children.entry(parent).or_default();
+        let at = siblings.partition_point(|s| *s < child);
+        siblings.insert(at, child);
+    }
+
+    fn remove(&mut self, child: Entity) {
+        let Some(parent) = self.parents.remove(&child) else {
+            return;
+        };
+        if let Some(siblings) = self.children.get_mut(&parent) {
+            siblings.retain(|s| *s != child);
+            if siblings.is_empty() {
+                self.children.remove(&parent);
+            }
+        }
+    }
+}
+
+/// Apply this frame's [`BindTo`] inserts, changes and removals to the
+/// [`BindIndex`].
+pub fn sync_bind_index(
+    mut index: ResMut<BindIndex>,
+    changed: Query<(Entity, &BindTo), Changed<BindTo>>,
+    mut removed: RemovedComponents<BindTo>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+    for (child, bind) in &changed {
+        index.insert(child, bind.id);
+    }
+}
+
+impl BindTo {
+    /// Bind `child` to `parent`, replacing any previous parent.
+    ///
+    /// Fails with [`LunarisError::CycleDetected`] if `parent` is `child` or
+    /// already bound to it, directly or further up.
+    pub fn attach(world: &mut World, child: Entity, parent: Entity) -> Result {
+        let mut path = vec![child, parent];
+        let mut current = parent;
+        while current != child {
+            match world.get::<BindTo>(current) {
+                Some(bind) if !path[1..].contains(&bind.id) => {
+                    current = bind.id;
+                    path.push(current);
+                }
+                // An existing cycle further up does not involve `child`.
+                _ => break,
+            }
+        }
+        if current == child {
+            return Err(LunarisError::CycleDetected {
+                path: path.iter().map(Entity::to_string).collect(),
+            });
+        }
+        world
+            .get_entity_mut(child)
+            .map_err(|_| LunarisError::NotFound {
+                item: format!("Entity: {child}"),
+            })?
+            .insert(Self { id: parent });
+        Ok(())
+    }
+
+    /// Unbind `child` from its parent, if it has one.
+    pub fn detach(world: &mut World, child: Entity) {
+        if let Ok(mut entity) = world.get_entity_mut(child) {
+            entity.remove::<Self>();
+        }
+    }
+}
+
+/// Fail with [`LunarisError::CycleDetected`] if any entity is bound to
+/// itself, directly or through its parents.
+pub fn check_bind_cycles(world: &World) -> Result {
+    let Some(mut query) = world.try_query::<(Entity, &BindTo)>() else {
+        return Ok(());
+    };
+    let edges: HashMap<Entity, Vec<Entity>> = query
+        .iter(world)
+        .map(|(child, bind)| (child, vec![bind.id]))
+        .collect();
+    match find_cycle(&edges) {
+        Some(cycle) => Err(LunarisError::CycleDetected {
+            path: cycle.iter().map(Entity::to_string).collect(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Position, scale and rotation of an element in 2D.
+///
+/// Points are scaled first, then rotated, then moved by `translation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2D {
+    pub translation: [f64; 2],
+    pub scale: [f64; 2],
+    /// Degrees, counter-clockwise.
+    pub rotation: f64,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform2D {
+    pub const IDENTITY: Self = Self {
+        translation: [0.0, 0.0],
+        scale: [1.0, 1.0],
+        rotation: 0.0,
+    };
+
+    /// Transform stored in the [`POSITION_KEY`], [`SCALE_KEY`] and
+    /// [`ROTATION_KEY`] properties, evaluated at `tick`. Missing or
+    /// mistyped properties keep their identity value.
+    pub fn from_properties(properties: &Properties, tick: u64) -> Self {
+        let mut transform = Self::IDENTITY;
+        if let Some(position) = properties
+            .value_at(POSITION_KEY, tick)
+            .and_then(|v| v.as_vec2())
+        {
+            transform.translation = position;
+        }
+        if let Some(scale) = properties.value_at(SCALE_KEY, tick) {
+            if let Some(scale) = scale.as_vec2() {
+                transform.scale = scale;
+            } else if let Some(uniform) = scale.as_f64() {
+                transform.scale = [uniform, uniform];
+            }
+        }
+        if let Some(rotation) = properties
+            .value_at(ROTATION_KEY, tick)
+            .and_then(|v| v.as_f64())
+        {
+            transform.rotation = rotation;
+        }
+        transform
+    }
+
+    pub fn apply(&self, point: [f64; 2]) -> [f64; 2] {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let [x, y] = [point[0] * self.scale[0], point[1] * self.scale[1]];
+        [
+            x * cos - y * sin + self.translation[0],
+            x * sin + y * cos + self.translation[1],
+        ]
+    }
+
+    /// `self` placed inside `parent`, as one transform.
+    ///
+    /// Scales multiply per axis, so a non-uniform parent scale over a
+    /// rotated child is approximated without shear.
+    pub fn within(&self, parent: &Self) -> Self {
+        Self {
+            translation: parent.apply(self.translation),
+            scale: [
+                self.scale[0] * parent.scale[0],
+                self.scale[1] * parent.scale[1],
+            ],
+            rotation: self.rotation + parent.rotation,
+        }
+    }
+}
+
+/// Transform of `entity` at `tick` with every parent it is bound to
+/// applied.
+pub fn world_transform(world: &World, entity: Entity, tick: u64) -> Result<Transform2D> {
+    let mut chain = vec![entity];
+    let mut current = entity;
+    while let Some(bind) = world.get::<BindTo>(current) {
+        if let Some(from) = chain.iter().position(|e| *e == bind.id) {
+            let mut cycle: Vec<String> = chain[from..].iter().map(Entity::to_string).collect();
+            cycle.push(bind.id.to_string());
+            return Err(LunarisError::CycleDetected { path: cycle });
+        }
+        current = bind.id;
+        chain.push(current);
+    }
+    Ok(chain.iter().rev().fold(Transform2D::IDENTITY, |parent, e| {
+        world
+            .get::<Properties>(*e)
+            .map_or(Transform2D::IDENTITY, |p| {
+                Transform2D::from_properties(p, tick)
+            })
+            .within(&parent)
+    }))
+}
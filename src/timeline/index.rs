@@ -0,0 +1,316 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bevy_ecs::{
+    entity::Entity,
+    query::{Changed, Or},
+    removal_detection::RemovedComponents,
+    resource::Resource,
+    system::{Query, ResMut},
+};
+
+use crate::timeline::{TimelineSpan, elements::TimelineElement, sequence::InSequence};
+
+/// Per-track interval index over every [`TimelineElement`].
+///
+/// Tracks are keyed by their [`InSequence`]; the methods without `_in` look
+/// at the root timeline.
+///
+/// Answers "what is under tick T" and "what overlaps this span" in
+/// logarithmic time. Kept in sync by [`sync_timeline_index`], which the host
+/// should run once per frame after plugins have edited the timeline; results
+/// reflect the world as of its last run. The first run picks up every
+/// element that already exists.
+#[derive(Resource, Default)]
+pub struct TimelineIndex {
+    entries: HashMap<Entity, TrackKey>,
+    tracks: BTreeMap<TrackKey, TrackIndex>,
+    dirty: HashSet<TrackKey>,
+}
+
+type TrackKey = (Option<Entity>, u64);
+
+impl TimelineIndex {
+    /// Elements on `track` that contain `tick`, ordered by start.
+    pub fn at(&self, track: u64, tick: u64) -> Vec<Entity> {
+        self.at_in(None, track, tick)
+    }
+
+    /// Elements on `track` of `sequence` that contain `tick`, ordered by
+    /// start.
+    pub fn at_in(&self, sequence: Option<Entity>, track: u64, tick: u64) -> Vec<Entity> {
+        let Some(track) = self.tracks.get(&(sequence, track)) else {
+            return Vec::new();
+        };
+        let mut hits = Vec::new();
+        track.tree.point(tick, &mut hits);
+        sorted(hits)
+    }
+
+    /// Elements on any track that contain `tick`, as `(track, entity)` pairs
+    /// ordered by track and then by start.
+    pub fn at_all(&self, tick: u64) -> Vec<(u64, Entity)> {
+        self.at_all_in(None, tick)
+    }
+
+    /// Like [`at_all`](Self::at_all) within `sequence`.
+    pub fn at_all_in(&self, sequence: Option<Entity>, tick: u64) -> Vec<(u64, Entity)> {
+        self.tracks_in(sequence)
+            .flat_map(|track| {
+                self.at_in(sequence, track, tick)
+                    .into_iter()
+                    .map(move |e| (track, e))
+            })
+            .collect()
+    }
+
+    /// Elements on `track` sharing at least one tick with `span`, ordered by
+    /// start.
+    pub fn overlapping(&self, track: u64, span: &TimelineSpan) -> Vec<Entity> {
+        self.overlapping_in(None, track, span)
+    }
+
+    /// Like [`overlapping`](Self::overlapping) within `sequence`.
+    pub fn overlapping_in(
+        &self,
+        sequence: Option<Entity>,
+        track: u64,
+        span: &TimelineSpan,
+    ) -> Vec<Entity> {
+        let Some(track) = self.tracks.get(&(sequence, track)) else {
+            return Vec::new();
+        };
+        if span.is_empty() {
+            return Vec::new();
+        }
+        let mut hits = Vec::new();
+        track.tree.range(span.start(), span.end(), &mut hits);
+        sorted(hits)
+    }
+
+    /// Track and span of `entity` as last seen by the index.
+    pub fn get(&self, entity: Entity) -> Option<(u64, TimelineSpan)> {
+        let key = *self.entries.get(&entity)?;
+        let span = *self.tracks.get(&key)?.members.get(&entity)?;
+        Some((key.1, span))
+    }
+
+    /// Root timeline track numbers that hold at least one element, bottom to
+    /// top.
+    pub fn tracks(&self) -> impl Iterator<Item = u64> + '_ {
+        self.tracks_in(None)
+    }
+
+    /// Track numbers of `sequence` that hold at least one element, bottom to
+    /// top.
+    pub fn tracks_in(&self, sequence: Option<Entity>) -> impl Iterator<Item = u64> + '_ {
+        self.tracks
+            .range((sequence, 0)..=(sequence, u64::MAX))
+            .map(|((_, track), _)| *track)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn upsert(&mut self, entity: Entity, track: TrackKey, span: TimelineSpan) {
+        if let Some(previous) = self.entries.insert(entity, track)
+            && previous != track
+        {
+            self.detach(entity, previous);
+        }
+        self.tracks
+            .entry(track)
+            .or_default()
+            .members
+            .insert(entity, span);
+        self.dirty.insert(track);
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        if let Some(track) = self.entries.remove(&entity) {
+            self.detach(entity, track);
+        }
+    }
+
+    fn detach(&mut self, entity: Entity, track: TrackKey) {
+        if let Some(index) = self.tracks.get_mut(&track) {
+            index.members.remove(&entity);
+            self.dirty.insert(track);
+        }
+    }
+
+    /// Rebuild the trees of every track touched since the last flush.
+    fn flush(&mut self) {
+        for track in self.dirty.drain() {
+            let Some(index) = self.tracks.get_mut(&track) else {
+                continue;
+            };
+            if index.members.is_empty() {
+                self.tracks.remove(&track);
+                continue;
+            }
+            index.tree = IntervalTree::build(
+                index
+                    .members
+                    .iter()
+                    .filter(|(_, span)| !span.is_empty())
+                    .map(|(&entity, span)| Interval {
+                        start: span.start(),
+                        end: span.end(),
+                        entity,
+                    })
+                    .collect(),
+            );
+        }
+    }
+}
+
+/// Apply this frame's [`TimelineElement`] and [`InSequence`] inserts,
+/// changes and removals to the [`TimelineIndex`]. Only tracks that actually
+/// changed are rebuilt.
+#[allow(clippy::type_complexity)]
+pub fn sync_timeline_index(
+    mut index: ResMut<TimelineIndex>,
+    elements: Query<(&TimelineElement, Option<&InSequence>)>,
+    changed: Query<
+        (Entity, &TimelineElement, Option<&InSequence>),
+        Or<(Changed<TimelineElement>, Changed<InSequence>)>,
+    >,
+    mut removed: RemovedComponents<TimelineElement>,
+    mut left: RemovedComponents<InSequence>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+    for entity in left.read() {
+        if let Ok((element, None)) = elements.get(entity) {
+            index.upsert(entity, (None, element.track_num), element.position);
+        }
+    }
+    for (entity, element, parent) in &changed {
+        let key = (parent.map(|p| p.sequence), element.track_num);
+        index.upsert(entity, key, element.position);
+    }
+    index.flush();
+}
+
+#[derive(Default)]
+struct TrackIndex {
+    members: HashMap<Entity, TimelineSpan>,
+    tree: IntervalTree,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    start: u64,
+    end: u64,
+    entity: Entity,
+}
+
+/// Centered interval tree over non-empty half-open intervals.
+///
+/// Each node keeps the intervals containing its center twice, sorted by
+/// ascending start and by descending end, so a query only walks the
+/// intervals it reports plus one root-to-leaf path.
+#[derive(Default)]
+struct IntervalTree {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+
+struct Node {
+    center: u64,
+    by_start: Vec<Interval>,
+    by_end: Vec<Interval>,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl IntervalTree {
+    fn build(intervals: Vec<Interval>) -> Self {
+        let mut tree = Self::default();
+        tree.root = tree.build_node(intervals);
+        tree
+    }
+
+    fn build_node(&mut self, intervals: Vec<Interval>) -> Option<usize> {
+        if intervals.is_empty() {
+            return None;
+        }
+        // The median start always lands in this node, so each level at least
+        // halves the work left for its children.
+        let mut starts: Vec<u64> = intervals.iter().map(|i| i.start).collect();
+        let mid = starts.len() / 2;
+        let center = *starts.select_nth_unstable(mid).1;
+
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        let mut here = Vec::new();
+        for interval in intervals {
+            if interval.end <= center {
+                left.push(interval);
+            } else if interval.start > center {
+                right.push(interval);
+            } else {
+                here.push(interval);
+            }
+        }
+
+        let left = self.build_node(left);
+        let right = self.build_node(right);
+        let mut by_start = here.clone();
+        by_start.sort_by_key(|i| i.start);
+        let mut by_end = here;
+        by_end.sort_by_key(|i| std::cmp::Reverse(i.end));
+
+        self.nodes.push(Node {
+            center,
+            by_start,
+            by_end,
+            left,
+            right,
+        });
+        Some(self.nodes.len() - 1)
+    }
+
+    fn point(&self, tick: u64, out: &mut Vec<Interval>) {
+        let mut next = self.root;
+        while let Some(id) = next {
+            let node = &self.nodes[id];
+            if tick < node.center {
+                out.extend(node.by_start.iter().take_while(|i| i.start <= tick));
+                next = node.left;
+            } else {
+                out.extend(node.by_end.iter().take_while(|i| i.end > tick));
+                next = node.right;
+            }
+        }
+    }
+
+    fn range(&self, start: u64, end: u64, out: &mut Vec<Interval>) {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if end <= node.center {
+                out.extend(node.by_start.iter().take_while(|i| i.start < end));
+                stack.extend(node.left);
+            } else if start > node.center {
+                out.extend(node.by_end.iter().take_while(|i| i.end > start));
+                stack.extend(node.right);
+            } else {
+                out.extend(node.by_start.iter());
+                stack.extend(node.left);
+                stack.extend(node.right);
+            }
+        }
+    }
+}
+
+fn sorted(mut hits: Vec<Interval>) -> Vec<Entity> {
+    hits.sort_by_key(|i| (i.start, i.entity));
+    hits.into_iter().map(|i| i.entity).collect()
+}
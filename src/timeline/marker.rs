@@ -0,0 +1,257 @@
+use std::fmt::Write;
+
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    prelude::*,
+    timeline::{Playhead, Sequence, TimelineSpan},
+};
+
+/// Color given to markers and regions that don't pick one. RGBA.
+pub const DEFAULT_MARKER_COLOR: [u8; 4] = [0xF2, 0xB1, 0x34, 0xFF];
+
+/// A named point on the timeline.
+///
+/// Markers with a `track` belong to that track only; the others apply to
+/// the whole timeline.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Marker {
+    pub tick: u64,
+    pub name: String,
+    /// RGBA.
+    pub color: [u8; 4],
+    pub note: String,
+    pub track: Option<u64>,
+}
+
+/// A named span of the timeline. Scoped like [`Marker`].
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub span: TimelineSpan,
+    pub name: String,
+    /// RGBA.
+    pub color: [u8; 4],
+    pub note: String,
+    pub track: Option<u64>,
+}
+
+/// Whether something scoped to `track` is visible from `scope`. A `None`
+/// scope sees everything; a track scope sees its own items and global ones.
+fn in_scope(track: Option<u64>, scope: Option<u64>) -> bool {
+    scope.is_none() || track.is_none() || track == scope
+}
+
+impl Marker {
+    pub fn new(tick: u64, name: impl Into<String>) -> Self {
+        Self {
+            tick,
+            name: name.into(),
+            color: DEFAULT_MARKER_COLOR,
+            note: String::new(),
+            track: None,
+        }
+    }
+
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = note.into();
+        self
+    }
+
+    pub fn on_track(mut self, track: u64) -> Self {
+        self.track = Some(track);
+        self
+    }
+
+    /// Markers visible from `scope`, ordered by tick.
+    pub fn all(world: &World, scope: Option<u64>) -> Vec<(Entity, &Marker)> {
+        let Some(mut query) = world.try_query::<(Entity, &Marker)>() else {
+            return Vec::new();
+        };
+        let mut markers: Vec<_> = query
+            .iter(world)
+            .filter(|(_, marker)| in_scope(marker.track, scope))
+            .collect();
+        markers.sort_by_key(|(entity, marker)| (marker.tick, *entity));
+        markers
+    }
+
+    /// First marker strictly after `tick`.
+    pub fn next(world: &World, tick: u64, scope: Option<u64>) -> Option<(Entity, &Marker)> {
+        Self::all(world, scope)
+            .into_iter()
+            .find(|(_, marker)| marker.tick > tick)
+    }
+
+    /// Last marker strictly before `tick`.
+    pub fn previous(world: &World, tick: u64, scope: Option<u64>) -> Option<(Entity, &Marker)> {
+        Self::all(world, scope)
+            .into_iter()
+            .rev()
+            .find(|(_, marker)| marker.tick < tick)
+    }
+
+    /// First marker after the position of `playhead`.
+    pub fn next_from_playhead(
+        world: &World,
+        playhead: Entity,
+        scope: Option<u64>,
+    ) -> Result<Option<(Entity, &Marker)>> {
+        Ok(Self::next(world, Playhead::tick(world, playhead)?, scope))
+    }
+
+    /// Last marker before the position of `playhead`.
+    pub fn previous_from_playhead(
+        world: &World,
+        playhead: Entity,
+        scope: Option<u64>,
+    ) -> Result<Option<(Entity, &Marker)>> {
+        Ok(Self::previous(
+            world,
+            Playhead::tick(world, playhead)?,
+            scope,
+        ))
+    }
+}
+
+impl Region {
+    pub fn new(span: TimelineSpan, name: impl Into<String>) -> Self {
+        Self {
+            span,
+            name: name.into(),
+            color: DEFAULT_MARKER_COLOR,
+            note: String::new(),
+            track: None,
+        }
+    }
+
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = note.into();
+        self
+    }
+
+    pub fn on_track(mut self, track: u64) -> Self {
+        self.track = Some(track);
+        self
+    }
+
+    /// Regions visible from `scope`, ordered by start.
+    pub fn all(world: &World, scope: Option<u64>) -> Vec<(Entity, &Region)> {
+        let Some(mut query) = world.try_query::<(Entity, &Region)>() else {
+            return Vec::new();
+        };
+        let mut regions: Vec<_> = query
+            .iter(world)
+            .filter(|(_, region)| in_scope(region.track, scope))
+            .collect();
+        regions.sort_by_key(|(entity, region)| (region.span.start(), region.span.end(), *entity));
+        regions
+    }
+
+    /// Regions visible from `scope` that contain `tick`.
+    pub fn at(world: &World, tick: u64, scope: Option<u64>) -> Vec<(Entity, &Region)> {
+        let mut regions = Self::all(world, scope);
+        regions.retain(|(_, region)| region.span.contains(tick));
+        regions
+    }
+}
+
+/// One entry of a chapter list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    pub span: TimelineSpan,
+    pub title: String,
+}
+
+/// Chapters from the timeline-wide markers and regions, ordered by start.
+///
+/// A region is a chapter of its own. A marker starts a chapter that runs
+/// until the next chapter starts, or until the last element of the root
+/// timeline ends.
+pub fn chapters(world: &World) -> Vec<Chapter> {
+    let timeline_end = Sequence::duration(world, None);
+
+    let markers: Vec<_> = Marker::all(world, None)
+        .into_iter()
+        .filter(|(_, marker)| marker.track.is_none())
+        .map(|(_, marker)| (marker.tick, marker.name.clone()))
+        .collect();
+    let mut chapters: Vec<Chapter> = Region::all(world, None)
+        .into_iter()
+        .filter(|(_, region)| region.track.is_none())
+        .map(|(_, region)| Chapter {
+            span: region.span,
+            title: region.name.clone(),
+        })
+        .collect();
+
+    let mut starts: Vec<u64> = markers
+        .iter()
+        .map(|(tick, _)| *tick)
+        .chain(chapters.iter().map(|c| c.span.start()))
+        .collect();
+    starts.sort_unstable();
+    for (tick, title) in markers {
+        let next = starts.iter().copied().find(|start| *start > tick);
+        let end = next.unwrap_or(timeline_end).max(tick);
+        chapters.push(Chapter {
+            span: TimelineSpan::new_unchecked(tick, end),
+            title,
+        });
+    }
+    chapters.sort_by_key(|c| (c.span.start(), c.span.end()));
+    chapters
+}
+
+/// Split `ticks` into hours, minutes, seconds and milliseconds.
+fn clock(ticks: u64, tps: u64) -> (u64, u64, u64, u64) {
+    let millis = (ticks as u128 * 1000 / tps.max(1) as u128) as u64;
+    (
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000,
+    )
+}
+
+/// One `HH:MM:SS Title` line per chapter, the format video platforms accept
+/// in descriptions.
+pub fn chapters_to_text(chapters: &[Chapter], tps: u64) -> String {
+    let mut out = String::new();
+    for chapter in chapters {
+        let (h, m, s, _) = clock(chapter.span.start(), tps);
+        let _ = writeln!(out, "{h:02}:{m:02}:{s:02} {}", chapter.title);
+    }
+    out
+}
+
+/// WebVTT chapter track.
+pub fn chapters_to_webvtt(chapters: &[Chapter], tps: u64) -> String {
+    let mut out = String::from("WEBVTT\n");
+    for (i, chapter) in chapters.iter().enumerate() {
+        let (h0, m0, s0, ms0) = clock(chapter.span.start(), tps);
+        let (h1, m1, s1, ms1) = clock(chapter.span.end(), tps);
+        // Cue text may not contain "-->" or blank lines, and a lone CR ends a
+        // line as well as LF does.
+        let title = chapter
+            .title
+            .replace("-->", "->")
+            .replace(['\r', '\n'], " ");
+        let _ = write!(
+            out,
+            "\n{}\n{h0:02}:{m0:02}:{s0:02}.{ms0:03} --> {h1:02}:{m1:02}:{s1:02}.{ms1:03}\n{title}\n",
+            i + 1
+        );
+    }
+    out
+}
@@ -1,14 +1,29 @@
-use bevy_ecs::component::Component;
-
+pub mod edit;
 pub mod elements;
+pub mod hierarchy;
+pub mod index;
+pub mod marker;
+pub mod playhead;
+pub mod sequence;
+pub mod snap;
+pub mod span;
+pub mod timebase;
+pub mod timecode;
+pub mod track;
+pub mod transition;
+pub mod transport;
+pub mod validate;
 
-#[derive(Debug)]
-pub struct TimelineSpan {
-    pub start: u64,
-    pub end: u64,
-}
-
-#[derive(Component)]
-pub struct Playhead {
-    pub current: u64,
-}
+pub use edit::{TimelineEdit, TimelineEditToken};
+pub use hierarchy::{BindIndex, Transform2D};
+pub use index::TimelineIndex;
+pub use marker::{Marker, Region};
+pub use playhead::{Playhead, PlayheadTarget};
+pub use sequence::{CompoundClip, InSequence, Sequence};
+pub use span::TimelineSpan;
+pub use timebase::Timebase;
+pub use timecode::{FrameRate, Timecode};
+pub use track::{Track, TrackKind};
+pub use transition::{Transition, TransitionKind};
+pub use transport::Transport;
+pub use validate::Validator;
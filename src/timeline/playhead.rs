@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+
+use crate::prelude::*;
+
+/// A position on whatever its [`PlayheadTarget`] shows.
+///
+/// Every viewer owns one playhead entity: the program monitor on the root
+/// timeline, source monitors on media, reference viewers on sequences. Give
+/// it a [`Transport`](crate::timeline::Transport) to play it back.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Playhead {
+    pub current: u64,
+}
+
+/// What a [`Playhead`] moves over. Playheads without one are on the root
+/// timeline.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Default)]
+pub enum PlayheadTarget {
+    #[default]
+    Root,
+    /// A nested [`Sequence`](crate::timeline::Sequence).
+    Sequence(Entity),
+    /// A source file, with the playhead counting ticks from its start.
+    Media(PathBuf),
+}
+
+impl PlayheadTarget {
+    /// Sequence shown by this target, `Some(None)` for the root timeline and
+    /// `None` for media.
+    pub fn sequence(&self) -> Option<Option<Entity>> {
+        match self {
+            Self::Root => Some(None),
+            Self::Sequence(sequence) => Some(Some(*sequence)),
+            Self::Media(_) => None,
+        }
+    }
+}
+
+impl Playhead {
+    /// Spawn a playhead at tick `0` of `target`.
+    pub fn spawn(world: &mut World, target: PlayheadTarget) -> Entity {
+        world.spawn((Self { current: 0 }, target)).id()
+    }
+
+    /// Position of `playhead`.
+    pub fn tick(world: &World, playhead: Entity) -> Result<u64> {
+        Ok(Self::get(world, playhead)?.current)
+    }
+
+    /// Move `playhead` to `tick`.
+    pub fn seek(world: &mut World, playhead: Entity, tick: u64) -> Result {
+        let mut current = world
+            .get_mut::<Self>(playhead)
+            .ok_or_else(|| not_found(playhead))?;
+        current.current = tick;
+        Ok(())
+    }
+
+    /// Target of `playhead`.
+    pub fn target(world: &World, playhead: Entity) -> Result<PlayheadTarget> {
+        Self::get(world, playhead)?;
+        Ok(world
+            .get::<PlayheadTarget>(playhead)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Every playhead on `target`, in entity order.
+    pub fn on(world: &World, target: &PlayheadTarget) -> Vec<Entity> {
+        let Some(mut query) = world.try_query::<(Entity, &Playhead)>() else {
+            return Vec::new();
+        };
+        let mut playheads: Vec<Entity> = query
+            .iter(world)
+            .map(|(entity, _)| entity)
+            .filter(|entity| {
+                world
+                    .get::<PlayheadTarget>(*entity)
+                    .unwrap_or(&PlayheadTarget::Root)
+                    == target
+            })
+            .collect();
+        playheads.sort();
+        playheads
+    }
+
+    fn get(world: &World, playhead: Entity) -> Result<&Self> {
+        world
+            .get::<Self>(playhead)
+            .ok_or_else(|| not_found(playhead))
+    }
+}
+
+fn not_found(playhead: Entity) -> LunarisError {
+    LunarisError::NotFound {
+        item: format!("Playhead for Entity: {playhead}"),
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    prelude::*,
+    timeline::{
+        TimelineIndex, Track, TrackKind,
+        elements::{TimelineElement, remap},
+    },
+    util::graph::find_cycle,
+};
+
+/// A timeline of its own that can be placed inside another one through a
+/// [`CompoundClip`]. Its tracks and elements carry [`InSequence`].
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sequence {
+    pub name: String,
+}
+
+/// Puts a track or element into a nested [`Sequence`]. Entities without it
+/// belong to the root timeline.
+///
+/// Track indices are only unique within a sequence, and an element belongs
+/// to the track with its `track_num` in the same sequence.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InSequence {
+    pub sequence: Entity,
+}
+
+/// Element that plays another [`Sequence`].
+///
+/// The element's [`SourceOffset`] is the sequence tick shown at the
+/// element's start, so slipping, trimming and [`TimeRemap`] work on a
+/// compound clip like they do on media.
+///
+/// [`SourceOffset`]: crate::timeline::elements::SourceOffset
+/// [`TimeRemap`]: crate::timeline::elements::TimeRemap
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompoundClip {
+    pub sequence: Entity,
+}
+
+/// Sequence `entity` belongs to, `None` for the root timeline.
+pub fn sequence_of(world: &World, entity: Entity) -> Option<Entity> {
+    world.get::<InSequence>(entity).map(|s| s.sequence)
+}
+
+impl Sequence {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    pub fn spawn(world: &mut World, name: impl Into<String>) -> Entity {
+        world.spawn(Self::new(name)).id()
+    }
+
+    /// End of the last element in `sequence`, or `0` if it is empty.
+    pub fn duration(world: &World, sequence: Option<Entity>) -> u64 {
+        let Some(mut query) = world.try_query::<(Entity, &TimelineElement)>() else {
+            return 0;
+        };
+        query
+            .iter(world)
+            .filter(|(entity, _)| sequence_of(world, *entity) == sequence)
+            .map(|(_, element)| element.position.end())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl CompoundClip {
+    /// Make `element` play `sequence`.
+    ///
+    /// Fails with [`LunarisError::CycleDetected`] if `sequence` would end up
+    /// containing itself, directly or through other compound clips.
+    pub fn attach(world: &mut World, element: Entity, sequence: Entity) -> Result {
+        if world.get::<Sequence>(sequence).is_none() {
+            return Err(LunarisError::NotFound {
+                item: format!("Sequence for Entity: {sequence}"),
+            });
+        }
+        let mut edges = nesting(world);
+        edges.retain(|_, children| {
+            children.retain(|(clip, _)| *clip != element);
+            !children.is_empty()
+        });
+        if let Some(parent) = sequence_of(world, element) {
+            edges.entry(parent).or_default().push((element, sequence));
+        }
+        check(world, &edges)?;
+        world
+            .get_entity_mut(element)
+            .map_err(|_| LunarisError::NotFound {
+                item: format!("Timeline element for Entity: {element}"),
+            })?
+            .insert(Self { sequence });
+        Ok(())
+    }
+}
+
+/// Fail with [`LunarisError::CycleDetected`] if any sequence contains
+/// itself.
+pub fn check_cycles(world: &World) -> Result {
+    check(world, &nesting(world))
+}
+
+/// Compound clips of each sequence and the sequence they play.
+fn nesting(world: &World) -> HashMap<Entity, Vec<(Entity, Entity)>> {
+    let mut edges: HashMap<Entity, Vec<(Entity, Entity)>> = HashMap::new();
+    let Some(mut query) = world.try_query::<(Entity, &CompoundClip)>() else {
+        return edges;
+    };
+    for (clip, compound) in query.iter(world) {
+        // Nothing can contain the root timeline, so its clips never close a
+        // cycle.
+        if let Some(parent) = sequence_of(world, clip) {
+            edges
+                .entry(parent)
+                .or_default()
+                .push((clip, compound.sequence));
+        }
+    }
+    edges
+}
+
+fn check(world: &World, edges: &HashMap<Entity, Vec<(Entity, Entity)>>) -> Result {
+    let graph = edges
+        .iter()
+        .map(|(parent, children)| (*parent, children.iter().map(|(_, seq)| *seq).collect()))
+        .collect();
+    match find_cycle(&graph) {
+        Some(cycle) => Err(LunarisError::CycleDetected {
+            path: cycle.into_iter().map(|seq| label(world, seq)).collect(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn label(world: &World, sequence: Entity) -> String {
+    match world.get::<Sequence>(sequence) {
+        Some(s) => format!("{} ({sequence})", s.name),
+        None => sequence.to_string(),
+    }
+}
+
+/// An element to composite, found by [`resolve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedElement {
+    pub entity: Entity,
+    /// Tick inside the sequence that holds the element. Pass this to the
+    /// renderer, as the element's keyframes are in that sequence's time.
+    pub local_tick: u64,
+    /// Position in the element's source media.
+    pub source_tick: u64,
+    /// Compound clips passed through to reach the element, outermost first.
+    pub path: Vec<Entity>,
+}
+
+/// Elements of `kind` showing at `tick` of the root timeline, bottom to top.
+///
+/// Only active tracks are considered. Compound clips on them are replaced by
+/// what their sequence shows at the mapped tick, recursively. A compound
+/// clip only passes through tracks of `kind`.
+///
+/// Elements come from the [`TimelineIndex`] when the world has one, so they
+/// reflect its last sync; otherwise every element is scanned at each level.
+pub fn resolve(world: &World, tick: u64, kind: TrackKind) -> Result<Vec<ResolvedElement>> {
+    resolve_in(world, None, tick, kind)
+}
+
+/// Like [`resolve`], starting from `sequence`.
+pub fn resolve_in(
+    world: &World,
+    sequence: Option<Entity>,
+    tick: u64,
+    kind: TrackKind,
+) -> Result<Vec<ResolvedElement>> {
+    let mut out = Vec::new();
+    let mut stack: Vec<Entity> = sequence.into_iter().collect();
+    descend(
+        world,
+        sequence,
+        tick,
+        kind,
+        &mut Vec::new(),
+        &mut stack,
+        &mut out,
+    )?;
+    Ok(out)
+}
+
+fn descend(
+    world: &World,
+    sequence: Option<Entity>,
+    tick: u64,
+    kind: TrackKind,
+    path: &mut Vec<Entity>,
+    stack: &mut Vec<Entity>,
+    out: &mut Vec<ResolvedElement>,
+) -> Result {
+    let index = world.get_resource::<TimelineIndex>();
+    let scanned = match index {
+        Some(_) => Vec::new(),
+        None => showing(world, sequence, tick),
+    };
+
+    for (_, track) in Track::active_in(world, sequence, kind) {
+        let on_track: Vec<Entity> = match index {
+            Some(index) => index.at_in(sequence, track.index(), tick),
+            None => scanned
+                .iter()
+                .filter(|(num, ..)| *num == track.index())
+                .map(|(.., entity)| *entity)
+                .collect(),
+        };
+        for entity in on_track {
+            let source_tick = remap::source_tick(world, entity, tick).unwrap_or(0);
+            let Some(compound) = world.get::<CompoundClip>(entity) else {
+                out.push(ResolvedElement {
+                    entity,
+                    local_tick: tick,
+                    source_tick,
+                    path: path.clone(),
+                });
+                continue;
+            };
+            if let Some(from) = stack.iter().position(|s| *s == compound.sequence) {
+                let mut cycle: Vec<String> =
+                    stack[from..].iter().map(|s| label(world, *s)).collect();
+                cycle.push(label(world, compound.sequence));
+                return Err(LunarisError::CycleDetected { path: cycle });
+            }
+            path.push(entity);
+            stack.push(compound.sequence);
+            descend(
+                world,
+                Some(compound.sequence),
+                source_tick,
+                kind,
+                path,
+                stack,
+                out,
+            )?;
+            stack.pop();
+            path.pop();
+        }
+    }
+    Ok(())
+}
+
+/// Elements of `sequence` containing `tick`, as `(track, start, entity)`
+/// sorted. Used when the world has no [`TimelineIndex`].
+fn showing(world: &World, sequence: Option<Entity>, tick: u64) -> Vec<(u64, u64, Entity)> {
+    let Some(mut query) = world.try_query::<(Entity, &TimelineElement)>() else {
+        return Vec::new();
+    };
+    let mut showing: Vec<(u64, u64, Entity)> = query
+        .iter(world)
+        .filter(|(entity, element)| {
+            element.position.contains(tick) && sequence_of(world, *entity) == sequence
+        })
+        .map(|(entity, element)| (element.track_num, element.position.start(), entity))
+        .collect();
+    showing.sort_unstable();
+    showing
+}
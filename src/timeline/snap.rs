@@ -0,0 +1,230 @@
+use bevy_ecs::{entity::Entity, world::World};
+
+use crate::timeline::{
+    FrameRate, Marker, Playhead, PlayheadTarget, Region, Timebase, TimelineIndex, TimelineSpan,
+    elements::TimelineElement, sequence::sequence_of,
+};
+
+/// How far a candidate may move to snap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapTolerance {
+    Ticks(u64),
+    /// Screen distance, for pointer interactions. `ticks_per_pixel` is the
+    /// current zoom of the timeline widget.
+    Pixels {
+        pixels: f64,
+        ticks_per_pixel: f64,
+    },
+}
+
+impl SnapTolerance {
+    pub fn ticks(self) -> u64 {
+        match self {
+            Self::Ticks(ticks) => ticks,
+            Self::Pixels {
+                pixels,
+                ticks_per_pixel,
+            } => (pixels * ticks_per_pixel).round().max(0.0) as u64,
+        }
+    }
+}
+
+/// What a tick snapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapTarget {
+    ElementStart(Entity),
+    ElementEnd(Entity),
+    Marker(Entity),
+    RegionStart(Entity),
+    RegionEnd(Entity),
+    Playhead(Entity),
+    /// Start of the given frame.
+    Frame(u64),
+}
+
+impl SnapTarget {
+    /// Lower wins when two targets are equally close.
+    fn priority(&self) -> u8 {
+        match self {
+            Self::Playhead(_) => 0,
+            Self::Marker(_) | Self::RegionStart(_) | Self::RegionEnd(_) => 1,
+            Self::ElementStart(_) | Self::ElementEnd(_) => 2,
+            Self::Frame(_) => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snap {
+    pub tick: u64,
+    pub target: SnapTarget,
+    /// Distance from the candidate, in ticks.
+    pub distance: u64,
+}
+
+/// Which targets [`snap`] considers.
+#[derive(Debug, Clone)]
+pub struct SnapOptions {
+    /// Sequence whose elements and playheads are considered. Markers and
+    /// regions only exist on the root timeline.
+    pub sequence: Option<Entity>,
+    pub elements: bool,
+    pub markers: bool,
+    pub playheads: bool,
+    /// Snap to frame boundaries of this rate.
+    pub frames: Option<FrameRate>,
+    /// Never snap to these, usually the elements being dragged.
+    pub ignore: Vec<Entity>,
+}
+
+impl Default for SnapOptions {
+    fn default() -> Self {
+        Self {
+            sequence: None,
+            elements: true,
+            markers: true,
+            playheads: true,
+            frames: None,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+impl SnapOptions {
+    pub fn in_sequence(mut self, sequence: Entity) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    pub fn frames(mut self, rate: FrameRate) -> Self {
+        self.frames = Some(rate);
+        self
+    }
+
+    pub fn ignore(mut self, entities: impl IntoIterator<Item = Entity>) -> Self {
+        self.ignore.extend(entities);
+        self
+    }
+}
+
+/// Closest target within `tolerance` of `candidate`.
+///
+/// Element edges come from the [`TimelineIndex`] when the world has one, so
+/// they reflect its last sync; otherwise every element is scanned. Equally
+/// close targets are preferred in the order playhead, marker or region,
+/// element edge, frame boundary.
+pub fn snap(
+    world: &World,
+    candidate: u64,
+    tolerance: SnapTolerance,
+    options: &SnapOptions,
+) -> Option<Snap> {
+    let tolerance = tolerance.ticks();
+    let low = candidate.saturating_sub(tolerance);
+    let high = candidate.saturating_add(tolerance);
+    let mut best: Option<Snap> = None;
+    let mut offer = |tick: u64, target: SnapTarget| {
+        if tick < low || tick > high {
+            return;
+        }
+        let snap = Snap {
+            tick,
+            target,
+            distance: tick.abs_diff(candidate),
+        };
+        if best.is_none_or(|b| {
+            (snap.distance, snap.target.priority()) < (b.distance, b.target.priority())
+        }) {
+            best = Some(snap);
+        }
+    };
+
+    if options.elements {
+        for (entity, span) in elements_near(world, options.sequence, low, high) {
+            if !options.ignore.contains(&entity) {
+                offer(span.start(), SnapTarget::ElementStart(entity));
+                offer(span.end(), SnapTarget::ElementEnd(entity));
+            }
+        }
+    }
+    if options.markers && options.sequence.is_none() {
+        for (entity, marker) in Marker::all(world, None) {
+            if !options.ignore.contains(&entity) {
+                offer(marker.tick, SnapTarget::Marker(entity));
+            }
+        }
+        for (entity, region) in Region::all(world, None) {
+            if !options.ignore.contains(&entity) {
+                offer(region.span.start(), SnapTarget::RegionStart(entity));
+                offer(region.span.end(), SnapTarget::RegionEnd(entity));
+            }
+        }
+    }
+    if options.playheads {
+        let target = match options.sequence {
+            Some(sequence) => PlayheadTarget::Sequence(sequence),
+            None => PlayheadTarget::Root,
+        };
+        for playhead in Playhead::on(world, &target) {
+            if let Ok(tick) = Playhead::tick(world, playhead) {
+                offer(tick, SnapTarget::Playhead(playhead));
+            }
+        }
+    }
+    if let Some(rate) = options.frames {
+        let tps = Timebase::of(world).tps();
+        let frame = rate.ticks_to_frame(candidate, tps);
+        for frame in [frame, frame + 1] {
+            offer(rate.frame_to_ticks(frame, tps), SnapTarget::Frame(frame));
+        }
+    }
+    best
+}
+
+/// Snap a dragged `span` by whichever of its edges lands closer to a
+/// target. Returns the shift to apply to the span and the snap it makes.
+pub fn snap_span(
+    world: &World,
+    span: TimelineSpan,
+    tolerance: SnapTolerance,
+    options: &SnapOptions,
+) -> Option<(i64, Snap)> {
+    let start = snap(world, span.start(), tolerance, options);
+    let end = snap(world, span.end(), tolerance, options);
+    let shift = |snap: Snap, edge: u64| (snap.tick as i64 - edge as i64, snap);
+    match (start, end) {
+        (Some(s), Some(e)) if e.distance < s.distance => Some(shift(e, span.end())),
+        (Some(s), _) => Some(shift(s, span.start())),
+        (None, Some(e)) => Some(shift(e, span.end())),
+        (None, None) => None,
+    }
+}
+
+/// Elements of `sequence` with an edge that may lie in `low..=high`.
+fn elements_near(
+    world: &World,
+    sequence: Option<Entity>,
+    low: u64,
+    high: u64,
+) -> Vec<(Entity, TimelineSpan)> {
+    // Widened by a tick on each side so that elements ending exactly at
+    // `low` or starting exactly at `high` still overlap the window.
+    let window = TimelineSpan::new_unchecked(low.saturating_sub(1), high.saturating_add(1));
+    if let Some(index) = world.get_resource::<TimelineIndex>() {
+        return index
+            .tracks_in(sequence)
+            .flat_map(|track| index.overlapping_in(sequence, track, &window))
+            .filter_map(|entity| Some((entity, index.get(entity)?.1)))
+            .collect();
+    }
+    let Some(mut query) = world.try_query::<(Entity, &TimelineElement)>() else {
+        return Vec::new();
+    };
+    query
+        .iter(world)
+        .filter(|(entity, element)| {
+            element.position.intersects(&window) && sequence_of(world, *entity) == sequence
+        })
+        .map(|(entity, element)| (entity, element.position))
+        .collect()
+}
@@ -0,0 +1,212 @@
+use std::{fmt::Display, ops::Range};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{prelude::*, timeline::FrameRate};
+
+/// Half-open tick interval `[start, end)`.
+///
+/// Construction is checked, so `start <= end` always holds. A span with
+/// `start == end` is empty: it contains no ticks and intersects nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(try_from = "(u64, u64)", into = "(u64, u64)")]
+pub struct TimelineSpan {
+    start: u64,
+    end: u64,
+}
+
+impl TimelineSpan {
+    pub fn new(start: u64, end: u64) -> Result<Self> {
+        if start > end {
+            return Err(LunarisError::InvalidArgument {
+                name: "span".to_string(),
+                reason: Some(format!("start {start} is after end {end}")),
+            });
+        }
+        Ok(Self { start, end })
+    }
+
+    /// Span of `duration` ticks beginning at `start`.
+    pub fn with_duration(start: u64, duration: u64) -> Result<Self> {
+        let end = start
+            .checked_add(duration)
+            .ok_or_else(|| LunarisError::InvalidArgument {
+                name: "span".to_string(),
+                reason: Some(format!("{start} + {duration} overflows the timeline")),
+            })?;
+        Ok(Self { start, end })
+    }
+
+    /// Empty span sitting at `tick`.
+    pub const fn empty_at(tick: u64) -> Self {
+        Self {
+            start: tick,
+            end: tick,
+        }
+    }
+
+    /// Caller guarantees `start <= end`.
+    pub(crate) const fn new_unchecked(start: u64, end: u64) -> Self {
+        Self { start, end }
+    }
+
+    #[inline]
+    pub const fn start(&self) -> u64 {
+        self.start
+    }
+
+    #[inline]
+    pub const fn end(&self) -> u64 {
+        self.end
+    }
+
+    #[inline]
+    pub const fn duration(&self) -> u64 {
+        self.end - self.start
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    #[inline]
+    pub const fn contains(&self, tick: u64) -> bool {
+        self.start <= tick && tick < self.end
+    }
+
+    /// Whether `other` lies entirely inside this span.
+    pub const fn contains_span(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Whether the two spans share at least one tick.
+    pub const fn intersects(&self, other: &Self) -> bool {
+        !self.is_empty() && !other.is_empty() && self.start < other.end && other.start < self.end
+    }
+
+    /// Whether the two spans overlap or sit back to back.
+    pub const fn touches(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Ticks shared by both spans, or `None` when they do not intersect.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.intersects(other).then(|| Self {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+
+    /// Combined span, or `None` when the spans leave a gap between them.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        self.touches(other).then(|| self.hull(other))
+    }
+
+    /// Smallest span covering both, including any gap between them.
+    pub fn hull(&self, other: &Self) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    /// Cut into `[start, tick)` and `[tick, end)`. `tick` must fall strictly
+    /// inside the span so that neither half is empty.
+    pub fn split_at(&self, tick: u64) -> Result<(Self, Self)> {
+        if tick <= self.start || tick >= self.end {
+            return Err(LunarisError::InvalidArgument {
+                name: "tick".to_string(),
+                reason: Some(format!("{tick} is not strictly inside {self}")),
+            });
+        }
+        Ok((
+            Self {
+                start: self.start,
+                end: tick,
+            },
+            Self {
+                start: tick,
+                end: self.end,
+            },
+        ))
+    }
+
+    /// Move the span by `delta` ticks, keeping its duration.
+    pub fn shift(&self, delta: i64) -> Result<Self> {
+        match (
+            self.start.checked_add_signed(delta),
+            self.end.checked_add_signed(delta),
+        ) {
+            (Some(start), Some(end)) => Ok(Self { start, end }),
+            _ => Err(LunarisError::InvalidArgument {
+                name: "delta".to_string(),
+                reason: Some(format!("shifting {self} by {delta} leaves the timeline")),
+            }),
+        }
+    }
+
+    /// Move the span by `delta` ticks, clamping both ends to the timeline.
+    /// The duration shrinks if an end hits `0` or `u64::MAX`.
+    pub const fn saturating_shift(&self, delta: i64) -> Self {
+        Self {
+            start: self.start.saturating_add_signed(delta),
+            end: self.end.saturating_add_signed(delta),
+        }
+    }
+
+    /// Move the span later by `ticks`, clamping at `u64::MAX`.
+    pub const fn saturating_add(&self, ticks: u64) -> Self {
+        Self {
+            start: self.start.saturating_add(ticks),
+            end: self.end.saturating_add(ticks),
+        }
+    }
+
+    /// Move the span earlier by `ticks`, clamping at `0`.
+    pub const fn saturating_sub(&self, ticks: u64) -> Self {
+        Self {
+            start: self.start.saturating_sub(ticks),
+            end: self.end.saturating_sub(ticks),
+        }
+    }
+
+    /// Same span with its start moved to `start`.
+    pub fn trim_start(&self, start: u64) -> Result<Self> {
+        Self::new(start, self.end)
+    }
+
+    /// Same span with its end moved to `end`.
+    pub fn trim_end(&self, end: u64) -> Result<Self> {
+        Self::new(self.start, end)
+    }
+
+    /// Frame numbers at `rate` that overlap the span.
+    pub fn frames(&self, rate: FrameRate, tps: u64) -> Range<u64> {
+        if self.is_empty() {
+            let frame = rate.ticks_to_frame(self.start, tps);
+            return frame..frame;
+        }
+        rate.ticks_to_frame(self.start, tps)..rate.ticks_to_frame(self.end - 1, tps) + 1
+    }
+}
+
+impl TryFrom<(u64, u64)> for TimelineSpan {
+    type Error = LunarisError;
+
+    fn try_from((start, end): (u64, u64)) -> Result<Self> {
+        Self::new(start, end)
+    }
+}
+
+impl From<TimelineSpan> for (u64, u64) {
+    fn from(span: TimelineSpan) -> Self {
+        (span.start, span.end)
+    }
+}
+
+impl Display for TimelineSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
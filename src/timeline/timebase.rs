@@ -0,0 +1,207 @@
+use bevy_ecs::{resource::Resource, world::World};
+
+use crate::{
+    consts::DEFAULT_TPS,
+    history::UndoHistory,
+    prelude::*,
+    timeline::{
+        Marker, Playhead, Region, TimelineSpan, Transition, Transport,
+        elements::{Properties, Property, SourceOffset, TimeRemap, TimelineElement},
+    },
+};
+
+/// Tick rate of the project living in a `World`.
+///
+/// Every tick-valued quantity in that world (spans, playheads, markers,
+/// [`Property::Ticks`]) is counted against this rate. A world without the
+/// resource uses [`DEFAULT_TPS`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timebase {
+    tps: u64,
+}
+
+impl Default for Timebase {
+    fn default() -> Self {
+        Self { tps: DEFAULT_TPS }
+    }
+}
+
+impl Timebase {
+    pub fn new(tps: u64) -> Result<Self> {
+        if tps == 0 {
+            return Err(LunarisError::InvalidArgument {
+                name: "tps".to_string(),
+                reason: Some("timebase must have at least one tick per second".to_string()),
+            });
+        }
+        Ok(Self { tps })
+    }
+
+    #[inline]
+    pub const fn tps(&self) -> u64 {
+        self.tps
+    }
+
+    /// Timebase of `world`, falling back to the default when unset.
+    pub fn of(world: &World) -> Self {
+        world.get_resource::<Self>().copied().unwrap_or_default()
+    }
+
+    /// Switch `world` to a new tick rate.
+    ///
+    /// Every [`TimelineElement`] span, [`SourceOffset`], [`TimeRemap`] ramp,
+    /// [`Playhead`], [`Transport`] loop, [`Marker`], [`Region`],
+    /// [`Transition`] span, [`Property::Ticks`] and [`Property::Curve`] key
+    /// is rescaled in the same call, so the timeline never observes a mix
+    /// of rates. Values are rounded to the nearest target tick, half-way up.
+    ///
+    /// The [`UndoHistory`] is cleared rather than rescaled: its steps hold
+    /// ticks at the old rate inside tokens this crate cannot look into.
+    pub fn change(world: &mut World, to: Timebase) -> RescaleReport {
+        let from = Self::of(world);
+        let mut rescaler = Rescaler::new(from, to);
+
+        if from != to {
+            let mut elements = world.query::<&mut TimelineElement>();
+            for mut element in elements.iter_mut(world) {
+                element.position = rescaler.rescale_span(element.position);
+            }
+
+            let mut offsets = world.query::<&mut SourceOffset>();
+            for mut offset in offsets.iter_mut(world) {
+                offset.ticks = rescaler.rescale(offset.ticks);
+            }
+
+            let mut remaps = world.query::<&mut TimeRemap>();
+            for mut remap in remaps.iter_mut(world) {
+                if let TimeRemap::Ramp(curve) = &mut *remap {
+                    curve.map_ticks(|tick| rescaler.rescale(tick));
+                }
+            }
+
+            let mut playheads = world.query::<&mut Playhead>();
+            for mut playhead in playheads.iter_mut(world) {
+                playhead.current = rescaler.rescale(playhead.current);
+            }
+
+            let mut markers = world.query::<&mut Marker>();
+            for mut marker in markers.iter_mut(world) {
+                marker.tick = rescaler.rescale(marker.tick);
+            }
+
+            let mut regions = world.query::<&mut Region>();
+            for mut region in regions.iter_mut(world) {
+                region.span = rescaler.rescale_span(region.span);
+            }
+
+            let mut transitions = world.query::<&mut Transition>();
+            for mut transition in transitions.iter_mut(world) {
+                transition.span = rescaler.rescale_span(transition.span);
+            }
+
+            let mut transports = world.query::<&mut Transport>();
+            for mut transport in transports.iter_mut(world) {
+                if let Some(span) = transport.loop_span {
+                    transport.loop_span = Some(rescaler.rescale_span(span));
+                }
+            }
+
+            let mut properties = world.query::<&mut Properties>();
+            for mut properties in properties.iter_mut(world) {
+                for value in properties.properties.values_mut() {
+                    match value {
+                        Property::Ticks(ticks) => *ticks = rescaler.rescale(*ticks),
+                        Property::Curve(curve) => curve.map_ticks(|tick| rescaler.rescale(tick)),
+                        _ => {}
+                    }
+                }
+            }
+
+            if let Some(mut history) = world.get_resource_mut::<UndoHistory>() {
+                history.clear();
+            }
+        }
+
+        world.insert_resource(to);
+        rescaler.report()
+    }
+}
+
+/// Outcome of [`Timebase::change`]. Errors are measured in target ticks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RescaleReport {
+    pub from: Timebase,
+    pub to: Timebase,
+    /// Number of tick values that were rescaled.
+    pub rescaled: usize,
+    /// Number of values that did not land exactly on a target tick.
+    pub inexact: usize,
+    /// Largest rounding error of a single value. Never above `0.5`.
+    pub max_error: f64,
+    /// Sum of the rounding errors of every value.
+    pub total_error: f64,
+}
+
+impl RescaleReport {
+    /// Whether the change preserved every value exactly.
+    pub fn is_lossless(&self) -> bool {
+        self.inexact == 0
+    }
+}
+
+/// Integer rescaling that tracks the accumulated rounding error.
+///
+/// Errors are kept as numerators over the source rate until the report is
+/// built, so the result does not depend on float evaluation order.
+struct Rescaler {
+    from: u64,
+    to: u64,
+    rescaled: usize,
+    inexact: usize,
+    max_error: u128,
+    total_error: u128,
+}
+
+impl Rescaler {
+    fn new(from: Timebase, to: Timebase) -> Self {
+        Self {
+            from: from.tps,
+            to: to.tps,
+            rescaled: 0,
+            inexact: 0,
+            max_error: 0,
+            total_error: 0,
+        }
+    }
+
+    fn rescale(&mut self, ticks: u64) -> u64 {
+        let exact = ticks as u128 * self.to as u128;
+        let from = self.from as u128;
+        let rounded = (exact + from / 2) / from;
+        let error = exact.abs_diff(rounded * from);
+
+        self.rescaled += 1;
+        if error != 0 {
+            self.inexact += 1;
+            self.max_error = self.max_error.max(error);
+            self.total_error += error;
+        }
+        u64::try_from(rounded).unwrap_or(u64::MAX)
+    }
+
+    /// Rounding is monotonic, so a rescaled span stays well-formed.
+    fn rescale_span(&mut self, span: TimelineSpan) -> TimelineSpan {
+        TimelineSpan::new_unchecked(self.rescale(span.start()), self.rescale(span.end()))
+    }
+
+    fn report(&self) -> RescaleReport {
+        RescaleReport {
+            from: Timebase { tps: self.from },
+            to: Timebase { tps: self.to },
+            rescaled: self.rescaled,
+            inexact: self.inexact,
+            max_error: self.max_error as f64 / self.from as f64,
+            total_error: self.total_error as f64 / self.from as f64,
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::prelude::*;
+
+/// Rational frame rate, e.g. `24000/1001` for 23.976 fps.
+///
+/// All conversions go through integer math against the tick rate so that
+/// fractional NTSC rates never accumulate floating point drift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameRate {
+    num: u32,
+    den: u32,
+}
+
+impl FrameRate {
+    pub const FPS_23_976: Self = Self::new_unchecked(24000, 1001);
+    pub const FPS_24: Self = Self::new_unchecked(24, 1);
+    pub const FPS_25: Self = Self::new_unchecked(25, 1);
+    pub const FPS_29_97: Self = Self::new_unchecked(30000, 1001);
+    pub const FPS_30: Self = Self::new_unchecked(30, 1);
+    pub const FPS_48: Self = Self::new_unchecked(48, 1);
+    pub const FPS_50: Self = Self::new_unchecked(50, 1);
+    pub const FPS_59_94: Self = Self::new_unchecked(60000, 1001);
+    pub const FPS_60: Self = Self::new_unchecked(60, 1);
+
+    /// Construct a frame rate of `num / den` frames per second. The fraction
+    /// is reduced, so `48/2` and `24/1` compare equal.
+    pub fn new(num: u32, den: u32) -> Result<Self> {
+        if num == 0 || den == 0 {
+            return Err(LunarisError::InvalidArgument {
+                name: "frame rate".to_string(),
+                reason: Some(format!("{num}/{den} is not a positive rate")),
+            });
+        }
+        let divisor = gcd(num, den);
+        Ok(Self::new_unchecked(num / divisor, den / divisor))
+    }
+
+    const fn new_unchecked(num: u32, den: u32) -> Self {
+        Self { num, den }
+    }
+
+    #[inline]
+    pub const fn numerator(self) -> u32 {
+        self.num
+    }
+
+    #[inline]
+    pub const fn denominator(self) -> u32 {
+        self.den
+    }
+
+    /// Frames per second as a float. Only use this for display.
+    #[inline]
+    pub fn as_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    /// Integer frame count used for timecode labels, e.g. 30 for 29.97.
+    #[inline]
+    pub const fn nominal(self) -> u32 {
+        let nominal = (self.num + self.den / 2) / self.den;
+        if nominal == 0 { 1 } else { nominal }
+    }
+
+    /// Whether SMPTE drop-frame counting is defined for this rate.
+    pub const fn supports_drop_frame(self) -> bool {
+        self.den == 1001 && (self.num == 30000 || self.num == 60000)
+    }
+
+    /// First tick belonging to `frame`.
+    ///
+    /// Frame boundaries rarely land on whole ticks at NTSC rates, so the
+    /// boundary is rounded up; this keeps `ticks_to_frame(frame_to_ticks(n))`
+    /// equal to `n` for every frame.
+    pub fn frame_to_ticks(self, frame: u64, tps: u64) -> u64 {
+        let num = frame as u128 * tps as u128 * self.den as u128;
+        saturate(num.div_ceil(self.num as u128))
+    }
+
+    /// Frame containing `ticks`.
+    pub fn ticks_to_frame(self, ticks: u64, tps: u64) -> u64 {
+        let den = tps as u128 * self.den as u128;
+        saturate(ticks as u128 * self.num as u128 / den.max(1))
+    }
+
+    /// Frame whose start is closest to `ticks`, rounding half-way up.
+    pub fn ticks_to_nearest_frame(self, ticks: u64, tps: u64) -> u64 {
+        let den = tps as u128 * self.den as u128;
+        let num = 2 * ticks as u128 * self.num as u128 + den;
+        saturate(num / (2 * den).max(1))
+    }
+
+    /// Move `ticks` back to the start of the frame containing it.
+    pub fn floor_to_frame(self, ticks: u64, tps: u64) -> u64 {
+        self.frame_to_ticks(self.ticks_to_frame(ticks, tps), tps)
+    }
+
+    /// Move `ticks` to the nearest frame boundary.
+    pub fn round_to_frame(self, ticks: u64, tps: u64) -> u64 {
+        self.frame_to_ticks(self.ticks_to_nearest_frame(ticks, tps), tps)
+    }
+}
+
+impl Display for FrameRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// SMPTE timecode label. `HH:MM:SS:FF`, or `HH:MM:SS;FF` when drop-frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Timecode {
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+    pub frames: u32,
+    pub drop_frame: bool,
+}
+
+impl Timecode {
+    pub const fn new(
+        hours: u32,
+        minutes: u32,
+        seconds: u32,
+        frames: u32,
+        drop_frame: bool,
+    ) -> Self {
+        Self {
+            hours,
+            minutes,
+            seconds,
+            frames,
+            drop_frame,
+        }
+    }
+
+    /// Label a frame number. Drop-frame labels are only valid for 29.97 and
+    /// 59.94.
+    pub fn from_frame(frame: u64, rate: FrameRate, drop_frame: bool) -> Result<Self> {
+        let fps = rate.nominal() as u64;
+        let mut frame = frame;
+        if drop_frame {
+            let dropped = drop_frame_count(rate)?;
+            let per_ten_minutes = fps * 600 - dropped * 9;
+            let per_minute = fps * 60 - dropped;
+            let tens = frame / per_ten_minutes;
+            let rem = frame % per_ten_minutes;
+            frame += dropped * 9 * tens;
+            if rem >= dropped {
+                frame += dropped * ((rem - dropped) / per_minute);
+            }
+        }
+
+        let hours = frame / (fps * 3600);
+        Ok(Self {
+            hours: u32::try_from(hours).map_err(|_| LunarisError::InvalidArgument {
+                name: "frame".to_string(),
+                reason: Some(format!("{frame} is beyond the timecode range")),
+            })?,
+            minutes: ((frame / (fps * 60)) % 60) as u32,
+            seconds: ((frame / fps) % 60) as u32,
+            frames: (frame % fps) as u32,
+            drop_frame,
+        })
+    }
+
+    /// Label the frame containing `ticks`.
+    pub fn from_ticks(ticks: u64, tps: u64, rate: FrameRate, drop_frame: bool) -> Result<Self> {
+        Self::from_frame(rate.ticks_to_frame(ticks, tps), rate, drop_frame)
+    }
+
+    /// Frame number this label refers to at `rate`.
+    pub fn to_frame(&self, rate: FrameRate) -> Result<u64> {
+        self.validate(rate)?;
+        let fps = rate.nominal() as u64;
+        let total_seconds =
+            self.hours as u64 * 3600 + self.minutes as u64 * 60 + self.seconds as u64;
+        let mut frame = total_seconds * fps + self.frames as u64;
+        if self.drop_frame {
+            let dropped = drop_frame_count(rate)?;
+            let total_minutes = self.hours as u64 * 60 + self.minutes as u64;
+            frame -= dropped * (total_minutes - total_minutes / 10);
+        }
+        Ok(frame)
+    }
+
+    /// First tick of the frame this label refers to.
+    pub fn to_ticks(&self, tps: u64, rate: FrameRate) -> Result<u64> {
+        Ok(rate.frame_to_ticks(self.to_frame(rate)?, tps))
+    }
+
+    fn validate(&self, rate: FrameRate) -> Result {
+        let invalid = |reason: String| {
+            Err(LunarisError::InvalidArgument {
+                name: "timecode".to_string(),
+                reason: Some(reason),
+            })
+        };
+        if self.minutes >= 60 || self.seconds >= 60 {
+            return invalid(format!("{self} has out of range minutes or seconds"));
+        }
+        if self.frames >= rate.nominal() {
+            return invalid(format!("{self} has too many frames for {rate} fps"));
+        }
+        if self.drop_frame {
+            let dropped = drop_frame_count(rate)?;
+            if self.seconds == 0
+                && !self.minutes.is_multiple_of(10)
+                && (self.frames as u64) < dropped
+            {
+                return invalid(format!("{self} is a dropped frame label"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for Timecode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.drop_frame { ';' } else { ':' };
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{}{:02}",
+            self.hours, self.minutes, self.seconds, separator, self.frames
+        )
+    }
+}
+
+impl FromStr for Timecode {
+    type Err = LunarisError;
+
+    /// Parse `HH:MM:SS:FF`. A `;` or `.` before the frames field marks the
+    /// label as drop-frame.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || LunarisError::InvalidArgument {
+            name: "timecode".to_string(),
+            reason: Some(format!("could not parse {s:?} as HH:MM:SS:FF")),
+        };
+        let s = s.trim();
+        let split = s.rfind([':', ';', '.']).ok_or_else(invalid)?;
+        let drop_frame = matches!(s.as_bytes()[split], b';' | b'.');
+        let fields: Vec<&str> = s[..split].split([':', ';']).collect();
+        let [hours, minutes, seconds] = fields[..] else {
+            return Err(invalid());
+        };
+        let parse = |field: &str| {
+            if field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            field.parse::<u32>().map_err(|_| invalid())
+        };
+        Ok(Self {
+            hours: parse(hours)?,
+            minutes: parse(minutes)?,
+            seconds: parse(seconds)?,
+            frames: parse(&s[split + 1..])?,
+            drop_frame,
+        })
+    }
+}
+
+/// Frame labels skipped at the start of each non-tenth minute.
+fn drop_frame_count(rate: FrameRate) -> Result<u64> {
+    if !rate.supports_drop_frame() {
+        return Err(LunarisError::InvalidArgument {
+            name: "frame rate".to_string(),
+            reason: Some(format!("drop-frame timecode is undefined at {rate} fps")),
+        });
+    }
+    Ok(rate.nominal() as u64 / 15)
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn saturate(value: u128) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
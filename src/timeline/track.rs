@@ -0,0 +1,278 @@
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    prelude::*,
+    timeline::{
+        Marker, Region,
+        elements::{TimelineElement, serialize::finite},
+        sequence::{InSequence, sequence_of},
+    },
+};
+
+/// What a track carries. Renderers and mixers only look at their own kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Subtitle,
+    Data,
+}
+
+/// Row height the timeline UI should give a track.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum TrackHeight {
+    Collapsed,
+    Small,
+    #[default]
+    Medium,
+    Large,
+    /// Explicit height in UI points.
+    Custom(#[serde(serialize_with = "finite")] f32),
+}
+
+impl TrackHeight {
+    pub fn points(self) -> f32 {
+        match self {
+            Self::Collapsed => 12.0,
+            Self::Small => 32.0,
+            Self::Medium => 56.0,
+            Self::Large => 96.0,
+            Self::Custom(points) => points,
+        }
+    }
+}
+
+/// A timeline track.
+///
+/// Elements belong to the track of their [`InSequence`] whose `index` equals
+/// their [`TimelineElement::track_num`]. Higher indices are composited on
+/// top.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Track {
+    pub name: String,
+    pub kind: TrackKind,
+    index: u64,
+    /// Disabled tracks are skipped entirely, as if they did not exist.
+    pub enabled: bool,
+    pub muted: bool,
+    pub solo: bool,
+    /// Locked tracks refuse edits.
+    pub locked: bool,
+    pub height: TrackHeight,
+}
+
+impl Track {
+    pub fn new(name: impl Into<String>, kind: TrackKind, index: u64) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            index,
+            enabled: true,
+            muted: false,
+            solo: false,
+            locked: false,
+            height: TrackHeight::default(),
+        }
+    }
+
+    /// Z-index of this track, shared with the `track_num` of its elements.
+    #[inline]
+    pub const fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Spawn `track` on the root timeline, refusing to share an index with
+    /// an existing track.
+    pub fn spawn(world: &mut World, track: Track) -> Result<Entity> {
+        Self::spawn_in(world, None, track)
+    }
+
+    /// Spawn `track` in `sequence`, refusing to share an index with another
+    /// track of that sequence.
+    pub fn spawn_in(world: &mut World, sequence: Option<Entity>, track: Track) -> Result<Entity> {
+        if Self::find_in(world, sequence, track.index).is_some() {
+            return Err(LunarisError::AlreadyExists {
+                item: format!("track with index {}", track.index),
+            });
+        }
+        let mut entity = world.spawn(track);
+        if let Some(sequence) = sequence {
+            entity.insert(InSequence { sequence });
+        }
+        Ok(entity.id())
+    }
+
+    /// Spawn a track above every existing one of the root timeline.
+    pub fn push(world: &mut World, name: impl Into<String>, kind: TrackKind) -> Entity {
+        Self::push_in(world, None, name, kind)
+    }
+
+    /// Spawn a track above every existing one of `sequence`.
+    pub fn push_in(
+        world: &mut World,
+        sequence: Option<Entity>,
+        name: impl Into<String>,
+        kind: TrackKind,
+    ) -> Entity {
+        let index = Self::all_in(world, sequence)
+            .last()
+            .map_or(0, |(_, track)| track.index + 1);
+        let mut entity = world.spawn(Self::new(name, kind, index));
+        if let Some(sequence) = sequence {
+            entity.insert(InSequence { sequence });
+        }
+        entity.id()
+    }
+
+    /// Root timeline track with the given index.
+    pub fn find(world: &World, index: u64) -> Option<Entity> {
+        Self::find_in(world, None, index)
+    }
+
+    /// Track of `sequence` with the given index.
+    pub fn find_in(world: &World, sequence: Option<Entity>, index: u64) -> Option<Entity> {
+        Self::all_in(world, sequence)
+            .into_iter()
+            .find(|(_, track)| track.index == index)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Every track of the root timeline, bottom to top.
+    pub fn all(world: &World) -> Vec<(Entity, &Track)> {
+        Self::all_in(world, None)
+    }
+
+    /// Every track of `sequence`, bottom to top.
+    pub fn all_in(world: &World, sequence: Option<Entity>) -> Vec<(Entity, &Track)> {
+        let Some(mut query) = world.try_query::<(Entity, &Track)>() else {
+            return Vec::new();
+        };
+        let mut tracks: Vec<_> = query
+            .iter(world)
+            .filter(|(entity, _)| sequence_of(world, *entity) == sequence)
+            .collect();
+        tracks.sort_by_key(|(_, track)| track.index);
+        tracks
+    }
+
+    /// Root timeline tracks of `kind`, bottom to top.
+    pub fn ordered(world: &World, kind: TrackKind) -> Vec<(Entity, &Track)> {
+        Self::ordered_in(world, None, kind)
+    }
+
+    /// Tracks of `kind` in `sequence`, bottom to top.
+    pub fn ordered_in(
+        world: &World,
+        sequence: Option<Entity>,
+        kind: TrackKind,
+    ) -> Vec<(Entity, &Track)> {
+        let mut tracks = Self::all_in(world, sequence);
+        tracks.retain(|(_, track)| track.kind == kind);
+        tracks
+    }
+
+    /// Root timeline tracks of `kind` that should be rendered or mixed,
+    /// bottom to top.
+    ///
+    /// Disabled and muted tracks are dropped. If any enabled track of `kind`
+    /// is soloed, only the soloed tracks remain.
+    pub fn active(world: &World, kind: TrackKind) -> Vec<(Entity, &Track)> {
+        Self::active_in(world, None, kind)
+    }
+
+    /// Like [`active`](Self::active) for the tracks of `sequence`. Soloing
+    /// only applies within a sequence.
+    pub fn active_in(
+        world: &World,
+        sequence: Option<Entity>,
+        kind: TrackKind,
+    ) -> Vec<(Entity, &Track)> {
+        let mut tracks = Self::ordered_in(world, sequence, kind);
+        tracks.retain(|(_, track)| track.enabled);
+        let soloing = tracks.iter().any(|(_, track)| track.solo);
+        tracks.retain(|(_, track)| !track.muted && (!soloing || track.solo));
+        tracks
+    }
+
+    /// Elements on the track, in no particular order.
+    pub fn elements(world: &World, track: Entity) -> Result<Vec<Entity>> {
+        let index = Self::get(world, track)?.index;
+        let sequence = sequence_of(world, track);
+        let Some(mut query) = world.try_query::<(Entity, &TimelineElement)>() else {
+            return Ok(Vec::new());
+        };
+        Ok(query
+            .iter(world)
+            .filter(|(entity, element)| {
+                element.track_num == index && sequence_of(world, *entity) == sequence
+            })
+            .map(|(entity, _)| entity)
+            .collect())
+    }
+
+    /// Move `track` to Z-index `to`.
+    ///
+    /// Tracks, elements, markers and regions of the same sequence between the
+    /// old and new index shift by one to make room, and those on `track`
+    /// follow it, so the stacking order of everything else is preserved.
+    pub fn reorder(world: &mut World, track: Entity, to: u64) -> Result {
+        let from = Self::get(world, track)?.index;
+        if from == to {
+            return Ok(());
+        }
+        let sequence = sequence_of(world, track);
+        let renumber = |index: u64| {
+            if index == from {
+                to
+            } else if from < to && (from + 1..=to).contains(&index) {
+                index - 1
+            } else if to < from && (to..from).contains(&index) {
+                index + 1
+            } else {
+                index
+            }
+        };
+
+        let mut tracks = world.query::<(&mut Track, Option<&InSequence>)>();
+        for (mut track, parent) in tracks.iter_mut(world) {
+            let index = renumber(track.index);
+            if parent.map(|p| p.sequence) == sequence && index != track.index {
+                track.index = index;
+            }
+        }
+
+        let mut elements = world.query::<(&mut TimelineElement, Option<&InSequence>)>();
+        for (mut element, parent) in elements.iter_mut(world) {
+            let index = renumber(element.track_num);
+            if parent.map(|p| p.sequence) == sequence && index != element.track_num {
+                element.track_num = index;
+            }
+        }
+
+        let mut markers = world.query::<(&mut Marker, Option<&InSequence>)>();
+        for (mut marker, parent) in markers.iter_mut(world) {
+            let index = marker.track.map(renumber);
+            if parent.map(|p| p.sequence) == sequence && index != marker.track {
+                marker.track = index;
+            }
+        }
+
+        let mut regions = world.query::<(&mut Region, Option<&InSequence>)>();
+        for (mut region, parent) in regions.iter_mut(world) {
+            let index = region.track.map(renumber);
+            if parent.map(|p| p.sequence) == sequence && index != region.track {
+                region.track = index;
+            }
+        }
+        Ok(())
+    }
+
+    fn get(world: &World, track: Entity) -> Result<&Track> {
+        world
+            .get::<Track>(track)
+            .ok_or_else(|| LunarisError::NotFound {
+                item: format!("Track for Entity: {track}"),
+            })
+    }
+}
@@ -0,0 +1,199 @@
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    plugin::RenderJob,
+    prelude::*,
+    render::RawImage,
+    timeline::{
+        TimelineSpan,
+        elements::{Properties, TimelineElement, remap, serialize::finite},
+        sequence::sequence_of,
+    },
+};
+
+/// How a [`Transition`] blends its two elements.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TransitionKind {
+    Dissolve,
+    /// Through a solid RGBA color.
+    DipToColor([u8; 4]),
+    /// Hard edge moving in the direction of `angle`, in degrees. See
+    /// [`RawImage::wipe`].
+    Wipe {
+        #[serde(serialize_with = "finite")]
+        angle: f64,
+    },
+    /// Blended by the [`Transition`](crate::plugin::Transition) plugin
+    /// registered under this name.
+    Plugin(String),
+}
+
+impl TransitionKind {
+    /// Blend with the built-in implementation. Fails with
+    /// [`LunarisError::NotSupported`] for [`Plugin`](Self::Plugin) kinds,
+    /// which the host schedules on the plugin instead.
+    pub fn blend(
+        &self,
+        outgoing: &RawImage,
+        incoming: &RawImage,
+        progress: f64,
+    ) -> Result<RawImage> {
+        match self {
+            Self::Dissolve => outgoing.dissolve(incoming, progress),
+            Self::DipToColor(color) => outgoing.dip_to_color(incoming, progress, *color),
+            Self::Wipe { angle } => outgoing.wipe(incoming, progress, *angle),
+            Self::Plugin(_) => Err(LunarisError::NotSupported {
+                operation: "built-in blend of a plugin transition",
+            }),
+        }
+    }
+}
+
+/// Blend between two adjacent elements on one track.
+///
+/// The cut where `outgoing` ends and `incoming` starts lies inside `span`.
+/// Across the span both elements are rendered, each past its own edge from
+/// the media around it, and blended by `kind`. Parameters for plugin kinds
+/// live in the entity's [`Properties`].
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub outgoing: Entity,
+    pub incoming: Entity,
+    pub span: TimelineSpan,
+    pub kind: TransitionKind,
+}
+
+impl Transition {
+    pub fn new(
+        outgoing: Entity,
+        incoming: Entity,
+        span: TimelineSpan,
+        kind: TransitionKind,
+    ) -> Self {
+        Self {
+            outgoing,
+            incoming,
+            span,
+            kind,
+        }
+    }
+
+    /// Spawn a transition of `kind` centred on the cut between `outgoing`
+    /// and `incoming`, lasting `duration` ticks.
+    pub fn spawn(
+        world: &mut World,
+        outgoing: Entity,
+        incoming: Entity,
+        duration: u64,
+        kind: TransitionKind,
+    ) -> Result<Entity> {
+        let cut = element(world, outgoing)?.position.end();
+        let start = cut.saturating_sub(duration / 2);
+        let end = start
+            .checked_add(duration)
+            .ok_or_else(|| LunarisError::InvalidArgument {
+                name: "duration".to_string(),
+                reason: Some(format!(
+                    "a {duration} tick transition runs past the timeline"
+                )),
+            })?;
+        let span = TimelineSpan::new(start, end)?;
+        let transition = Self::new(outgoing, incoming, span, kind);
+        transition.check(world)?;
+        Ok(world.spawn(transition).id())
+    }
+
+    /// Fail with [`LunarisError::InvalidArgument`] unless both elements
+    /// exist, sit next to each other on the same track and sequence, and
+    /// `span` is non-empty, contains the cut and stays within them.
+    pub fn check(&self, world: &World) -> Result {
+        let outgoing = element(world, self.outgoing)?;
+        let incoming = element(world, self.incoming)?;
+        let invalid = |reason: &str| {
+            Err(LunarisError::InvalidArgument {
+                name: "transition".to_string(),
+                reason: Some(reason.to_string()),
+            })
+        };
+        if outgoing.track_num != incoming.track_num
+            || sequence_of(world, self.outgoing) != sequence_of(world, self.incoming)
+        {
+            return invalid("elements are on different tracks");
+        }
+        let cut = outgoing.position.end();
+        if cut != incoming.position.start() {
+            return invalid("outgoing element does not end where incoming starts");
+        }
+        if self.span.is_empty() || !(self.span.start()..=self.span.end()).contains(&cut) {
+            return invalid("span does not contain the cut");
+        }
+        if self.span.start() < outgoing.position.start()
+            || self.span.end() > incoming.position.end()
+        {
+            return invalid("span reaches past the elements");
+        }
+        Ok(())
+    }
+
+    /// How far through the transition `tick` is, from `0.0` at its start to
+    /// `1.0` at its end.
+    pub fn progress(&self, tick: u64) -> f64 {
+        if self.span.is_empty() {
+            return 1.0;
+        }
+        let into = tick.clamp(self.span.start(), self.span.end()) - self.span.start();
+        into as f64 / self.span.duration() as f64
+    }
+
+    /// Jobs rendering the outgoing and incoming element at `tick`.
+    pub fn jobs(&self, world: &World, tick: u64) -> (RenderJob, RenderJob) {
+        let job = |entity: Entity| RenderJob {
+            source_tick: remap::source_tick(world, entity, tick).unwrap_or(0),
+            ..RenderJob::new(
+                tick,
+                entity,
+                world.get::<Properties>(entity).cloned().unwrap_or_default(),
+            )
+        };
+        (job(self.outgoing), job(self.incoming))
+    }
+
+    /// Transitions in `sequence` whose span contains `tick`, ordered by
+    /// track.
+    pub fn at(world: &World, sequence: Option<Entity>, tick: u64) -> Vec<(Entity, &Transition)> {
+        let Some(mut query) = world.try_query::<(Entity, &Transition)>() else {
+            return Vec::new();
+        };
+        let mut transitions: Vec<_> = query
+            .iter(world)
+            .filter(|(_, t)| t.span.contains(tick) && sequence_of(world, t.outgoing) == sequence)
+            .collect();
+        transitions.sort_by_key(|(entity, t)| {
+            (
+                world
+                    .get::<TimelineElement>(t.outgoing)
+                    .map(|e| e.track_num),
+                *entity,
+            )
+        });
+        transitions
+    }
+
+    /// The transition between `outgoing` and `incoming`, if there is one.
+    pub fn between(world: &World, outgoing: Entity, incoming: Entity) -> Option<Entity> {
+        world
+            .try_query::<(Entity, &Transition)>()?
+            .iter(world)
+            .find(|(_, t)| t.outgoing == outgoing && t.incoming == incoming)
+            .map(|(entity, _)| entity)
+    }
+}
+
+fn element(world: &World, entity: Entity) -> Result<&TimelineElement> {
+    world
+        .get::<TimelineElement>(entity)
+        .ok_or_else(|| LunarisError::NotFound {
+            item: format!("Timeline element for Entity: {entity}"),
+        })
+}
@@ -0,0 +1,270 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    system::{Query, Res},
+    world::{Mut, World},
+};
+
+use crate::{
+    prelude::*,
+    timeline::{FrameRate, Playhead, Timebase, TimelineSpan},
+};
+
+/// Shuttle speeds reached by pressing J or L repeatedly.
+pub const SHUTTLE_SPEEDS: [f64; 4] = [1.0, 2.0, 4.0, 8.0];
+
+/// Source of wall-clock time for a [`Transport`].
+pub trait Clock: Send + Sync {
+    /// Time elapsed since some fixed point. Must never go backwards.
+    fn now(&self) -> Duration;
+}
+
+/// [`Clock`] reading the system's monotonic clock.
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// [`Clock`] that only moves when told to. Clones share the same time, so
+/// a test can keep one and hand the other to the [`Transport`].
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.nanos
+            .fetch_add(by.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+}
+
+/// Playback state of the [`Playhead`] on the same entity.
+///
+/// [`advance_transport`] moves each playhead by the wall-clock time elapsed
+/// since its last run, times [`speed`](Self::speed). The host should run it
+/// once per frame.
+#[derive(Component)]
+pub struct Transport {
+    /// Rate used for frame stepping.
+    pub frame_rate: FrameRate,
+    /// Playback wraps around inside this span while `looping` is set.
+    pub loop_span: Option<TimelineSpan>,
+    pub looping: bool,
+    speed: f64,
+    clock: Arc<dyn Clock>,
+    last: Option<Duration>,
+    /// Fraction of a tick carried over between runs, so slow speeds and
+    /// short frames still add up.
+    carry: f64,
+}
+
+impl Transport {
+    pub fn new(frame_rate: FrameRate) -> Self {
+        Self::with_clock(frame_rate, Arc::new(SystemClock::default()))
+    }
+
+    pub fn with_clock(frame_rate: FrameRate, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            frame_rate,
+            loop_span: None,
+            looping: false,
+            speed: 0.0,
+            clock,
+            last: None,
+            carry: 0.0,
+        }
+    }
+
+    /// Playback speed as a multiple of real time. Negative plays backwards,
+    /// `0.0` is paused.
+    #[inline]
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    #[inline]
+    pub fn is_playing(&self) -> bool {
+        self.speed != 0.0
+    }
+
+    /// Play at `speed`. Non-finite speeds pause.
+    pub fn set_speed(&mut self, speed: f64) {
+        let speed = if speed.is_finite() { speed } else { 0.0 };
+        if speed != 0.0 && !self.is_playing() {
+            self.last = Some(self.clock.now());
+            self.carry = 0.0;
+        }
+        self.speed = speed;
+    }
+
+    /// Play forward at normal speed.
+    pub fn play(&mut self) {
+        self.set_speed(1.0);
+    }
+
+    pub fn pause(&mut self) {
+        self.set_speed(0.0);
+    }
+
+    pub fn toggle(&mut self) {
+        if self.is_playing() {
+            self.pause();
+        } else {
+            self.play();
+        }
+    }
+
+    /// L: play forward, or go to the next faster [`SHUTTLE_SPEEDS`] step if
+    /// already playing forward. Playing backwards switches to normal speed
+    /// forward.
+    pub fn shuttle_forward(&mut self) {
+        self.set_speed(next_shuttle(self.speed));
+    }
+
+    /// J: the mirror image of [`shuttle_forward`](Self::shuttle_forward).
+    pub fn shuttle_reverse(&mut self) {
+        self.set_speed(-next_shuttle(-self.speed));
+    }
+
+    /// K: stop shuttling.
+    pub fn shuttle_stop(&mut self) {
+        self.pause();
+    }
+
+    /// Loop playback between `span`.
+    pub fn set_loop(&mut self, span: TimelineSpan) {
+        self.loop_span = Some(span);
+        self.looping = true;
+    }
+
+    pub fn clear_loop(&mut self) {
+        self.loop_span = None;
+        self.looping = false;
+    }
+
+    /// Where a playhead at `current` should be now, given the time elapsed
+    /// since the last call.
+    ///
+    /// Reverse playback pauses at tick `0`. While looping, forward playback
+    /// wraps from the loop end to its start and reverse playback the other
+    /// way.
+    pub fn advance(&mut self, current: u64, tps: u64) -> u64 {
+        let now = self.clock.now();
+        let elapsed = self
+            .last
+            .map_or(Duration::ZERO, |last| now.saturating_sub(last));
+        self.last = Some(now);
+        if !self.is_playing() {
+            return current;
+        }
+
+        let ticks = elapsed.as_secs_f64() * tps as f64 * self.speed + self.carry;
+        let whole = ticks.trunc();
+        self.carry = ticks - whole;
+        let target = current as i128 + whole as i128;
+
+        if self.looping
+            && let Some(span) = self.loop_span.filter(|s| !s.is_empty())
+        {
+            let (start, len) = (span.start() as i128, span.duration() as i128);
+            // Outside the loop the playhead plays normally until it enters.
+            let inside = (start..start + len).contains(&(current as i128));
+            if inside {
+                return (start + (target - start).rem_euclid(len)) as u64;
+            }
+        }
+        if target <= 0 {
+            if self.speed < 0.0 {
+                self.pause();
+            }
+            return 0;
+        }
+        target.min(u64::MAX as i128) as u64
+    }
+
+    /// Transport of `playhead`.
+    pub fn of(world: &mut World, playhead: Entity) -> Result<Mut<'_, Self>> {
+        world
+            .get_mut::<Self>(playhead)
+            .ok_or_else(|| LunarisError::NotFound {
+                item: format!("Transport for Entity: {playhead}"),
+            })
+    }
+
+    /// Pause `playhead` and move it by `frames` frames of
+    /// [`frame_rate`](Self::frame_rate), landing on a frame boundary.
+    pub fn step(world: &mut World, playhead: Entity, frames: i64) -> Result {
+        let tps = Timebase::of(world).tps();
+        let mut transport = Self::of(world, playhead)?;
+        transport.pause();
+        let rate = transport.frame_rate;
+        let current = Playhead::tick(world, playhead)?;
+        Playhead::seek(world, playhead, step_frames(rate, tps, current, frames))
+    }
+}
+
+fn next_shuttle(speed: f64) -> f64 {
+    SHUTTLE_SPEEDS
+        .iter()
+        .copied()
+        .find(|s| *s > speed)
+        .unwrap_or(SHUTTLE_SPEEDS[SHUTTLE_SPEEDS.len() - 1])
+}
+
+/// Frame boundary `frames` frames away from `current`. A playhead between
+/// two boundaries counts the one behind it as the first backward step.
+fn step_frames(rate: FrameRate, tps: u64, current: u64, frames: i64) -> u64 {
+    let mut frame = rate.ticks_to_frame(current, tps) as i128;
+    if frames < 0 && rate.frame_to_ticks(frame as u64, tps) < current {
+        frame += 1;
+    }
+    let target = (frame + frames as i128).clamp(0, u64::MAX as i128) as u64;
+    rate.frame_to_ticks(target, tps)
+}
+
+/// Advance every playhead with a [`Transport`] by the time elapsed since
+/// the last run.
+pub fn advance_transport(
+    timebase: Option<Res<Timebase>>,
+    mut playheads: Query<(&mut Playhead, &mut Transport)>,
+) {
+    let tps = timebase.map_or_else(|| Timebase::default().tps(), |t| t.tps());
+    for (mut playhead, mut transport) in &mut playheads {
+        let next = transport.advance(playhead.current, tps);
+        if next != playhead.current {
+            playhead.current = next;
+        }
+    }
+}
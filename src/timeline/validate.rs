@@ -0,0 +1,538 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+};
+
+use bevy_ecs::{entity::Entity, world::World};
+
+use crate::{
+    prelude::*,
+    timeline::{
+        TimelineSpan, Transition,
+        edit::{ElementState, read_state, write_state},
+        elements::{
+            BindTo, Curve, CurveValue, Properties, Property, PropertySchema, SchemaViolation,
+            TimelineElement, schema::Problem,
+        },
+        sequence::sequence_of,
+    },
+};
+
+/// How much an [`Issue`] matters. Errors break rendering or editing;
+/// warnings are legal but usually unintended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Something wrong with the timeline, found by [`Validator::run`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueKind {
+    /// `later` starts before `earlier` ends on the same track.
+    Overlap {
+        sequence: Option<Entity>,
+        track: u64,
+        earlier: Entity,
+        later: Entity,
+        span: TimelineSpan,
+    },
+    /// Nothing on `track` between two elements.
+    Gap {
+        sequence: Option<Entity>,
+        track: u64,
+        span: TimelineSpan,
+    },
+    /// An element that starts where it ends.
+    EmptySpan { entity: Entity },
+    /// [`BindTo`] pointing at an entity that no longer exists.
+    DanglingBind { entity: Entity, target: Entity },
+    /// [`Property::Entity`] pointing at an entity that no longer exists.
+    DanglingProperty {
+        entity: Entity,
+        key: String,
+        target: Entity,
+    },
+    /// A [`Transition`] whose elements are gone or no longer meet at its
+    /// span.
+    InvalidTransition { entity: Entity, reason: String },
+    /// A property the element's [`PropertySchema`] rejects.
+    Property {
+        entity: Entity,
+        violation: SchemaViolation,
+    },
+}
+
+impl IssueKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Gap { .. }
+            | Self::Property {
+                violation:
+                    SchemaViolation {
+                        problem: Problem::Unknown,
+                        ..
+                    },
+                ..
+            } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Sequence and track whose layout this issue is about.
+    fn track(&self) -> Option<TrackKey> {
+        match self {
+            Self::Overlap {
+                sequence, track, ..
+            }
+            | Self::Gap {
+                sequence, track, ..
+            } => Some((*sequence, *track)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for IssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overlap {
+                track,
+                earlier,
+                later,
+                span,
+                ..
+            } => write!(f, "{later} overlaps {earlier} on track {track} over {span}"),
+            Self::Gap { track, span, .. } => write!(f, "gap on track {track} over {span}"),
+            Self::EmptySpan { entity } => write!(f, "{entity} has an empty span"),
+            Self::DanglingBind { entity, target } => {
+                write!(f, "{entity} is bound to missing {target}")
+            }
+            Self::DanglingProperty {
+                entity,
+                key,
+                target,
+            } => write!(f, "{entity}: {key} refers to missing {target}"),
+            Self::InvalidTransition { entity, reason } => {
+                write!(f, "transition {entity}: {reason}")
+            }
+            Self::Property { entity, violation } => write!(f, "{entity}: {violation}"),
+        }
+    }
+}
+
+/// Change that resolves an [`Issue`].
+///
+/// Fixes write components directly and are not recorded for undo. Element
+/// moves take their keyframes along, like a [`TimelineEdit`] would.
+///
+/// [`TimelineEdit`]: crate::timeline::TimelineEdit
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fix {
+    /// End `entity` at `end`.
+    Trim {
+        entity: Entity,
+        end: u64,
+    },
+    /// Move `entity` to start at `start`, keeping its duration.
+    Move {
+        entity: Entity,
+        start: u64,
+    },
+    /// Pull everything on `track` after `span` left to close it.
+    CloseGap {
+        sequence: Option<Entity>,
+        track: u64,
+        span: TimelineSpan,
+    },
+    /// Take `entity` off the timeline, like a deleting edit does.
+    RemoveElement(Entity),
+    Unbind(Entity),
+    Despawn(Entity),
+    RemoveProperty {
+        entity: Entity,
+        key: String,
+    },
+    SetProperty {
+        entity: Entity,
+        key: String,
+        value: Property,
+    },
+}
+
+impl Fix {
+    pub fn apply(&self, world: &mut World) -> Result {
+        match self {
+            Self::Trim { entity, end } => {
+                let state = read_state(world, *entity).ok_or_else(|| not_found(*entity))?;
+                TimelineSpan::new(state.start, *end)?;
+                write_state(world, *entity, Some(ElementState { end: *end, ..state }))
+            }
+            Self::Move { entity, start } => move_to(world, *entity, *start),
+            Self::CloseGap {
+                sequence,
+                track,
+                span,
+            } => {
+                let Some(mut query) = world.try_query::<(Entity, &TimelineElement)>() else {
+                    return Ok(());
+                };
+                let after: Vec<(Entity, u64)> = query
+                    .iter(world)
+                    .filter(|(entity, element)| {
+                        element.track_num == *track
+                            && element.position.start() >= span.end()
+                            && sequence_of(world, *entity) == *sequence
+                    })
+                    .map(|(entity, element)| (entity, element.position.start()))
+                    .collect();
+                for (entity, start) in after {
+                    move_to(world, entity, start - span.duration())?;
+                }
+                Ok(())
+            }
+            Self::RemoveElement(entity) => write_state(world, *entity, None),
+            Self::Unbind(entity) => {
+                BindTo::detach(world, *entity);
+                Ok(())
+            }
+            Self::Despawn(entity) => {
+                world.despawn(*entity);
+                Ok(())
+            }
+            Self::RemoveProperty { entity, key } => {
+                if let Some(mut properties) = world.get_mut::<Properties>(*entity) {
+                    properties.remove(key);
+                }
+                Ok(())
+            }
+            Self::SetProperty { entity, key, value } => {
+                world
+                    .get_mut::<Properties>(*entity)
+                    .ok_or_else(|| not_found(*entity))?
+                    .insert(key.clone(), value.clone());
+                Ok(())
+            }
+        }
+    }
+}
+
+fn move_to(world: &mut World, entity: Entity, start: u64) -> Result {
+    let state = read_state(world, entity).ok_or_else(|| not_found(entity))?;
+    let span = TimelineSpan::with_duration(start, state.end - state.start)?;
+    write_state(
+        world,
+        entity,
+        Some(ElementState {
+            start: span.start(),
+            end: span.end(),
+            ..state
+        }),
+    )
+}
+
+fn not_found(entity: Entity) -> LunarisError {
+    LunarisError::NotFound {
+        item: format!("Timeline element for Entity: {entity}"),
+    }
+}
+
+/// A problem and the fix suggested for it, if one can be made safely.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub fix: Option<Fix>,
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+/// Sequence and track number.
+type TrackKey = (Option<Entity>, u64);
+
+type SchemaLookup = Box<dyn Fn(&World, Entity) -> Option<PropertySchema> + Send + Sync>;
+
+/// Integrity checks over the timeline of a [`World`].
+///
+/// Hosts should run it after loading a project and before exporting one.
+#[derive(Default)]
+pub struct Validator {
+    schemas: Option<SchemaLookup>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also check each entity's [`Properties`] against the schema `lookup`
+    /// returns for it, usually the
+    /// [`property_schema`](crate::plugin::Renderer::property_schema) of the
+    /// plugin that draws it.
+    pub fn with_schemas(
+        mut self,
+        lookup: impl Fn(&World, Entity) -> Option<PropertySchema> + Send + Sync + 'static,
+    ) -> Self {
+        self.schemas = Some(Box::new(lookup));
+        self
+    }
+
+    /// Every issue in `world`: track layout first, in timeline order, then
+    /// references, transitions and properties.
+    pub fn run(&self, world: &World) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        check_tracks(world, &mut issues);
+        check_references(world, &mut issues);
+        check_transitions(world, &mut issues);
+        if let Some(lookup) = &self.schemas {
+            check_properties(world, lookup, &mut issues);
+        }
+        issues
+    }
+
+    /// Apply the suggested fix of every issue of at least `min` severity,
+    /// checking again until no fixable issue is left. Returns what remains.
+    ///
+    /// Only one fix per track is applied between checks, so that moving
+    /// one element never acts on a stale view of its neighbours.
+    ///
+    /// Stops at the first fix that fails and returns its error. Fixes
+    /// applied before it stay applied, and since fixes are not recorded for
+    /// undo, they cannot be taken back.
+    pub fn fix(&self, world: &mut World, min: Severity) -> Result<Vec<Issue>> {
+        let mut remaining = self.run(world);
+        loop {
+            let mut tracks = HashSet::new();
+            let mut applied = false;
+            for issue in &remaining {
+                let Some(fix) = issue.fix.as_ref().filter(|_| issue.severity() >= min) else {
+                    continue;
+                };
+                if let Some(track) = issue.kind.track()
+                    && !tracks.insert(track)
+                {
+                    continue;
+                }
+                fix.apply(world)?;
+                applied = true;
+            }
+            if !applied {
+                return Ok(remaining);
+            }
+            let next = self.run(world);
+            if next == remaining {
+                return Ok(next);
+            }
+            remaining = next;
+        }
+    }
+}
+
+/// [`Validator::run`] without property schemas.
+pub fn validate(world: &World) -> Vec<Issue> {
+    Validator::new().run(world)
+}
+
+fn check_tracks(world: &World, issues: &mut Vec<Issue>) {
+    let Some(mut query) = world.try_query::<(Entity, &TimelineElement)>() else {
+        return;
+    };
+    let mut tracks: BTreeMap<TrackKey, Vec<(TimelineSpan, Entity)>> = BTreeMap::new();
+    for (entity, element) in query.iter(world) {
+        if element.position.is_empty() {
+            issues.push(Issue {
+                kind: IssueKind::EmptySpan { entity },
+                fix: Some(Fix::RemoveElement(entity)),
+            });
+            continue;
+        }
+        tracks
+            .entry((sequence_of(world, entity), element.track_num))
+            .or_default()
+            .push((element.position, entity));
+    }
+
+    for ((sequence, track), mut elements) in tracks {
+        elements.sort_unstable_by_key(|(span, entity)| (span.start(), span.end(), *entity));
+        // Element reaching furthest right so far.
+        let mut reach: Option<(TimelineSpan, Entity)> = None;
+        for (span, entity) in elements {
+            if let Some((earlier_span, earlier)) = reach {
+                let end = earlier_span.end();
+                if span.start() < end {
+                    let fix = if span.start() > earlier_span.start() {
+                        Fix::Trim {
+                            entity: earlier,
+                            end: span.start(),
+                        }
+                    } else {
+                        Fix::Move { entity, start: end }
+                    };
+                    issues.push(Issue {
+                        kind: IssueKind::Overlap {
+                            sequence,
+                            track,
+                            earlier,
+                            later: entity,
+                            span: TimelineSpan::new_unchecked(span.start(), end.min(span.end())),
+                        },
+                        fix: Some(fix),
+                    });
+                } else if span.start() > end {
+                    let gap = TimelineSpan::new_unchecked(end, span.start());
+                    issues.push(Issue {
+                        kind: IssueKind::Gap {
+                            sequence,
+                            track,
+                            span: gap,
+                        },
+                        fix: Some(Fix::CloseGap {
+                            sequence,
+                            track,
+                            span: gap,
+                        }),
+                    });
+                }
+            }
+            if reach.is_none_or(|(r, _)| span.end() > r.end()) {
+                reach = Some((span, entity));
+            }
+        }
+    }
+}
+
+fn check_references(world: &World, issues: &mut Vec<Issue>) {
+    let exists = |entity: Entity| world.get_entity(entity).is_ok();
+    if let Some(mut query) = world.try_query::<(Entity, &BindTo)>() {
+        for (entity, bind) in query.iter(world) {
+            if !exists(bind.id) {
+                issues.push(Issue {
+                    kind: IssueKind::DanglingBind {
+                        entity,
+                        target: bind.id,
+                    },
+                    fix: Some(Fix::Unbind(entity)),
+                });
+            }
+        }
+    }
+    if let Some(mut query) = world.try_query::<(Entity, &Properties)>() {
+        for (entity, properties) in query.iter(world) {
+            let mut dangling: Vec<(&String, Entity)> = properties
+                .properties
+                .iter()
+                .filter_map(|(key, value)| match value {
+                    Property::Entity(target) if !exists(*target) => Some((key, *target)),
+                    _ => None,
+                })
+                .collect();
+            dangling.sort();
+            issues.extend(dangling.into_iter().map(|(key, target)| Issue {
+                kind: IssueKind::DanglingProperty {
+                    entity,
+                    key: key.clone(),
+                    target,
+                },
+                fix: Some(Fix::RemoveProperty {
+                    entity,
+                    key: key.clone(),
+                }),
+            }));
+        }
+    }
+}
+
+fn check_transitions(world: &World, issues: &mut Vec<Issue>) {
+    let Some(mut query) = world.try_query::<(Entity, &Transition)>() else {
+        return;
+    };
+    for (entity, transition) in query.iter(world) {
+        if let Err(e) = transition.check(world) {
+            let reason = match e {
+                LunarisError::InvalidArgument {
+                    reason: Some(reason),
+                    ..
+                } => reason,
+                e => e.to_string(),
+            };
+            issues.push(Issue {
+                kind: IssueKind::InvalidTransition { entity, reason },
+                fix: Some(Fix::Despawn(entity)),
+            });
+        }
+    }
+}
+
+fn check_properties(world: &World, lookup: &SchemaLookup, issues: &mut Vec<Issue>) {
+    let Some(mut query) = world.try_query::<(Entity, &Properties)>() else {
+        return;
+    };
+    for (entity, properties) in query.iter(world) {
+        let Some(schema) = lookup(world, entity) else {
+            continue;
+        };
+        for violation in schema.validate(properties) {
+            let fix = property_fix(&schema, properties, entity, &violation);
+            issues.push(Issue {
+                kind: IssueKind::Property { entity, violation },
+                fix,
+            });
+        }
+    }
+}
+
+/// Clamp out-of-range values, drop unknown keys and fall back to the
+/// field's default for values of the wrong type or choice.
+fn property_fix(
+    schema: &PropertySchema,
+    properties: &Properties,
+    entity: Entity,
+    violation: &SchemaViolation,
+) -> Option<Fix> {
+    let key = violation.key.clone();
+    let field = schema.get(&key);
+    match &violation.problem {
+        Problem::Unknown => Some(Fix::RemoveProperty { entity, key }),
+        Problem::OutOfRange { min, max, .. } => {
+            let value = clamp(properties.get(&key)?, *min, *max)?;
+            Some(Fix::SetProperty { entity, key, value })
+        }
+        Problem::Missing | Problem::TypeMismatch { .. } | Problem::InvalidChoice { .. } => {
+            match field?.default.clone() {
+                Some(value) => Some(Fix::SetProperty { entity, key, value }),
+                None if !field?.required && properties.get(&key).is_some() => {
+                    Some(Fix::RemoveProperty { entity, key })
+                }
+                None => None,
+            }
+        }
+    }
+}
+
+fn clamp(value: &Property, min: f64, max: f64) -> Option<Property> {
+    Some(match value {
+        Property::Float(v) => Property::Float(v.clamp(min, max)),
+        Property::Integer(v) => Property::Integer((*v as f64).clamp(min, max).round() as u64),
+        Property::Ticks(v) => Property::Ticks((*v as f64).clamp(min, max).round() as u64),
+        Property::Curve(curve) => Property::Curve(
+            Curve::from_keys(curve.keys().iter().map(|key| {
+                let mut key = *key;
+                if let CurveValue::Float(v) = key.value {
+                    key.value = CurveValue::Float(v.clamp(min, max));
+                }
+                key
+            }))
+            .ok()?,
+        ),
+        _ => return None,
+    })
+}
@@ -81,6 +81,10 @@ pub enum LunarisError {
     #[error("Invalid state: expected {expected}, found {found}")]
     InvalidState { expected: String, found: String },
 
+    /// Something ended up containing or depending on itself.
+    #[error("Cycle detected: {}", .path.join(" -> "))]
+    CycleDetected { path: Vec<String> },
+
     /// Found a deadlock. Will try to kill that command.
     #[error("Deadlock detected in {component}")]
     DeadlockDetected { component: String },
@@ -152,6 +156,13 @@ pub enum LunarisError {
     #[error("Failed to migrate old save file: {reason}")]
     FailedSaveMigration { reason: String },
 
+    /// A timeline file from another application could not be read.
+    #[error("Invalid {format} data: {reason}")]
+    InvalidInterchange {
+        format: &'static str,
+        reason: String,
+    },
+
     #[error("Invalid path: {reason}")]
     InvalidPath { reason: String },
 
@@ -0,0 +1,48 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A cycle in the directed graph `edges`, as the nodes along it with the
+/// first node repeated at the end. Nodes are visited in sorted order so the
+/// reported cycle is stable.
+pub(crate) fn find_cycle<N: Copy + Eq + Hash + Ord>(edges: &HashMap<N, Vec<N>>) -> Option<Vec<N>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Open,
+        Done,
+    }
+
+    let mut marks: HashMap<N, Mark> = HashMap::new();
+    let mut roots: Vec<N> = edges.keys().copied().collect();
+    roots.sort_unstable();
+    for root in roots {
+        if marks.contains_key(&root) {
+            continue;
+        }
+        // Depth-first walk keeping the current path and, for each node on
+        // it, how many of its edges were already followed.
+        let mut path = vec![(root, 0usize)];
+        marks.insert(root, Mark::Open);
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            let Some(&child) = edges.get(&node).and_then(|children| children.get(*next)) else {
+                marks.insert(node, Mark::Done);
+                path.pop();
+                continue;
+            };
+            *next += 1;
+            match marks.get(&child) {
+                Some(Mark::Done) => {}
+                Some(Mark::Open) => {
+                    let from = path.iter().position(|(n, _)| *n == child)?;
+                    let mut cycle: Vec<N> = path[from..].iter().map(|(n, _)| *n).collect();
+                    cycle.push(child);
+                    return Some(cycle);
+                }
+                None => {
+                    marks.insert(child, Mark::Open);
+                    path.push((child, 0));
+                }
+            }
+        }
+    }
+    None
+}
@@ -1,4 +1,5 @@
 pub mod error;
+pub(crate) mod graph;
 
 pub enum PositionVertical {
     Top,
@@ -0,0 +1,243 @@
+use std::{path::PathBuf, time::Duration};
+
+use bevy_ecs::{entity::Entity, query::With, system::Command, world::World};
+use futures::future::BoxFuture;
+use lunaris_api::{
+    history::UndoHistory,
+    prelude::*,
+    project::autosave::{self, Autosave},
+    request::{DynOrchestrator, OrchestratorProfile, Priority},
+    timeline::{
+        InSequence, Sequence, TimelineEdit, TimelineSpan,
+        elements::{Properties, Property, SourceOffset, TimeRemap, TimelineElement},
+    },
+};
+
+/// Runs every job on the calling thread before returning.
+struct Inline;
+
+impl DynOrchestrator for Inline {
+    fn submit_job_boxed(&self, job: Box<dyn FnOnce() + Send + 'static>, _: Priority) -> Result {
+        job();
+        Ok(())
+    }
+
+    fn submit_async_boxed(&self, fut: BoxFuture<'static, ()>, _: Priority) -> Result {
+        futures::executor::block_on(fut);
+        Ok(())
+    }
+
+    fn join_foreground(&self) -> Result {
+        Ok(())
+    }
+
+    fn set_threads(&self, _: usize, _: usize, _: usize) {}
+
+    fn profile(&self) -> OrchestratorProfile {
+        OrchestratorProfile {
+            immediate: 0,
+            normal: 0,
+            deferred: 0,
+            frame: 0,
+            running_tasks: 0,
+        }
+    }
+}
+
+/// Rejects every job, like an orchestrator whose queue is full.
+struct Full;
+
+impl DynOrchestrator for Full {
+    fn submit_job_boxed(&self, _: Box<dyn FnOnce() + Send + 'static>, _: Priority) -> Result {
+        Err(LunarisError::Busy {
+            resource: "orchestrator".to_string(),
+        })
+    }
+
+    fn submit_async_boxed(&self, _: BoxFuture<'static, ()>, _: Priority) -> Result {
+        Err(LunarisError::Busy {
+            resource: "orchestrator".to_string(),
+        })
+    }
+
+    fn join_foreground(&self) -> Result {
+        Ok(())
+    }
+
+    fn set_threads(&self, _: usize, _: usize, _: usize) {}
+
+    fn profile(&self) -> OrchestratorProfile {
+        Inline.profile()
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lunaris-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn spans(world: &mut World) -> Vec<(u64, u64)> {
+    let mut query = world.query::<&TimelineElement>();
+    let mut spans: Vec<_> = query
+        .iter(world)
+        .map(|e| (e.position.start(), e.position.end()))
+        .collect();
+    spans.sort();
+    spans
+}
+
+#[test]
+fn recovers_undo_of_step_before_snapshot() {
+    let dir = temp_dir("autosave-undo");
+    let mut world = World::new();
+    world.init_resource::<UndoHistory>();
+    Autosave::start(&mut world, &dir, Duration::from_secs(60)).unwrap();
+    let element = world
+        .spawn((
+            TimelineElement {
+                track_num: 0,
+                position: TimelineSpan::new(0, 100).unwrap(),
+            },
+            SourceOffset { ticks: 0 },
+        ))
+        .id();
+
+    TimelineEdit::Razor { element, at: 50 }.apply(&mut world);
+    Autosave::snapshot(&mut world, &Inline).unwrap();
+    assert_eq!(
+        UndoHistory::undo(&mut world).unwrap().as_deref(),
+        Some("Razor")
+    );
+    assert_eq!(spans(&mut world), vec![(0, 100)]);
+
+    // Crash: the session is gone, only the autosave files remain.
+    drop(world);
+    let mut recovered = World::new();
+    let report = autosave::recover(&mut recovered, &dir).unwrap().unwrap();
+    assert_eq!(report.replayed, 1);
+    assert!(!report.truncated);
+    assert_eq!(spans(&mut recovered), vec![(0, 100)]);
+
+    let history = recovered.resource::<UndoHistory>();
+    assert!(!history.can_undo());
+    assert_eq!(history.redo_label(), Some("Razor"));
+    UndoHistory::redo(&mut recovered).unwrap();
+    assert_eq!(spans(&mut recovered), vec![(0, 50), (50, 100)]);
+
+    autosave::discard(&dir).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn undo_without_step_fails_recovery() {
+    let dir = temp_dir("autosave-mismatch");
+    let mut world = World::new();
+    world.init_resource::<UndoHistory>();
+    Autosave::start(&mut world, &dir, Duration::from_secs(60)).unwrap();
+    let element = world
+        .spawn((
+            TimelineElement {
+                track_num: 0,
+                position: TimelineSpan::new(0, 100).unwrap(),
+            },
+            SourceOffset { ticks: 0 },
+        ))
+        .id();
+    TimelineEdit::Razor { element, at: 50 }.apply(&mut world);
+    Autosave::snapshot(&mut world, &Inline).unwrap();
+    UndoHistory::undo(&mut world).unwrap();
+
+    // Without the saved history the journaled undo has nothing to act on.
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("history-")
+        {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+    drop(world);
+    let mut recovered = World::new();
+    assert!(autosave::recover(&mut recovered, &dir).is_err());
+
+    autosave::discard(&dir).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn rejected_snapshot_does_not_stop_autosave() {
+    let dir = temp_dir("autosave-rejected");
+    let mut world = World::new();
+    Autosave::start(&mut world, &dir, Duration::from_secs(60)).unwrap();
+
+    assert!(Autosave::snapshot(&mut world, &Full).is_err());
+    assert!(!autosave::has_recovery(&dir));
+    Autosave::snapshot(&mut world, &Inline).unwrap();
+    assert!(autosave::has_recovery(&dir));
+
+    autosave::discard(&dir).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn replayed_razor_keeps_the_components_of_the_split_half() {
+    let dir = temp_dir("autosave-razor");
+    let mut world = World::new();
+    world.init_resource::<UndoHistory>();
+    Autosave::start(&mut world, &dir, Duration::from_secs(60)).unwrap();
+    let sequence = Sequence::spawn(&mut world, "Nested");
+    let mut properties = Properties::default();
+    properties.insert("opacity", Property::Float(0.5));
+    let element = world
+        .spawn((
+            TimelineElement {
+                track_num: 0,
+                position: TimelineSpan::new(0, 100).unwrap(),
+            },
+            SourceOffset { ticks: 0 },
+            InSequence { sequence },
+            properties,
+            TimeRemap::Speed(2.0),
+        ))
+        .id();
+    Autosave::snapshot(&mut world, &Inline).unwrap();
+    TimelineEdit::Razor { element, at: 50 }.apply(&mut world);
+
+    drop(world);
+    let mut recovered = World::new();
+    let report = autosave::recover(&mut recovered, &dir).unwrap().unwrap();
+    assert_eq!(report.replayed, 1);
+    assert_eq!(spans(&mut recovered), vec![(0, 50), (50, 100)]);
+
+    let sequence = recovered
+        .query_filtered::<Entity, With<Sequence>>()
+        .single(&recovered)
+        .unwrap();
+    let mut halves = recovered.query::<(
+        &TimelineElement,
+        &InSequence,
+        &Properties,
+        &TimeRemap,
+        &SourceOffset,
+    )>();
+    let halves: Vec<_> = halves.iter(&recovered).collect();
+    assert_eq!(halves.len(), 2);
+    for (element, parent, properties, remap, offset) in halves {
+        assert_eq!(parent.sequence, sequence);
+        assert_eq!(properties.get("opacity"), Some(&Property::Float(0.5)));
+        assert_eq!(remap, &TimeRemap::Speed(2.0));
+        let expected = if element.position.start() == 0 {
+            0
+        } else {
+            100
+        };
+        assert_eq!(offset.ticks, expected);
+    }
+
+    autosave::discard(&dir).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use bevy_ecs::{entity::Entity, world::World};
+use lunaris_api::{
+    interchange::{MEDIA_KEY, NAME_KEY, REEL_KEY},
+    timeline::{
+        Marker, Region, Track, TrackKind, Transition, TransitionKind,
+        elements::{Properties, Property, SourceOffset, TimeRemap, TimelineElement},
+    },
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ElementSnapshot {
+    pub track: u64,
+    pub start: u64,
+    pub end: u64,
+    pub offset: u64,
+    pub reel: Option<String>,
+    pub name: Option<String>,
+    pub media: Option<PathBuf>,
+    pub remap: Option<TimeRemap>,
+}
+
+/// Everything an interchange round trip should preserve, in a stable
+/// order.
+#[derive(Debug, PartialEq)]
+pub struct Snapshot {
+    pub tracks: Vec<(String, TrackKind, u64)>,
+    pub elements: Vec<ElementSnapshot>,
+    pub transitions: Vec<(u64, u64, TransitionKind)>,
+    pub markers: Vec<Marker>,
+    pub regions: Vec<Region>,
+}
+
+pub fn snapshot(world: &mut World) -> Snapshot {
+    let tracks = Track::all(world)
+        .into_iter()
+        .map(|(_, t)| (t.name.clone(), t.kind, t.index()))
+        .collect();
+    let mut query = world.query::<(Entity, &TimelineElement)>();
+    let mut elements: Vec<ElementSnapshot> = query
+        .iter(world)
+        .map(|(entity, element)| {
+            let property = |key| world.get::<Properties>(entity)?.get(key).cloned();
+            let string = |key| match property(key) {
+                Some(Property::String(s)) => Some(s),
+                _ => None,
+            };
+            ElementSnapshot {
+                track: element.track_num,
+                start: element.position.start(),
+                end: element.position.end(),
+                offset: world.get::<SourceOffset>(entity).map_or(0, |s| s.ticks),
+                reel: string(REEL_KEY),
+                name: string(NAME_KEY),
+                media: match property(MEDIA_KEY) {
+                    Some(Property::Path(path)) => Some(path),
+                    _ => None,
+                },
+                remap: world.get::<TimeRemap>(entity).cloned(),
+            }
+        })
+        .collect();
+    elements.sort_by_key(|e| (e.track, e.start));
+    let mut query = world.query::<&Transition>();
+    let mut transitions: Vec<_> = query
+        .iter(world)
+        .map(|t| (t.span.start(), t.span.end(), t.kind.clone()))
+        .collect();
+    transitions.sort_by_key(|(start, ..)| *start);
+    Snapshot {
+        tracks,
+        elements,
+        transitions,
+        markers: Marker::all(world, None)
+            .into_iter()
+            .map(|(_, m)| m.clone())
+            .collect(),
+        regions: Region::all(world, None)
+            .into_iter()
+            .map(|(_, r)| r.clone())
+            .collect(),
+    }
+}
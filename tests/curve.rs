@@ -0,0 +1,42 @@
+use lunaris_api::timeline::elements::{Curve, CurveValue, Keyframe};
+use serde_json::{Value, json};
+
+fn key(tick: u64, value: CurveValue) -> Value {
+    serde_json::to_value(Keyframe::new(tick, value)).unwrap()
+}
+
+#[test]
+fn round_trips() {
+    let curve = Curve::from_keys([
+        Keyframe::new(0, CurveValue::Float(1.0)),
+        Keyframe::new(100, CurveValue::Float(3.0)),
+    ])
+    .unwrap();
+    let json = serde_json::to_string(&curve).unwrap();
+    assert_eq!(serde_json::from_str::<Curve>(&json).unwrap(), curve);
+}
+
+#[test]
+fn loading_sorts_keys() {
+    let json = json!({
+        "keys": [
+            key(100, CurveValue::Float(3.0)),
+            key(0, CurveValue::Float(1.0)),
+        ]
+    });
+    let curve: Curve = serde_json::from_value(json).unwrap();
+    let ticks: Vec<_> = curve.keys().iter().map(|k| k.tick).collect();
+    assert_eq!(ticks, vec![0, 100]);
+    assert_eq!(curve.evaluate(50), Some(CurveValue::Float(2.0)));
+}
+
+#[test]
+fn loading_rejects_mixed_value_types() {
+    let json = json!({
+        "keys": [
+            key(0, CurveValue::Float(1.0)),
+            key(100, CurveValue::Vec2([1.0, 2.0])),
+        ]
+    });
+    assert!(serde_json::from_value::<Curve>(json).is_err());
+}
@@ -0,0 +1,346 @@
+use bevy_ecs::{entity::Entity, world::World};
+use lunaris_api::{
+    prelude::*,
+    timeline::{
+        TimelineEdit, TimelineSpan, Track, TrackKind,
+        elements::{BindTo, Properties, Property, SourceOffset, TimelineElement},
+    },
+};
+
+/// `(start, end, source offset)` of an element.
+type Placement = (u64, u64, u64);
+
+fn setup() -> World {
+    let mut world = World::new();
+    Track::push(&mut world, "V1", TrackKind::Video);
+    Track::push(&mut world, "V2", TrackKind::Video);
+    world
+}
+
+fn clip(world: &mut World, track: u64, (start, end, offset): Placement) -> Entity {
+    world
+        .spawn((
+            TimelineElement {
+                track_num: track,
+                position: TimelineSpan::new(start, end).unwrap(),
+            },
+            SourceOffset { ticks: offset },
+        ))
+        .id()
+}
+
+/// Element that is not on the timeline yet.
+fn unplaced(world: &mut World, offset: u64) -> Entity {
+    world.spawn(SourceOffset { ticks: offset }).id()
+}
+
+fn track(world: &mut World, track: u64) -> Vec<Placement> {
+    let mut query = world.query::<(&TimelineElement, Option<&SourceOffset>)>();
+    let mut placements: Vec<_> = query
+        .iter(world)
+        .filter(|(element, _)| element.track_num == track)
+        .map(|(element, offset)| {
+            (
+                element.position.start(),
+                element.position.end(),
+                offset.map_or(0, |o| o.ticks),
+            )
+        })
+        .collect();
+    placements.sort();
+    placements
+}
+
+fn lock(world: &mut World, index: u64) {
+    let entity = Track::find(world, index).unwrap();
+    world.get_mut::<Track>(entity).unwrap().locked = true;
+}
+
+#[test]
+fn razor_splits_and_undoes() {
+    let mut world = setup();
+    let element = clip(&mut world, 0, (0, 100, 10));
+    let mut properties = Properties::default();
+    properties.insert("name", Property::String("A".into()));
+    world.entity_mut(element).insert(properties);
+
+    let token = TimelineEdit::Razor { element, at: 40 }
+        .execute(&mut world)
+        .unwrap();
+    assert_eq!(token.label, "Razor");
+    assert_eq!(track(&mut world, 0), vec![(0, 40, 10), (40, 100, 50)]);
+    let right = token
+        .changes
+        .iter()
+        .map(|c| Entity::from_bits(c.entity))
+        .find(|e| *e != element)
+        .unwrap();
+    assert_eq!(
+        world.get::<Properties>(right).unwrap().get("name"),
+        Some(&Property::String("A".into()))
+    );
+
+    token.undo(&mut world).unwrap();
+    assert_eq!(track(&mut world, 0), vec![(0, 100, 10)]);
+    token.redo(&mut world).unwrap();
+    assert_eq!(track(&mut world, 0), vec![(0, 40, 10), (40, 100, 50)]);
+}
+
+#[test]
+fn ripple_delete_closes_the_gap() {
+    let mut world = setup();
+    let element = clip(&mut world, 0, (0, 100, 0));
+    clip(&mut world, 0, (100, 150, 0));
+    clip(&mut world, 0, (200, 250, 5));
+    clip(&mut world, 1, (300, 400, 0));
+
+    TimelineEdit::RippleDelete { element }
+        .execute(&mut world)
+        .unwrap();
+    assert_eq!(track(&mut world, 0), vec![(0, 50, 0), (100, 150, 5)]);
+    assert_eq!(track(&mut world, 1), vec![(300, 400, 0)]);
+}
+
+#[test]
+fn roll_moves_the_cut() {
+    let mut world = setup();
+    let outgoing = clip(&mut world, 0, (0, 100, 0));
+    let incoming = clip(&mut world, 0, (100, 200, 50));
+
+    TimelineEdit::Roll {
+        outgoing,
+        incoming,
+        to: 120,
+    }
+    .execute(&mut world)
+    .unwrap();
+    assert_eq!(track(&mut world, 0), vec![(0, 120, 0), (120, 200, 70)]);
+
+    for to in [0, 200] {
+        let edit = TimelineEdit::Roll {
+            outgoing,
+            incoming,
+            to,
+        };
+        assert!(edit.execute(&mut world).is_err());
+    }
+    let edit = TimelineEdit::Roll {
+        outgoing: incoming,
+        incoming: outgoing,
+        to: 120,
+    };
+    assert!(edit.execute(&mut world).is_err());
+    assert_eq!(track(&mut world, 0), vec![(0, 120, 0), (120, 200, 70)]);
+}
+
+#[test]
+fn slip_changes_only_the_source() {
+    let mut world = setup();
+    let element = clip(&mut world, 0, (0, 100, 50));
+
+    TimelineEdit::Slip {
+        element,
+        delta: -20,
+    }
+    .execute(&mut world)
+    .unwrap();
+    assert_eq!(track(&mut world, 0), vec![(0, 100, 30)]);
+
+    let edit = TimelineEdit::Slip {
+        element,
+        delta: -100,
+    };
+    assert!(edit.execute(&mut world).is_err());
+    assert_eq!(track(&mut world, 0), vec![(0, 100, 30)]);
+}
+
+#[test]
+fn slide_trims_neighbours() {
+    let mut world = setup();
+    clip(&mut world, 0, (0, 100, 0));
+    let element = clip(&mut world, 0, (100, 200, 0));
+    clip(&mut world, 0, (200, 300, 0));
+
+    TimelineEdit::Slide { element, delta: 20 }
+        .execute(&mut world)
+        .unwrap();
+    assert_eq!(
+        track(&mut world, 0),
+        vec![(0, 120, 0), (120, 220, 0), (220, 300, 20)]
+    );
+
+    let edit = TimelineEdit::Slide {
+        element,
+        delta: -120,
+    };
+    assert!(edit.execute(&mut world).is_err());
+}
+
+#[test]
+fn insert_splits_and_pushes_right() {
+    let mut world = setup();
+    clip(&mut world, 0, (0, 100, 0));
+    clip(&mut world, 0, (100, 200, 0));
+    let element = unplaced(&mut world, 7);
+
+    TimelineEdit::Insert {
+        element,
+        track: 0,
+        at: 50,
+        duration: 30,
+    }
+    .execute(&mut world)
+    .unwrap();
+    assert_eq!(
+        track(&mut world, 0),
+        vec![(0, 50, 0), (50, 80, 7), (80, 130, 50), (130, 230, 0)]
+    );
+}
+
+#[test]
+fn overwrite_replaces_material() {
+    let mut world = setup();
+    clip(&mut world, 0, (0, 100, 0));
+    let element = unplaced(&mut world, 0);
+
+    TimelineEdit::Overwrite {
+        element,
+        track: 0,
+        span: TimelineSpan::new(30, 60).unwrap(),
+    }
+    .execute(&mut world)
+    .unwrap();
+    assert_eq!(
+        track(&mut world, 0),
+        vec![(0, 30, 0), (30, 60, 0), (60, 100, 60)]
+    );
+}
+
+#[test]
+fn lift_leaves_a_gap() {
+    let mut world = setup();
+    clip(&mut world, 0, (0, 100, 0));
+    clip(&mut world, 0, (100, 200, 0));
+
+    TimelineEdit::Lift {
+        track: 0,
+        span: TimelineSpan::new(50, 150).unwrap(),
+    }
+    .execute(&mut world)
+    .unwrap();
+    assert_eq!(track(&mut world, 0), vec![(0, 50, 0), (150, 200, 50)]);
+}
+
+#[test]
+fn extract_closes_the_gap() {
+    let mut world = setup();
+    clip(&mut world, 0, (0, 100, 0));
+    clip(&mut world, 0, (100, 200, 0));
+    clip(&mut world, 0, (300, 400, 0));
+
+    TimelineEdit::Extract {
+        track: 0,
+        span: TimelineSpan::new(50, 150).unwrap(),
+    }
+    .execute(&mut world)
+    .unwrap();
+    assert_eq!(
+        track(&mut world, 0),
+        vec![(0, 50, 0), (50, 100, 50), (200, 300, 0)]
+    );
+}
+
+#[test]
+fn locked_tracks_refuse_edits() {
+    let mut world = setup();
+    let element = clip(&mut world, 0, (0, 100, 0));
+    lock(&mut world, 0);
+
+    let edits = [
+        TimelineEdit::Razor { element, at: 50 },
+        TimelineEdit::RippleDelete { element },
+        TimelineEdit::Slip { element, delta: 10 },
+        TimelineEdit::Lift {
+            track: 0,
+            span: TimelineSpan::new(0, 50).unwrap(),
+        },
+    ];
+    for edit in edits {
+        assert!(matches!(
+            edit.execute(&mut world),
+            Err(LunarisError::PermissionDenied { .. })
+        ));
+    }
+    assert_eq!(track(&mut world, 0), vec![(0, 100, 0)]);
+}
+
+#[test]
+fn failed_edit_rolls_back() {
+    let mut world = setup();
+    clip(&mut world, 0, (0, 100, 0));
+    let element = clip(&mut world, 0, (100, 200, 0));
+    clip(&mut world, 0, (200, 250, 0));
+
+    // The left neighbour is trimmed before the right one is found too short.
+    let edit = TimelineEdit::Slide { element, delta: 60 };
+    assert!(edit.execute(&mut world).is_err());
+    assert_eq!(
+        track(&mut world, 0),
+        vec![(0, 100, 0), (100, 200, 0), (200, 250, 0)]
+    );
+}
+
+#[test]
+fn failed_edit_despawns_new_elements() {
+    let mut world = setup();
+    clip(&mut world, 0, (0, 100, 0));
+    let parent = clip(&mut world, 0, (100, 200, 0));
+    let child = clip(&mut world, 1, (100, 150, 0));
+    world.entity_mut(child).insert(BindTo { id: parent });
+    lock(&mut world, 1);
+    let element = unplaced(&mut world, 0);
+    let entities = world.entities().len();
+
+    // Moving `parent` would move its child on the locked track.
+    let edit = TimelineEdit::Insert {
+        element,
+        track: 0,
+        at: 50,
+        duration: 30,
+    };
+    assert!(matches!(
+        edit.execute(&mut world),
+        Err(LunarisError::PermissionDenied { .. })
+    ));
+    assert_eq!(track(&mut world, 0), vec![(0, 100, 0), (100, 200, 0)]);
+    assert_eq!(track(&mut world, 1), vec![(100, 150, 0)]);
+    assert_eq!(world.entities().len(), entities);
+    assert!(world.get::<TimelineElement>(element).is_none());
+}
+
+#[test]
+fn shifts_too_long_for_i64_fail() {
+    let mut world = setup();
+    clip(&mut world, 0, (0, 100, 0));
+    let element = unplaced(&mut world, 0);
+
+    let edit = TimelineEdit::Insert {
+        element,
+        track: 0,
+        at: 0,
+        duration: u64::MAX,
+    };
+    assert!(matches!(
+        edit.execute(&mut world),
+        Err(LunarisError::InvalidArgument { .. })
+    ));
+    let edit = TimelineEdit::Extract {
+        track: 0,
+        span: TimelineSpan::new(0, u64::MAX).unwrap(),
+    };
+    assert!(matches!(
+        edit.execute(&mut world),
+        Err(LunarisError::InvalidArgument { .. })
+    ));
+    assert_eq!(track(&mut world, 0), vec![(0, 100, 0)]);
+}
@@ -0,0 +1,167 @@
+use std::path::PathBuf;
+
+use bevy_ecs::world::World;
+use lunaris_api::{
+    interchange::edl,
+    timeline::{
+        FrameRate, Timebase, TimelineSpan, Track, TrackKind, TransitionKind,
+        elements::{SourceOffset, TimeRemap, TimelineElement},
+    },
+};
+
+mod common;
+
+use common::snapshot;
+
+const SIMPLE: &str = include_str!("fixtures/edl/simple.edl");
+
+fn frames(rate: FrameRate, frames: u64) -> u64 {
+    rate.frame_to_ticks(frames, Timebase::default().tps())
+}
+
+#[test]
+fn imports_fixture() {
+    let f = |n| frames(FrameRate::FPS_24, n);
+    let mut world = World::new();
+    let imported = edl::import(&mut world, SIMPLE, FrameRate::FPS_24).unwrap();
+    assert_eq!(imported.tracks.len(), 3);
+    assert_eq!(imported.elements.len(), 6);
+    let lines: Vec<_> = imported.warnings.iter().map(|w| w.line).collect();
+    assert_eq!(lines, vec![Some(24), Some(25)]);
+
+    let snapshot = snapshot(&mut world);
+    assert_eq!(
+        snapshot.tracks,
+        vec![
+            ("V".to_string(), TrackKind::Video, 0),
+            ("A1".to_string(), TrackKind::Audio, 1),
+            ("A2".to_string(), TrackKind::Audio, 2),
+        ]
+    );
+    let spans: Vec<_> = snapshot
+        .elements
+        .iter()
+        .map(|e| (e.track, e.start, e.end, e.offset, e.reel.as_deref()))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            (0, 0, f(48), 0, Some("TAPE01")),
+            (0, f(48), f(120), f(100), Some("TAPE02")),
+            (0, f(144), f(156), f(12), None),
+            (0, f(192), f(216), f(24), Some("TAPE03")),
+            (1, 0, f(120), 0, Some("TAPE01")),
+            (2, 0, f(120), 0, Some("TAPE01")),
+        ]
+    );
+    let names: Vec<_> = snapshot
+        .elements
+        .iter()
+        .map(|e| e.name.as_deref())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            Some("A"),
+            Some("B"),
+            Some("C"),
+            None,
+            Some("A audio"),
+            Some("A audio")
+        ]
+    );
+    assert_eq!(
+        snapshot.elements[0].media,
+        Some(PathBuf::from("/media/a.mov"))
+    );
+    assert_eq!(
+        snapshot.elements[1].media,
+        Some(PathBuf::from("/media/b.mov"))
+    );
+    assert_eq!(snapshot.elements[2].remap, Some(TimeRemap::Speed(2.0)));
+    assert_eq!(
+        snapshot.transitions,
+        vec![(f(48), f(60), TransitionKind::Dissolve)]
+    );
+}
+
+#[test]
+fn fixture_round_trips() {
+    let mut world = World::new();
+    edl::import(&mut world, SIMPLE, FrameRate::FPS_24).unwrap();
+    let exported = edl::export(&world, "Simple", FrameRate::FPS_24, false).unwrap();
+    assert!(exported.warnings.is_empty(), "{:?}", exported.warnings);
+
+    let mut reimported = World::new();
+    let imported = edl::import(&mut reimported, &exported.text, FrameRate::FPS_24).unwrap();
+    assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+    assert_eq!(snapshot(&mut world), snapshot(&mut reimported));
+
+    let again = edl::export(&reimported, "Simple", FrameRate::FPS_24, false).unwrap();
+    assert_eq!(exported.text, again.text);
+}
+
+#[test]
+fn drop_frame_round_trips() {
+    let rate = FrameRate::FPS_29_97;
+    let mut world = World::new();
+    edl::import(&mut world, SIMPLE, rate).unwrap();
+    let exported = edl::export(&world, "Simple", rate, true).unwrap();
+    assert!(exported.text.contains("FCM: DROP FRAME"));
+    assert!(exported.text.contains("01:00:00;00"));
+
+    let mut reimported = World::new();
+    edl::import(&mut reimported, &exported.text, rate).unwrap();
+    assert_eq!(snapshot(&mut world), snapshot(&mut reimported));
+}
+
+#[test]
+fn reads_drop_frame_timecode() {
+    let rate = FrameRate::FPS_29_97;
+    let edl = "TITLE: Drop\n\
+               FCM: DROP FRAME\n\
+               001  AX       V     C        00:00:59;29 00:01:00;02 01:00:59;29 01:01:00;02\n";
+    let mut world = World::new();
+    let imported = edl::import(&mut world, edl, rate).unwrap();
+    let element = world.get::<TimelineElement>(imported.elements[0]).unwrap();
+    assert_eq!(element.position.start(), frames(rate, 1799));
+    assert_eq!(element.position.end(), frames(rate, 1800));
+    assert_eq!(
+        world
+            .get::<SourceOffset>(imported.elements[0])
+            .unwrap()
+            .ticks,
+        frames(rate, 1799)
+    );
+}
+
+#[test]
+fn warns_for_elements_without_track() {
+    let mut world = World::new();
+    edl::import(&mut world, SIMPLE, FrameRate::FPS_24).unwrap();
+    world.spawn(TimelineElement {
+        track_num: 9,
+        position: TimelineSpan::new(0, frames(FrameRate::FPS_24, 24)).unwrap(),
+    });
+    let exported = edl::export(&world, "Simple", FrameRate::FPS_24, false).unwrap();
+    assert_eq!(exported.warnings.len(), 1, "{:?}", exported.warnings);
+    assert!(exported.warnings[0].message.contains("track 9"));
+}
+
+#[test]
+fn warns_for_source_before_media_start() {
+    let rate = FrameRate::FPS_24;
+    let mut world = World::new();
+    Track::push(&mut world, "V", TrackKind::Video);
+    world.spawn((
+        TimelineElement {
+            track_num: 0,
+            position: TimelineSpan::new(0, frames(rate, 24)).unwrap(),
+        },
+        SourceOffset { ticks: 0 },
+        TimeRemap::REVERSE,
+    ));
+    let exported = edl::export(&world, "Reverse", rate, false).unwrap();
+    assert_eq!(exported.warnings.len(), 1, "{:?}", exported.warnings);
+    assert!(exported.warnings[0].message.contains("no source"));
+}
@@ -0,0 +1,157 @@
+use bevy_ecs::{entity::Entity, schedule::Schedule, world::World};
+use lunaris_api::{
+    prelude::*,
+    timeline::{
+        BindIndex, Transform2D,
+        elements::{BindTo, Curve, CurveValue, Keyframe, Properties, Property},
+        hierarchy::{
+            POSITION_KEY, ROTATION_KEY, SCALE_KEY, check_bind_cycles, sync_bind_index,
+            world_transform,
+        },
+    },
+};
+
+fn close(a: [f64; 2], b: [f64; 2]) -> bool {
+    (a[0] - b[0]).abs() < 1e-9 && (a[1] - b[1]).abs() < 1e-9
+}
+
+fn layer(world: &mut World, position: [f64; 2], scale: f64, rotation: f64) -> Entity {
+    let mut properties = Properties::default();
+    properties.insert(
+        POSITION_KEY,
+        Property::Curve(Curve::constant(CurveValue::Vec2(position))),
+    );
+    properties.insert(SCALE_KEY, Property::Float(scale));
+    properties.insert(ROTATION_KEY, Property::Float(rotation));
+    world.spawn(properties).id()
+}
+
+#[test]
+fn children_inherit_their_parents_transform() {
+    let mut world = World::new();
+    let parent = layer(&mut world, [100.0, 0.0], 2.0, 90.0);
+    let child = layer(&mut world, [10.0, 0.0], 0.5, 45.0);
+    let grandchild = world.spawn_empty().id();
+    BindTo::attach(&mut world, child, parent).unwrap();
+    BindTo::attach(&mut world, grandchild, child).unwrap();
+
+    let transform = world_transform(&world, child, 0).unwrap();
+    // Scaled by 2 and turned a quarter counter-clockwise, then moved.
+    assert!(close(transform.translation, [100.0, 20.0]));
+    assert!(close(transform.scale, [1.0, 1.0]));
+    assert!((transform.rotation - 135.0).abs() < 1e-9);
+
+    // Without properties of its own the grandchild sits where its parent is.
+    let inherited = world_transform(&world, grandchild, 0).unwrap();
+    assert!(close(inherited.translation, transform.translation));
+    assert!((inherited.rotation - transform.rotation).abs() < 1e-9);
+
+    BindTo::detach(&mut world, child);
+    let own = world_transform(&world, child, 0).unwrap();
+    assert!(close(own.translation, [10.0, 0.0]));
+}
+
+#[test]
+fn parents_are_evaluated_at_the_same_tick() {
+    let mut world = World::new();
+    let mut properties = Properties::default();
+    properties.insert(
+        POSITION_KEY,
+        Property::Curve(
+            Curve::from_keys([
+                Keyframe::new(0, CurveValue::Vec2([0.0, 0.0])),
+                Keyframe::new(10, CurveValue::Vec2([100.0, 0.0])),
+            ])
+            .unwrap(),
+        ),
+    );
+    let parent = world.spawn(properties).id();
+    let child = layer(&mut world, [0.0, 5.0], 1.0, 0.0);
+    BindTo::attach(&mut world, child, parent).unwrap();
+    assert!(close(
+        world_transform(&world, child, 5).unwrap().translation,
+        [50.0, 5.0]
+    ));
+}
+
+#[test]
+fn transform_applies_scale_then_rotation_then_translation() {
+    let transform = Transform2D {
+        translation: [1.0, 2.0],
+        scale: [2.0, 3.0],
+        rotation: 90.0,
+    };
+    assert!(close(transform.apply([1.0, 1.0]), [-2.0, 4.0]));
+    assert!(close(Transform2D::IDENTITY.apply([3.0, 4.0]), [3.0, 4.0]));
+    // Mistyped properties keep their identity value.
+    let mut properties = Properties::default();
+    properties.insert(ROTATION_KEY, Property::String("left".to_string()));
+    assert_eq!(
+        Transform2D::from_properties(&properties, 0),
+        Transform2D::IDENTITY
+    );
+}
+
+#[test]
+fn attach_refuses_cycles() {
+    let mut world = World::new();
+    let a = world.spawn_empty().id();
+    let b = world.spawn_empty().id();
+    let c = world.spawn_empty().id();
+    BindTo::attach(&mut world, b, a).unwrap();
+    BindTo::attach(&mut world, c, b).unwrap();
+    assert!(matches!(
+        BindTo::attach(&mut world, a, c),
+        Err(LunarisError::CycleDetected { .. })
+    ));
+    assert!(matches!(
+        BindTo::attach(&mut world, a, a),
+        Err(LunarisError::CycleDetected { .. })
+    ));
+    assert!(check_bind_cycles(&world).is_ok());
+
+    // Cycles inserted behind attach's back are still caught.
+    world.entity_mut(a).insert(BindTo { id: c });
+    assert!(check_bind_cycles(&world).is_err());
+    assert!(matches!(
+        world_transform(&world, a, 0),
+        Err(LunarisError::CycleDetected { .. })
+    ));
+}
+
+#[test]
+fn bind_index_follows_binds() {
+    let mut world = World::new();
+    world.init_resource::<BindIndex>();
+    let mut schedule = Schedule::default();
+    schedule.add_systems(sync_bind_index);
+    let root = world.spawn_empty().id();
+    let a = world.spawn_empty().id();
+    let b = world.spawn_empty().id();
+    let c = world.spawn_empty().id();
+    BindTo::attach(&mut world, a, root).unwrap();
+    BindTo::attach(&mut world, b, root).unwrap();
+    BindTo::attach(&mut world, c, a).unwrap();
+    schedule.run(&mut world);
+    world.clear_trackers();
+
+    let index = world.resource::<BindIndex>();
+    assert_eq!(index.children(root), [a, b]);
+    assert_eq!(index.parent(c), Some(a));
+    assert_eq!(index.descendants(root), vec![a, b, c]);
+
+    // Rebinding moves the child, unbinding drops it.
+    BindTo::attach(&mut world, c, b).unwrap();
+    BindTo::detach(&mut world, a);
+    schedule.run(&mut world);
+    world.clear_trackers();
+    let index = world.resource::<BindIndex>();
+    assert_eq!(index.children(root), [b]);
+    assert_eq!(index.children(b), [c]);
+    assert_eq!(index.parent(a), None);
+    assert_eq!(index.len(), 2);
+    assert_eq!(
+        BindIndex::build(&world).descendants(root),
+        index.descendants(root)
+    );
+}
@@ -0,0 +1,180 @@
+use bevy_ecs::{
+    event::{Event, Events},
+    resource::Resource,
+    system::RunSystemOnce,
+    world::World,
+};
+use lunaris_api::{
+    history::{UndoHistory, Undoable, record_undo_events},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Resource, Default)]
+struct Counter(i64);
+
+/// Adds its value to the [`Counter`].
+#[derive(Event, Serialize, Deserialize)]
+struct Add(i64);
+
+impl Undoable for Add {
+    const KIND: &'static str = "test.add";
+
+    fn undo(&self, world: &mut World) -> Result {
+        world.resource_mut::<Counter>().0 -= self.0;
+        Ok(())
+    }
+
+    fn redo(&self, world: &mut World) -> Result {
+        world.resource_mut::<Counter>().0 += self.0;
+        Ok(())
+    }
+
+    fn label(&self) -> String {
+        format!("Add {}", self.0)
+    }
+}
+
+fn world(history: UndoHistory) -> World {
+    let mut world = World::new();
+    world.init_resource::<Counter>();
+    world.insert_resource(history);
+    world
+}
+
+fn add(world: &mut World, value: i64) {
+    world.resource_mut::<Counter>().0 += value;
+    world
+        .resource_mut::<UndoHistory>()
+        .push(Add(value))
+        .unwrap();
+}
+
+fn counter(world: &World) -> i64 {
+    world.resource::<Counter>().0
+}
+
+#[test]
+fn undo_and_redo_single_steps() {
+    let mut world = world(UndoHistory::default());
+    add(&mut world, 1);
+    add(&mut world, 10);
+    assert_eq!(counter(&world), 11);
+
+    assert_eq!(
+        UndoHistory::undo(&mut world).unwrap().as_deref(),
+        Some("Add 10")
+    );
+    assert_eq!(counter(&world), 1);
+    assert_eq!(
+        UndoHistory::redo(&mut world).unwrap().as_deref(),
+        Some("Add 10")
+    );
+    assert_eq!(counter(&world), 11);
+    assert_eq!(UndoHistory::redo(&mut world).unwrap(), None);
+}
+
+#[test]
+fn groups_undo_together() {
+    let mut world = world(UndoHistory::default());
+    {
+        let mut history = world.resource_mut::<UndoHistory>();
+        history.begin_group("Outer");
+        history.begin_group("Inner");
+    }
+    add(&mut world, 1);
+    world.resource_mut::<UndoHistory>().end_group().unwrap();
+    add(&mut world, 2);
+
+    // Stepping while a group is open would split it.
+    assert!(UndoHistory::undo(&mut world).is_err());
+    world.resource_mut::<UndoHistory>().end_group().unwrap();
+    assert!(world.resource_mut::<UndoHistory>().end_group().is_err());
+
+    assert_eq!(world.resource::<UndoHistory>().undo_label(), Some("Outer"));
+    UndoHistory::undo(&mut world).unwrap();
+    assert_eq!(counter(&world), 0);
+    assert!(!world.resource::<UndoHistory>().can_undo());
+    UndoHistory::redo(&mut world).unwrap();
+    assert_eq!(counter(&world), 3);
+}
+
+#[test]
+fn empty_group_adds_no_step() {
+    let mut world = world(UndoHistory::default());
+    let mut history = world.resource_mut::<UndoHistory>();
+    history.begin_group("Nothing");
+    history.end_group().unwrap();
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn limit_drops_oldest_steps() {
+    let mut world = world(UndoHistory::with_limit(2));
+    for value in [1, 10, 100] {
+        add(&mut world, value);
+    }
+    while UndoHistory::undo(&mut world).unwrap().is_some() {}
+    assert_eq!(counter(&world), 1);
+
+    while UndoHistory::redo(&mut world).unwrap().is_some() {}
+    world.resource_mut::<UndoHistory>().set_limit(1);
+    while UndoHistory::undo(&mut world).unwrap().is_some() {}
+    assert_eq!(counter(&world), 11);
+}
+
+#[test]
+fn push_after_undo_truncates_redo() {
+    let mut world = world(UndoHistory::default());
+    add(&mut world, 1);
+    add(&mut world, 10);
+    UndoHistory::undo(&mut world).unwrap();
+    assert!(world.resource::<UndoHistory>().can_redo());
+
+    add(&mut world, 100);
+    let history = world.resource::<UndoHistory>();
+    assert!(!history.can_redo());
+    assert_eq!(history.undo_label(), Some("Add 100"));
+    assert_eq!(UndoHistory::redo(&mut world).unwrap(), None);
+    assert_eq!(counter(&world), 101);
+}
+
+#[test]
+fn history_round_trips_through_bytes() {
+    let mut world = world(UndoHistory::default());
+    add(&mut world, 1);
+    add(&mut world, 10);
+    UndoHistory::undo(&mut world).unwrap();
+    let bytes = world.resource::<UndoHistory>().to_bytes().unwrap();
+
+    let mut loaded = UndoHistory::default();
+    loaded.register::<Add>();
+    loaded.load_bytes(&bytes).unwrap();
+    world.insert_resource(loaded);
+    assert_eq!(
+        UndoHistory::redo(&mut world).unwrap().as_deref(),
+        Some("Add 10")
+    );
+    UndoHistory::undo(&mut world).unwrap();
+    UndoHistory::undo(&mut world).unwrap();
+    assert_eq!(counter(&world), 0);
+}
+
+#[test]
+fn undo_events_are_recorded() {
+    let mut world = world(UndoHistory::default());
+    world.init_resource::<Events<Add>>();
+    for value in [1, 10] {
+        world.resource_mut::<Counter>().0 += value;
+        world.send_event(Add(value));
+    }
+    world.run_system_once(record_undo_events::<Add>).unwrap();
+    assert!(world.resource::<Events<Add>>().is_empty());
+
+    assert_eq!(
+        UndoHistory::undo(&mut world).unwrap().as_deref(),
+        Some("Add 10")
+    );
+    UndoHistory::undo(&mut world).unwrap();
+    assert_eq!(counter(&world), 0);
+}
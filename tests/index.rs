@@ -0,0 +1,161 @@
+use bevy_ecs::{entity::Entity, schedule::Schedule, world::World};
+use lunaris_api::timeline::{
+    InSequence, Sequence, TimelineIndex, TimelineSpan, elements::TimelineElement,
+    index::sync_timeline_index,
+};
+
+fn span(start: u64, end: u64) -> TimelineSpan {
+    TimelineSpan::new(start, end).unwrap()
+}
+
+fn element(world: &mut World, track: u64, start: u64, end: u64) -> Entity {
+    world
+        .spawn(TimelineElement {
+            track_num: track,
+            position: span(start, end),
+        })
+        .id()
+}
+
+/// Keeps one system instance across frames, so change detection only sees
+/// what happened since the previous sync, like it does in a host app.
+struct Frames {
+    schedule: Schedule,
+}
+
+impl Frames {
+    fn new(world: &mut World) -> Self {
+        world.init_resource::<TimelineIndex>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(sync_timeline_index);
+        Self { schedule }
+    }
+
+    fn sync(&mut self, world: &mut World) {
+        self.schedule.run(world);
+        world.clear_trackers();
+    }
+}
+
+/// Small deterministic generator, so the tree is checked against many
+/// layouts without a dependency.
+fn lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+#[test]
+fn tree_matches_a_linear_scan() {
+    let mut world = World::new();
+    let mut frames = Frames::new(&mut world);
+    let mut state = 7;
+    let mut elements = Vec::new();
+    for _ in 0..300 {
+        let track = lcg(&mut state) % 3;
+        let start = lcg(&mut state) % 1000;
+        let end = start + lcg(&mut state) % 80;
+        elements.push((
+            element(&mut world, track, start, end),
+            track,
+            span(start, end),
+        ));
+    }
+    frames.sync(&mut world);
+    let index = world.resource::<TimelineIndex>();
+    assert_eq!(index.len(), 300);
+
+    let expect = |matches: &dyn Fn(&TimelineSpan) -> bool, track: u64| {
+        let mut hits: Vec<_> = elements
+            .iter()
+            .filter(|(_, t, s)| *t == track && matches(s))
+            .map(|(e, _, s)| (s.start(), *e))
+            .collect();
+        hits.sort();
+        hits.into_iter().map(|(_, e)| e).collect::<Vec<_>>()
+    };
+    let by_start = |mut hits: Vec<Entity>| {
+        hits.sort_by_key(|e| (index.get(*e).unwrap().1.start(), *e));
+        hits
+    };
+    for tick in (0..1100).step_by(7) {
+        for track in 0..3 {
+            assert_eq!(
+                by_start(index.at(track, tick)),
+                expect(&|s| s.contains(tick), track)
+            );
+            let query = span(tick, tick + 25);
+            assert_eq!(
+                by_start(index.overlapping(track, &query)),
+                expect(&|s| s.intersects(&query), track)
+            );
+        }
+    }
+}
+
+#[test]
+fn sync_follows_changes_and_removals() {
+    let mut world = World::new();
+    let mut frames = Frames::new(&mut world);
+    let a = element(&mut world, 0, 0, 100);
+    let b = element(&mut world, 0, 100, 200);
+    frames.sync(&mut world);
+    assert_eq!(world.resource::<TimelineIndex>().at(0, 150), vec![b]);
+
+    // Moving to another track leaves the old one.
+    world.get_mut::<TimelineElement>(b).unwrap().track_num = 2;
+    frames.sync(&mut world);
+    let index = world.resource::<TimelineIndex>();
+    assert!(index.at(0, 150).is_empty());
+    assert_eq!(index.at(2, 150), vec![b]);
+    assert_eq!(index.get(b), Some((2, span(100, 200))));
+    assert_eq!(index.tracks().collect::<Vec<_>>(), vec![0, 2]);
+
+    // Removing the last element of a track drops the track.
+    world.entity_mut(b).remove::<TimelineElement>();
+    frames.sync(&mut world);
+    let index = world.resource::<TimelineIndex>();
+    assert_eq!(index.get(b), None);
+    assert_eq!(index.tracks().collect::<Vec<_>>(), vec![0]);
+
+    world.despawn(a);
+    frames.sync(&mut world);
+    assert!(world.resource::<TimelineIndex>().is_empty());
+}
+
+#[test]
+fn sync_follows_elements_between_sequences() {
+    let mut world = World::new();
+    let mut frames = Frames::new(&mut world);
+    let sequence = Sequence::spawn(&mut world, "Nested");
+    let element = element(&mut world, 0, 0, 100);
+    frames.sync(&mut world);
+    assert_eq!(world.resource::<TimelineIndex>().at(0, 50), vec![element]);
+
+    world.entity_mut(element).insert(InSequence { sequence });
+    frames.sync(&mut world);
+    let index = world.resource::<TimelineIndex>();
+    assert!(index.at(0, 50).is_empty());
+    assert_eq!(index.at_in(Some(sequence), 0, 50), vec![element]);
+    assert_eq!(index.at_all_in(Some(sequence), 50), vec![(0, element)]);
+
+    world.entity_mut(element).remove::<InSequence>();
+    frames.sync(&mut world);
+    let index = world.resource::<TimelineIndex>();
+    assert_eq!(index.at(0, 50), vec![element]);
+    assert!(index.tracks_in(Some(sequence)).next().is_none());
+}
+
+#[test]
+fn empty_spans_are_tracked_but_never_hit() {
+    let mut world = World::new();
+    let mut frames = Frames::new(&mut world);
+    let empty = element(&mut world, 0, 50, 50);
+    frames.sync(&mut world);
+    let index = world.resource::<TimelineIndex>();
+    assert_eq!(index.get(empty), Some((0, span(50, 50))));
+    assert!(index.at(0, 50).is_empty());
+    assert!(index.overlapping(0, &span(0, 100)).is_empty());
+    assert!(index.overlapping(0, &TimelineSpan::empty_at(50)).is_empty());
+}
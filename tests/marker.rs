@@ -0,0 +1,154 @@
+use bevy_ecs::{entity::Entity, world::World};
+use lunaris_api::timeline::{
+    CompoundClip, InSequence, Marker, Playhead, PlayheadTarget, Region, Sequence, TimelineSpan,
+    Track, TrackKind,
+    elements::TimelineElement,
+    marker::{Chapter, chapters, chapters_to_text, chapters_to_webvtt},
+};
+
+fn span(start: u64, end: u64) -> TimelineSpan {
+    TimelineSpan::new(start, end).unwrap()
+}
+
+#[test]
+fn reorder_moves_track_markers_and_regions() {
+    let mut world = World::new();
+    let v1 = Track::push(&mut world, "V1", TrackKind::Video);
+    Track::push(&mut world, "V2", TrackKind::Video);
+    Track::push(&mut world, "V3", TrackKind::Video);
+    let first = world.spawn(Marker::new(10, "first").on_track(0)).id();
+    let third = world.spawn(Marker::new(20, "third").on_track(2)).id();
+    let global = world.spawn(Marker::new(30, "global")).id();
+    let region = world
+        .spawn(Region::new(span(0, 50), "second").on_track(1))
+        .id();
+
+    // A nested sequence's markers keep their own numbering.
+    let sequence = Sequence::spawn(&mut world, "Nested");
+    let nested = world
+        .spawn((
+            Marker::new(0, "nested").on_track(0),
+            InSequence { sequence },
+        ))
+        .id();
+
+    Track::reorder(&mut world, v1, 2).unwrap();
+    let track = |world: &World, entity| world.get::<Marker>(entity).unwrap().track;
+    assert_eq!(track(&world, first), Some(2));
+    assert_eq!(track(&world, third), Some(1));
+    assert_eq!(track(&world, global), None);
+    assert_eq!(track(&world, nested), Some(0));
+    assert_eq!(world.get::<Region>(region).unwrap().track, Some(0));
+}
+
+#[test]
+fn last_chapter_ends_with_the_root_timeline() {
+    let mut world = World::new();
+    world.spawn(TimelineElement {
+        track_num: 0,
+        position: span(0, 100),
+    });
+    let sequence = Sequence::spawn(&mut world, "Nested");
+    world.spawn((
+        TimelineElement {
+            track_num: 0,
+            position: span(0, 500),
+        },
+        InSequence { sequence },
+    ));
+    let clip = world
+        .spawn(TimelineElement {
+            track_num: 1,
+            position: span(0, 80),
+        })
+        .id();
+    CompoundClip::attach(&mut world, clip, sequence).unwrap();
+    world.spawn(Marker::new(40, "End"));
+
+    assert_eq!(
+        chapters(&world),
+        vec![Chapter {
+            span: span(40, 100),
+            title: "End".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn webvtt_titles_stay_on_one_line() {
+    let chapters = [Chapter {
+        span: span(0, 1),
+        title: "a\rb\nc\r\nd --> e".to_string(),
+    }];
+    let vtt = chapters_to_webvtt(&chapters, 1);
+    assert_eq!(
+        vtt,
+        "WEBVTT\n\n1\n00:00:00.000 --> 00:00:01.000\na b c  d -> e\n"
+    );
+}
+
+#[test]
+fn next_and_previous_respect_scope() {
+    let mut world = World::new();
+    let a = world.spawn(Marker::new(10, "a")).id();
+    let b = world.spawn(Marker::new(20, "b").on_track(1)).id();
+    let c = world.spawn(Marker::new(30, "c").on_track(2)).id();
+    let entity = |found: Option<(Entity, &Marker)>| found.map(|(entity, _)| entity);
+
+    assert_eq!(entity(Marker::next(&world, 10, None)), Some(b));
+    assert_eq!(entity(Marker::next(&world, 10, Some(2))), Some(c));
+    assert_eq!(entity(Marker::next(&world, 30, None)), None);
+    assert_eq!(entity(Marker::previous(&world, 30, None)), Some(b));
+    assert_eq!(entity(Marker::previous(&world, 30, Some(2))), Some(a));
+    assert_eq!(entity(Marker::previous(&world, 10, None)), None);
+
+    let playhead = Playhead::spawn(&mut world, PlayheadTarget::Root);
+    Playhead::seek(&mut world, playhead, 20).unwrap();
+    assert_eq!(
+        entity(Marker::next_from_playhead(&world, playhead, Some(1)).unwrap()),
+        None
+    );
+    assert_eq!(
+        entity(Marker::previous_from_playhead(&world, playhead, Some(1)).unwrap()),
+        Some(a)
+    );
+}
+
+#[test]
+fn chapters_from_markers_and_regions() {
+    let mut world = World::new();
+    world.spawn(TimelineElement {
+        track_num: 0,
+        position: span(0, 5000),
+    });
+    world.spawn(Marker::new(0, "Intro"));
+    world.spawn(Marker::new(1000, "Talk"));
+    world.spawn(Region::new(span(3000, 4000), "Q&A"));
+    // Track markers and regions are not chapters.
+    world.spawn(Marker::new(2000, "Cut").on_track(0));
+    world.spawn(Region::new(span(100, 200), "Fix").on_track(0));
+
+    let chapters = chapters(&world);
+    let entries: Vec<_> = chapters
+        .iter()
+        .map(|c| (c.span.start(), c.span.end(), c.title.as_str()))
+        .collect();
+    // A marker's chapter ends where the next chapter starts.
+    assert_eq!(
+        entries,
+        vec![
+            (0, 1000, "Intro"),
+            (1000, 3000, "Talk"),
+            (3000, 4000, "Q&A")
+        ]
+    );
+
+    assert_eq!(
+        chapters_to_text(&chapters, 1000),
+        "00:00:00 Intro\n00:00:01 Talk\n00:00:03 Q&A\n"
+    );
+    assert_eq!(
+        chapters_to_webvtt(&chapters[..1], 1000),
+        "WEBVTT\n\n1\n00:00:00.000 --> 00:00:01.000\nIntro\n"
+    );
+}
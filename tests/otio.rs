@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+
+use bevy_ecs::world::World;
+use lunaris_api::{
+    interchange::otio,
+    timeline::{
+        FrameRate, Marker, Timebase, TimelineSpan, TrackKind, Transition, TransitionKind,
+        elements::{TimeRemap, TimelineElement},
+    },
+};
+
+mod common;
+
+use common::snapshot;
+
+const SIMPLE: &str = include_str!("fixtures/otio/simple.otio");
+
+fn frames(frames: u64) -> u64 {
+    FrameRate::FPS_24.frame_to_ticks(frames, Timebase::default().tps())
+}
+
+#[test]
+fn imports_fixture() {
+    let mut world = World::new();
+    let imported = otio::import(&mut world, SIMPLE).unwrap();
+    assert_eq!(imported.tracks.len(), 2);
+    assert_eq!(imported.elements.len(), 4);
+    assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+
+    let snapshot = snapshot(&mut world);
+    assert_eq!(
+        snapshot.tracks,
+        vec![
+            ("V1".to_string(), TrackKind::Video, 0),
+            ("A1".to_string(), TrackKind::Audio, 1)
+        ]
+    );
+    let spans: Vec<_> = snapshot
+        .elements
+        .iter()
+        .map(|e| (e.track, e.start, e.end, e.offset))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            (0, 0, frames(48), 0),
+            (0, frames(48), frames(120), frames(100)),
+            (0, frames(144), frames(168), frames(12)),
+            (1, 0, frames(120), 0),
+        ]
+    );
+    assert_eq!(
+        snapshot.elements[0].media,
+        Some(PathBuf::from("/media/a.mov"))
+    );
+    assert_eq!(
+        snapshot.elements[1].media,
+        Some(PathBuf::from("/media/b.mov"))
+    );
+    assert_eq!(snapshot.elements[2].media, None);
+    assert_eq!(snapshot.elements[2].remap, Some(TimeRemap::Speed(2.0)));
+    assert_eq!(
+        snapshot.transitions,
+        vec![(frames(42), frames(54), TransitionKind::Dissolve)]
+    );
+
+    let markers: Vec<_> = snapshot
+        .markers
+        .iter()
+        .map(|m| (m.name.as_str(), m.tick, m.track))
+        .collect();
+    assert_eq!(
+        markers,
+        vec![
+            ("Chapter 1", frames(10), None),
+            ("Fix flicker", frames(30), Some(0)),
+            ("Sync", frames(58), Some(0)),
+        ]
+    );
+    assert_eq!(snapshot.regions.len(), 1);
+    assert_eq!(snapshot.regions[0].note, "check the grade");
+    assert_eq!(snapshot.regions[0].span.start(), frames(50));
+    assert_eq!(snapshot.regions[0].span.end(), frames(74));
+}
+
+#[test]
+fn fixture_round_trips() {
+    let mut world = World::new();
+    otio::import(&mut world, SIMPLE).unwrap();
+    let exported = otio::export(&world, "Simple", FrameRate::FPS_24).unwrap();
+    assert!(exported.warnings.is_empty(), "{:?}", exported.warnings);
+
+    let mut reimported = World::new();
+    otio::import(&mut reimported, &exported.text).unwrap();
+    assert_eq!(snapshot(&mut world), snapshot(&mut reimported));
+
+    let again = otio::export(&reimported, "Simple", FrameRate::FPS_24).unwrap();
+    assert_eq!(exported.text, again.text);
+}
+
+#[test]
+fn custom_transitions_and_colors_round_trip() {
+    let mut world = World::new();
+    let mut edited = World::new();
+    otio::import(&mut world, SIMPLE).unwrap();
+    let mut query = world.query::<&mut Transition>();
+    for mut transition in query.iter_mut(&mut world) {
+        transition.kind = TransitionKind::Wipe { angle: 90.0 };
+    }
+    let mut query = world.query::<&mut Marker>();
+    for mut marker in query.iter_mut(&mut world) {
+        marker.color = [0x12, 0x34, 0x56, 0xFF];
+    }
+
+    let exported = otio::export(&world, "Edited", FrameRate::FPS_24).unwrap();
+    otio::import(&mut edited, &exported.text).unwrap();
+    assert_eq!(snapshot(&mut world), snapshot(&mut edited));
+}
+
+#[test]
+fn warns_for_elements_without_track() {
+    let mut world = World::new();
+    otio::import(&mut world, SIMPLE).unwrap();
+    world.spawn(TimelineElement {
+        track_num: 9,
+        position: TimelineSpan::new(0, frames(24)).unwrap(),
+    });
+    let exported = otio::export(&world, "Simple", FrameRate::FPS_24).unwrap();
+    assert_eq!(exported.warnings.len(), 1, "{:?}", exported.warnings);
+    assert!(exported.warnings[0].message.contains("track 9"));
+}
@@ -0,0 +1,87 @@
+use bevy_ecs::{entity::Entity, system::Command, world::World};
+use lunaris_api::{
+    history::UndoHistory,
+    project::{self, ProjectCompression, ProjectDocument},
+    timeline::{
+        TimelineEdit, TimelineSpan,
+        elements::{BindTo, Properties, Property, TimelineElement},
+        validate::{IssueKind, validate},
+    },
+};
+
+#[test]
+fn unsaved_references_become_dangling() {
+    let mut world = World::new();
+    let unsaved = world.spawn_empty().id();
+    let mut properties = Properties::default();
+    properties.insert("target", Property::Entity(unsaved));
+    world.spawn((
+        TimelineElement {
+            track_num: 0,
+            position: TimelineSpan::new(0, 10).unwrap(),
+        },
+        BindTo { id: unsaved },
+        properties,
+    ));
+    let document = ProjectDocument::capture(&world);
+
+    // The first entity spawned here reuses the id of `unsaved`.
+    let mut restored = World::new();
+    let map = document.restore(&mut restored).unwrap();
+    let element = *map.values().next().unwrap();
+    assert_eq!(
+        restored.get::<BindTo>(element).unwrap().id,
+        Entity::PLACEHOLDER
+    );
+    assert_eq!(
+        restored.get::<Properties>(element).unwrap().get("target"),
+        Some(&Property::Entity(Entity::PLACEHOLDER))
+    );
+
+    let issues = validate(&restored);
+    assert!(
+        issues
+            .iter()
+            .any(|i| matches!(i.kind, IssueKind::DanglingBind { .. }))
+    );
+    assert!(
+        issues
+            .iter()
+            .any(|i| matches!(i.kind, IssueKind::DanglingProperty { .. }))
+    );
+}
+
+#[test]
+fn resave_keeps_versions_of_missing_plugins() {
+    let mut world = World::new();
+    let mut document = ProjectDocument::capture(&world);
+    document
+        .plugin_versions
+        .insert("absent.plugin".to_string(), 3);
+    document.restore(&mut world).unwrap();
+
+    let resaved = ProjectDocument::capture(&world);
+    assert_eq!(resaved.plugin_versions.get("absent.plugin"), Some(&3));
+}
+
+#[test]
+fn load_clears_the_undo_history() {
+    let path = std::env::temp_dir().join(format!("lunaris-load-{}.lun", std::process::id()));
+    let mut world = World::new();
+    world.init_resource::<UndoHistory>();
+    let element = world
+        .spawn(TimelineElement {
+            track_num: 0,
+            position: TimelineSpan::new(0, 100).unwrap(),
+        })
+        .id();
+    project::save(&world, &path, ProjectCompression::None).unwrap();
+
+    TimelineEdit::Razor { element, at: 50 }.apply(&mut world);
+    assert!(world.resource::<UndoHistory>().can_undo());
+    project::load(&mut world, &path).unwrap();
+    assert!(!world.resource::<UndoHistory>().can_undo());
+    assert_eq!(UndoHistory::undo(&mut world).unwrap(), None);
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,215 @@
+use std::path::{Path, PathBuf};
+
+use bevy_ecs::entity::Entity;
+use lunaris_api::{
+    prelude::*,
+    timeline::elements::{
+        Curve, CurveValue, Keyframe, Property, PropertyKind, PropertySet, PropertyValue,
+    },
+};
+
+#[derive(PropertySet, Debug, Clone, PartialEq)]
+struct Blur {
+    radius: f64,
+    passes: u32,
+    #[property(rename = "label")]
+    name: String,
+    mask: PathBuf,
+    target: Entity,
+    strength: Curve,
+    seed: Option<u64>,
+    #[property(default)]
+    scale: f32,
+}
+
+fn blur() -> Blur {
+    Blur {
+        radius: 2.5,
+        passes: 3,
+        name: "soft".to_string(),
+        mask: PathBuf::from("/masks/a.png"),
+        target: Entity::from_raw(7),
+        strength: Curve::from_keys([
+            Keyframe::new(0, CurveValue::Float(0.0)),
+            Keyframe::new(100, CurveValue::Float(1.0)),
+        ])
+        .unwrap(),
+        seed: None,
+        scale: 1.5,
+    }
+}
+
+#[test]
+fn round_trips() {
+    let blur = blur();
+    let properties = blur.to_properties();
+    assert_eq!(
+        properties.get("label"),
+        Some(&Property::String("soft".into()))
+    );
+    assert_eq!(properties.get("name"), None);
+    assert_eq!(properties.get("seed"), None);
+    assert_eq!(Blur::from_properties(&properties).unwrap(), blur);
+
+    let seeded = Blur {
+        seed: Some(42),
+        ..blur
+    };
+    let properties = seeded.to_properties();
+    assert_eq!(properties.get("seed"), Some(&Property::Integer(42)));
+    assert_eq!(properties.parse::<Blur>().unwrap(), seeded);
+}
+
+#[test]
+fn missing_keys() {
+    let mut properties = blur().to_properties();
+    properties.properties.remove("scale");
+    assert_eq!(Blur::from_properties(&properties).unwrap().scale, 0.0);
+
+    properties.properties.remove("radius");
+    assert!(matches!(
+        Blur::from_properties(&properties),
+        Err(LunarisError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn wrong_values_fail() {
+    let mut properties = blur().to_properties();
+    properties.insert("radius", Property::String("wide".into()));
+    assert!(matches!(
+        Blur::from_properties(&properties),
+        Err(LunarisError::PropertyTypeMismatch { .. })
+    ));
+
+    let mut properties = blur().to_properties();
+    properties.insert("passes", Property::Integer(u64::from(u32::MAX) + 1));
+    assert!(matches!(
+        Blur::from_properties(&properties),
+        Err(LunarisError::InvalidArgument { .. })
+    ));
+}
+
+#[test]
+fn schema_matches_fields() {
+    let schema = Blur::schema();
+    let keys: Vec<_> = schema.fields().iter().map(|f| f.key.as_str()).collect();
+    assert_eq!(
+        keys,
+        vec![
+            "radius", "passes", "label", "mask", "target", "strength", "seed", "scale"
+        ]
+    );
+    let radius = schema.get("radius").unwrap();
+    assert_eq!(radius.kind, PropertyKind::Float);
+    assert!(radius.required);
+    let seed = schema.get("seed").unwrap();
+    assert_eq!(seed.kind, PropertyKind::Integer);
+    assert!(!seed.required);
+    let scale = schema.get("scale").unwrap();
+    assert!(!scale.required);
+    assert_eq!(scale.default, Some(Property::Float(0.0)));
+    assert!(schema.check(&blur().to_properties()).is_ok());
+}
+
+#[test]
+fn property_values_round_trip() {
+    fn check<T: PropertyValue + PartialEq + std::fmt::Debug>(value: T, kind: PropertyKind) {
+        let property = value.to_property();
+        assert_eq!(property.kind(), kind);
+        assert_eq!(T::KIND, kind);
+        assert_eq!(T::from_property(&property).unwrap(), value);
+    }
+    let blur = blur();
+    check(blur.radius, PropertyKind::Float);
+    check(blur.scale, PropertyKind::Float);
+    check(u64::MAX, PropertyKind::Integer);
+    check(blur.passes, PropertyKind::Integer);
+    check(blur.name, PropertyKind::String);
+    check(blur.mask, PropertyKind::Path);
+    check(blur.target, PropertyKind::Entity);
+    check(blur.strength, PropertyKind::Curve);
+}
+
+#[test]
+fn typed_getters() {
+    let mut properties = blur().to_properties();
+    properties.insert("at", Property::Ticks(480));
+    properties.insert("extra", Property::custom(5_i32));
+
+    assert_eq!(properties.get_f64("radius").unwrap(), 2.5);
+    assert_eq!(properties.get_u64("passes").unwrap(), 3);
+    assert_eq!(properties.get_ticks("at").unwrap(), 480);
+    assert_eq!(properties.get_string("label").unwrap(), "soft");
+    assert_eq!(
+        properties.get_path("mask").unwrap(),
+        Path::new("/masks/a.png")
+    );
+    assert_eq!(
+        properties.get_entity("target").unwrap(),
+        Entity::from_raw(7)
+    );
+    assert_eq!(properties.get_curve("strength").unwrap().keys().len(), 2);
+    assert_eq!(*properties.get_custom::<i32>("extra").unwrap(), 5);
+    assert_eq!(properties.get_as::<u32>("passes").unwrap(), 3);
+
+    // Ticks and integers are distinct kinds.
+    assert!(properties.get_ticks("passes").is_err());
+    assert!(properties.get_u64("at").is_err());
+    assert!(properties.get_custom::<u8>("extra").is_err());
+    assert!(matches!(
+        properties.get_string("missing"),
+        Err(LunarisError::NotFound { .. })
+    ));
+}
+
+mod renamed {
+    use lunaris_api as api;
+
+    use api::timeline::elements::{Property, PropertySet};
+
+    #[derive(PropertySet, Debug, PartialEq)]
+    #[property_set(crate = "api")]
+    struct Gain {
+        db: f64,
+    }
+
+    #[test]
+    fn crate_path_override() {
+        let gain = Gain { db: -6.0 };
+        let properties = gain.to_properties();
+        assert_eq!(properties.get("db"), Some(&Property::Float(-6.0)));
+        assert_eq!(Gain::from_properties(&properties).unwrap(), gain);
+    }
+}
+
+mod shadowed {
+    use lunaris_api::timeline::elements::PropertySet;
+
+    /// Shadows the prelude names the derive must not rely on.
+    #[allow(dead_code)]
+    enum Shadow {
+        Some,
+        None,
+        Ok,
+        Err,
+    }
+    #[allow(unused_imports)]
+    use Shadow::*;
+
+    #[derive(PropertySet, Debug, PartialEq)]
+    struct Gain {
+        db: f64,
+        limit: Option<f64>,
+    }
+
+    #[test]
+    fn prelude_names_can_be_shadowed() {
+        let gain = Gain {
+            db: -6.0,
+            limit: Option::Some(0.0),
+        };
+        let properties = gain.to_properties();
+        assert_eq!(Gain::from_properties(&properties).unwrap(), gain);
+    }
+}
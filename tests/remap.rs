@@ -0,0 +1,103 @@
+use bevy_ecs::{entity::Entity, world::World};
+use lunaris_api::{
+    plugin::RenderJob,
+    timeline::{
+        Playhead, PlayheadTarget, TimelineSpan, Track, TrackKind,
+        elements::{Curve, CurveValue, Keyframe, SourceOffset, TimeRemap, TimelineElement, remap},
+    },
+};
+
+fn ramp(keys: &[(u64, f64)]) -> TimeRemap {
+    TimeRemap::Ramp(
+        Curve::from_keys(
+            keys.iter()
+                .map(|&(tick, speed)| Keyframe::new(tick, CurveValue::Float(speed))),
+        )
+        .unwrap(),
+    )
+}
+
+fn element(world: &mut World, start: u64, end: u64, offset: u64) -> Entity {
+    world
+        .spawn((
+            TimelineElement {
+                track_num: 0,
+                position: TimelineSpan::new(start, end).unwrap(),
+            },
+            SourceOffset { ticks: offset },
+        ))
+        .id()
+}
+
+#[test]
+fn speed_scales_from_the_offset() {
+    let remap = TimeRemap::Speed(2.0);
+    assert_eq!(remap.source_tick(100, 10, 100), Some(10));
+    assert_eq!(remap.source_tick(100, 10, 130), Some(70));
+    let slow = TimeRemap::Speed(0.5);
+    assert_eq!(slow.source_tick(0, 0, 3), Some(2));
+}
+
+#[test]
+fn reverse_plays_back_from_the_offset() {
+    let remap = TimeRemap::REVERSE;
+    assert_eq!(remap.source_tick(100, 50, 100), Some(50));
+    assert_eq!(remap.source_tick(100, 50, 130), Some(20));
+    assert_eq!(remap.source_tick(100, 50, 150), Some(0));
+    assert_eq!(remap.source_tick(100, 50, 151), None);
+}
+
+#[test]
+fn freeze_holds_the_offset() {
+    for tick in [0, 100, u64::MAX] {
+        assert_eq!(TimeRemap::Freeze.source_tick(100, 42, tick), Some(42));
+    }
+}
+
+#[test]
+fn ramp_integrates_segments_longer_than_u32() {
+    for length in [1 << 32, (1 << 32) + 1, (3 << 32) + 7] {
+        let remap = ramp(&[(0, 2.0), (length, 2.0)]);
+        assert_eq!(remap.source_tick(0, 10, length), Some(10 + 2 * length));
+    }
+}
+
+#[test]
+fn ramp_integrates_linear_change() {
+    let remap = ramp(&[(0, 0.0), (1000, 2.0)]);
+    assert_eq!(remap.source_tick(0, 0, 1000), Some(1000));
+}
+
+#[test]
+fn element_source_ticks() {
+    let mut world = World::new();
+    let plain = element(&mut world, 100, 200, 10);
+    assert_eq!(remap::source_tick(&world, plain, 150), Some(60));
+
+    let reversed = element(&mut world, 100, 200, 20);
+    world.entity_mut(reversed).insert(TimeRemap::REVERSE);
+    // Before the start of the source clamps to it.
+    assert_eq!(remap::source_tick(&world, reversed, 110), Some(10));
+    assert_eq!(remap::source_tick(&world, reversed, 150), Some(0));
+
+    let late = element(&mut world, 0, 100, u64::MAX - 10);
+    assert_eq!(remap::source_tick(&world, late, 50), Some(u64::MAX));
+    let unplaced = world.spawn_empty().id();
+    assert_eq!(remap::source_tick(&world, unplaced, 0), None);
+}
+
+#[test]
+fn render_jobs_carry_the_source_tick() {
+    let mut world = World::new();
+    Track::push(&mut world, "V1", TrackKind::Video);
+    let clip = element(&mut world, 100, 200, 1000);
+    world.entity_mut(clip).insert(TimeRemap::Speed(2.0));
+    let playhead = Playhead::spawn(&mut world, PlayheadTarget::Root);
+    Playhead::seek(&mut world, playhead, 130).unwrap();
+
+    let jobs = RenderJob::for_playhead(&world, playhead, TrackKind::Video).unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].entity, clip);
+    assert_eq!(jobs[0].frame, 130);
+    assert_eq!(jobs[0].source_tick, 1060);
+}
@@ -0,0 +1,179 @@
+use lunaris_api::{
+    prelude::*,
+    timeline::elements::{
+        Curve, CurveValue, Keyframe, Properties, Property, PropertyField, PropertyKind,
+        PropertySchema, SchemaViolation, schema::Problem,
+    },
+};
+
+fn schema() -> PropertySchema {
+    PropertySchema::new()
+        .field(PropertyField::new("opacity", PropertyKind::Float).range(0.0, 1.0))
+        .field(
+            PropertyField::new("blend", PropertyKind::String)
+                .choices(["normal", "add"])
+                .default_value(Property::String("normal".to_string())),
+        )
+        .field(PropertyField::new("source", PropertyKind::Path).required())
+}
+
+fn properties(values: impl IntoIterator<Item = (&'static str, Property)>) -> Properties {
+    let mut properties = Properties::default();
+    for (key, value) in values {
+        properties.insert(key, value);
+    }
+    properties
+}
+
+fn problems(violations: Vec<SchemaViolation>) -> Vec<(String, Problem)> {
+    violations.into_iter().map(|v| (v.key, v.problem)).collect()
+}
+
+#[test]
+fn accepts_valid_properties() {
+    let valid = properties([
+        ("opacity", Property::Float(1.0)),
+        ("source", Property::Path("clip.mov".into())),
+    ]);
+    assert!(schema().validate(&valid).is_empty());
+    assert!(schema().check(&valid).is_ok());
+}
+
+#[test]
+fn reports_every_problem_in_field_order() {
+    let invalid = properties([
+        ("zeta", Property::Integer(1)),
+        ("opacity", Property::Float(1.5)),
+        ("blend", Property::String("multiply".to_string())),
+        ("alpha", Property::Integer(1)),
+    ]);
+    assert_eq!(
+        problems(schema().validate(&invalid)),
+        vec![
+            (
+                "opacity".to_string(),
+                Problem::OutOfRange {
+                    value: 1.5,
+                    min: 0.0,
+                    max: 1.0,
+                }
+            ),
+            (
+                "blend".to_string(),
+                Problem::InvalidChoice {
+                    value: "multiply".to_string(),
+                    choices: vec!["normal".to_string(), "add".to_string()],
+                }
+            ),
+            ("source".to_string(), Problem::Missing),
+            ("alpha".to_string(), Problem::Unknown),
+            ("zeta".to_string(), Problem::Unknown),
+        ]
+    );
+}
+
+#[test]
+fn type_mismatches_skip_the_value_checks() {
+    let invalid = properties([
+        ("opacity", Property::Integer(5)),
+        ("source", Property::Path("clip.mov".into())),
+    ]);
+    assert_eq!(
+        problems(schema().validate(&invalid)),
+        vec![(
+            "opacity".to_string(),
+            Problem::TypeMismatch {
+                expected: PropertyKind::Float,
+                found: PropertyKind::Integer,
+            }
+        )]
+    );
+    assert!(matches!(
+        schema().check(&invalid),
+        Err(LunarisError::PropertyTypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn ranges_apply_to_every_curve_key() {
+    let curve = Curve::from_keys([
+        Keyframe::new(0, CurveValue::Float(0.5)),
+        Keyframe::new(10, CurveValue::Float(-1.0)),
+        Keyframe::new(20, CurveValue::Float(2.0)),
+    ])
+    .unwrap();
+    let schema = PropertySchema::new()
+        .field(PropertyField::new("opacity", PropertyKind::Curve).range(0.0, 1.0));
+    let values: Vec<_> = schema
+        .validate(&properties([("opacity", Property::Curve(curve))]))
+        .into_iter()
+        .map(|v| match v.problem {
+            Problem::OutOfRange { value, .. } => value,
+            problem => panic!("unexpected {problem:?}"),
+        })
+        .collect();
+    assert_eq!(values, vec![-1.0, 2.0]);
+}
+
+#[test]
+fn defaults_satisfy_required_fields() {
+    let schema = PropertySchema::new().field(
+        PropertyField::new("gain", PropertyKind::Float)
+            .required()
+            .default_value(Property::Float(0.0)),
+    );
+    let mut empty = Properties::default();
+    assert!(schema.validate(&empty).is_empty());
+    schema.prepare(&mut empty).unwrap();
+    assert_eq!(empty.get("gain"), Some(&Property::Float(0.0)));
+
+    // Existing values are kept.
+    let mut set = properties([("gain", Property::Float(3.0))]);
+    schema.fill_defaults(&mut set);
+    assert_eq!(set.get("gain"), Some(&Property::Float(3.0)));
+}
+
+#[test]
+fn allow_unknown_accepts_extra_keys() {
+    let extra = properties([
+        ("source", Property::Path("clip.mov".into())),
+        ("note", Property::String("free-form".to_string())),
+    ]);
+    assert!(schema().allow_unknown().validate(&extra).is_empty());
+    assert!(PropertySchema::any().check(&extra).is_ok());
+    assert!(
+        PropertySchema::any()
+            .check_value("note", &Property::Integer(1))
+            .is_ok()
+    );
+}
+
+#[test]
+fn insert_checked_refuses_rejected_values() {
+    let schema = schema();
+    let mut properties = Properties::default();
+    assert!(
+        properties
+            .insert_checked(&schema, "opacity", Property::Float(2.0))
+            .is_err()
+    );
+    assert!(
+        properties
+            .insert_checked(&schema, "unknown", Property::Float(0.0))
+            .is_err()
+    );
+    assert_eq!(properties.get("opacity"), None);
+    properties
+        .insert_checked(&schema, "opacity", Property::Float(0.25))
+        .unwrap();
+    assert_eq!(properties.get("opacity"), Some(&Property::Float(0.25)));
+}
+
+#[test]
+fn later_fields_replace_earlier_ones() {
+    let schema = PropertySchema::new()
+        .field(PropertyField::new("x", PropertyKind::Float))
+        .field(PropertyField::new("x", PropertyKind::Integer));
+    assert_eq!(schema.fields().len(), 1);
+    assert_eq!(schema.get("x").unwrap().kind, PropertyKind::Integer);
+}
@@ -0,0 +1,102 @@
+use bevy_ecs::{entity::Entity, system::RunSystemOnce, world::World};
+use lunaris_api::{
+    plugin::RenderJob,
+    timeline::{
+        CompoundClip, InSequence, Playhead, PlayheadTarget, Sequence, TimelineIndex, TimelineSpan,
+        Track, TrackKind,
+        elements::{SourceOffset, TimelineElement},
+        index::sync_timeline_index,
+        sequence::resolve,
+    },
+};
+
+fn element(world: &mut World, sequence: Option<Entity>, start: u64, end: u64) -> Entity {
+    let mut entity = world.spawn((
+        TimelineElement {
+            track_num: 0,
+            position: TimelineSpan::new(start, end).unwrap(),
+        },
+        SourceOffset { ticks: 0 },
+    ));
+    if let Some(sequence) = sequence {
+        entity.insert(InSequence { sequence });
+    }
+    entity.id()
+}
+
+/// Root timeline with a compound clip at `100..200` playing a sequence
+/// that holds `inner` at `0..50` and `late` at `50..100`.
+fn nested(world: &mut World) -> (Entity, Entity, Entity) {
+    let sequence = Sequence::spawn(world, "Nested");
+    Track::push(world, "V1", TrackKind::Video);
+    Track::push_in(world, Some(sequence), "V1", TrackKind::Video);
+    let inner = element(world, Some(sequence), 0, 50);
+    let late = element(world, Some(sequence), 50, 100);
+    let clip = element(world, None, 100, 200);
+    CompoundClip::attach(world, clip, sequence).unwrap();
+    (clip, inner, late)
+}
+
+#[test]
+fn resolves_through_compound_clips() {
+    let mut world = World::new();
+    let (clip, inner, late) = nested(&mut world);
+
+    let resolved = resolve(&world, 120, TrackKind::Video).unwrap();
+    assert_eq!(resolved.len(), 1);
+    assert_eq!(resolved[0].entity, inner);
+    assert_eq!(resolved[0].local_tick, 20);
+    assert_eq!(resolved[0].path, vec![clip]);
+    assert_eq!(
+        resolve(&world, 160, TrackKind::Video).unwrap()[0].entity,
+        late
+    );
+    assert!(resolve(&world, 50, TrackKind::Video).unwrap().is_empty());
+}
+
+#[test]
+fn resolve_reads_the_index() {
+    let mut world = World::new();
+    let (_, inner, late) = nested(&mut world);
+    world.init_resource::<TimelineIndex>();
+    world.run_system_once(sync_timeline_index).unwrap();
+    assert_eq!(
+        resolve(&world, 120, TrackKind::Video).unwrap()[0].entity,
+        inner
+    );
+
+    // Until the next sync the index still holds the old positions.
+    let mut swap = |entity, start, end| {
+        world.get_mut::<TimelineElement>(entity).unwrap().position =
+            TimelineSpan::new(start, end).unwrap();
+    };
+    swap(inner, 50, 100);
+    swap(late, 0, 50);
+    assert_eq!(
+        resolve(&world, 120, TrackKind::Video).unwrap()[0].entity,
+        inner
+    );
+    world.run_system_once(sync_timeline_index).unwrap();
+    assert_eq!(
+        resolve(&world, 120, TrackKind::Video).unwrap()[0].entity,
+        late
+    );
+}
+
+#[test]
+fn render_jobs_read_the_index() {
+    let mut world = World::new();
+    let (_, inner, _) = nested(&mut world);
+    world.init_resource::<TimelineIndex>();
+    world.run_system_once(sync_timeline_index).unwrap();
+    let playhead = Playhead::spawn(&mut world, PlayheadTarget::Root);
+    Playhead::seek(&mut world, playhead, 120).unwrap();
+
+    // Elements the index has not seen yet are not rendered.
+    element(&mut world, None, 0, 300);
+    let jobs = RenderJob::for_playhead(&world, playhead, TrackKind::Video).unwrap();
+    let entities: Vec<_> = jobs.iter().map(|job| job.entity).collect();
+    assert_eq!(entities, vec![inner]);
+    assert_eq!(jobs[0].frame, 20);
+    assert_eq!(jobs[0].playhead, Some(playhead));
+}
@@ -0,0 +1,150 @@
+use std::any::TypeId;
+
+use bevy_ecs::world::{EntityWorldMut, World};
+use lunaris_api::{
+    prelude::*,
+    project::{ProjectCompression, ProjectDocument},
+    timeline::{
+        TimelineSpan,
+        elements::{
+            Curve, CurveValue, CustomPropertyRegistration, Keyframe, OpaqueCustom, Properties,
+            Property, TimeRemap, TimelineElement,
+            serialize::{decode_json, encode_json, is_custom_registered},
+        },
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Gradient {
+    stops: Vec<[u8; 4]>,
+}
+
+lunaris_api::submit_raw!(CustomPropertyRegistration {
+    name: "tests.gradient",
+    type_id: TypeId::of::<Gradient>,
+    encode: encode_json::<Gradient>,
+    decode: decode_json::<Gradient>,
+});
+
+/// Registered nowhere.
+struct Unknown;
+
+fn element(world: &mut World) -> EntityWorldMut<'_> {
+    world.spawn(TimelineElement {
+        track_num: 0,
+        position: TimelineSpan::new(0, 100).unwrap(),
+    })
+}
+
+fn with_property(value: Property) -> World {
+    let mut world = World::new();
+    let mut properties = Properties::default();
+    properties.insert("value", value);
+    element(&mut world).insert(properties);
+    world
+}
+
+fn round_trip(world: &World) -> World {
+    let bytes = ProjectDocument::capture(world)
+        .to_bytes(ProjectCompression::None)
+        .unwrap();
+    let mut loaded = World::new();
+    ProjectDocument::from_bytes(&bytes)
+        .unwrap()
+        .restore(&mut loaded)
+        .unwrap();
+    loaded
+}
+
+fn loaded_value(world: &World) -> Property {
+    let mut loaded = round_trip(world);
+    let mut query = loaded.query::<&Properties>();
+    query.single(&loaded).unwrap().get("value").unwrap().clone()
+}
+
+fn saves(world: &World) -> bool {
+    ProjectDocument::capture(world)
+        .to_bytes(ProjectCompression::None)
+        .is_ok()
+}
+
+#[test]
+fn floats_round_trip() {
+    for value in [0.0, -1.5, f64::MAX, f64::MIN_POSITIVE] {
+        let loaded = loaded_value(&with_property(Property::Float(value)));
+        assert_eq!(loaded, Property::Float(value));
+    }
+}
+
+#[test]
+fn non_finite_floats_fail_to_save() {
+    for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(!saves(&with_property(Property::Float(value))));
+
+        let mut world = World::new();
+        element(&mut world).insert(TimeRemap::Speed(value));
+        assert!(!saves(&world));
+
+        let curve = Curve::from_keys([Keyframe::new(0, CurveValue::Vec2([0.0, value]))]).unwrap();
+        assert!(!saves(&with_property(Property::Curve(curve))));
+    }
+
+    let mut world = World::new();
+    element(&mut world).insert(TimeRemap::Speed(-2.0));
+    let mut loaded = round_trip(&world);
+    let mut query = loaded.query::<&TimeRemap>();
+    assert_eq!(query.single(&loaded).unwrap(), &TimeRemap::Speed(-2.0));
+}
+
+#[test]
+fn registered_custom_values_round_trip() {
+    assert!(is_custom_registered("tests.gradient"));
+    let gradient = Gradient {
+        stops: vec![[0, 0, 0, 255], [255, 255, 255, 255]],
+    };
+    let loaded = loaded_value(&with_property(Property::custom(gradient.clone())));
+    assert_eq!(loaded.as_custom::<Gradient>(), Some(&gradient));
+}
+
+#[test]
+fn unregistered_custom_values_fail_to_save() {
+    assert!(!saves(&with_property(Property::custom(Unknown))));
+}
+
+#[test]
+fn values_of_missing_plugins_survive_as_opaque_blobs() {
+    let json = r#"{"Custom":{"type_name":"absent.plugin","bytes":[1,2,3]}}"#;
+    let property: Property = serde_json::from_str(json).unwrap();
+    assert!(!is_custom_registered("absent.plugin"));
+    assert_eq!(
+        property.as_custom::<OpaqueCustom>(),
+        Some(&OpaqueCustom {
+            type_name: "absent.plugin".to_string(),
+            bytes: vec![1, 2, 3],
+        })
+    );
+    assert_eq!(serde_json::to_string(&property).unwrap(), json);
+
+    // And through a whole project.
+    let loaded = loaded_value(&with_property(property));
+    assert_eq!(
+        loaded
+            .as_custom::<OpaqueCustom>()
+            .map(|o| o.bytes.as_slice()),
+        Some(&[1, 2, 3][..])
+    );
+}
+
+#[test]
+fn json_encoder_checks_the_type() {
+    let wrong: &(dyn std::any::Any + Send + Sync) = &5_u32;
+    assert!(matches!(
+        encode_json::<Gradient>(wrong),
+        Err(LunarisError::PropertyTypeMismatch { .. })
+    ));
+    assert!(matches!(
+        decode_json::<Gradient>(b"not json"),
+        Err(LunarisError::FailedSaveLoad { .. })
+    ));
+}
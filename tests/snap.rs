@@ -0,0 +1,192 @@
+use bevy_ecs::{entity::Entity, system::RunSystemOnce, world::World};
+use lunaris_api::timeline::{
+    FrameRate, Marker, Playhead, PlayheadTarget, Region, Sequence, Timebase, TimelineIndex,
+    TimelineSpan,
+    elements::TimelineElement,
+    index::sync_timeline_index,
+    sequence::InSequence,
+    snap::{Snap, SnapOptions, SnapTarget, SnapTolerance, snap, snap_span},
+};
+
+fn span(start: u64, end: u64) -> TimelineSpan {
+    TimelineSpan::new(start, end).unwrap()
+}
+
+fn element(world: &mut World, start: u64, end: u64) -> Entity {
+    world
+        .spawn(TimelineElement {
+            track_num: 0,
+            position: span(start, end),
+        })
+        .id()
+}
+
+fn target(
+    world: &World,
+    candidate: u64,
+    tolerance: u64,
+    options: &SnapOptions,
+) -> Option<SnapTarget> {
+    snap(world, candidate, SnapTolerance::Ticks(tolerance), options).map(|s| s.target)
+}
+
+#[test]
+fn tolerance_is_inclusive() {
+    let mut world = World::new();
+    let element = element(&mut world, 100, 200);
+    let options = SnapOptions::default();
+    assert_eq!(
+        snap(&world, 104, SnapTolerance::Ticks(4), &options),
+        Some(Snap {
+            tick: 100,
+            target: SnapTarget::ElementStart(element),
+            distance: 4,
+        })
+    );
+    assert_eq!(target(&world, 104, 3, &options), None);
+    assert_eq!(
+        target(&world, 205, 5, &options),
+        Some(SnapTarget::ElementEnd(element))
+    );
+
+    let pixels = SnapTolerance::Pixels {
+        pixels: 2.0,
+        ticks_per_pixel: 2.5,
+    };
+    assert_eq!(pixels.ticks(), 5);
+    assert!(snap(&world, 95, pixels, &options).is_some());
+    assert!(snap(&world, 94, pixels, &options).is_none());
+}
+
+#[test]
+fn closest_target_wins_then_priority() {
+    let mut world = World::new();
+    let element = element(&mut world, 100, 200);
+    let marker = world.spawn(Marker::new(100, "cut")).id();
+    let playhead = Playhead::spawn(&mut world, PlayheadTarget::Root);
+    Playhead::seek(&mut world, playhead, 100).unwrap();
+    let options = SnapOptions::default();
+    assert_eq!(
+        target(&world, 102, 5, &options),
+        Some(SnapTarget::Playhead(playhead))
+    );
+
+    let no_playheads = SnapOptions {
+        playheads: false,
+        ..SnapOptions::default()
+    };
+    assert_eq!(
+        target(&world, 102, 5, &no_playheads),
+        Some(SnapTarget::Marker(marker))
+    );
+    let elements_only = SnapOptions {
+        markers: false,
+        ..no_playheads.clone()
+    };
+    assert_eq!(
+        target(&world, 102, 5, &elements_only),
+        Some(SnapTarget::ElementStart(element))
+    );
+
+    // A closer element edge beats a marker further away.
+    let region = world.spawn(Region::new(span(90, 96), "region")).id();
+    assert_eq!(
+        target(&world, 97, 5, &no_playheads),
+        Some(SnapTarget::RegionEnd(region))
+    );
+    assert_eq!(
+        target(&world, 99, 5, &no_playheads),
+        Some(SnapTarget::Marker(marker))
+    );
+}
+
+#[test]
+fn ignored_entities_and_other_sequences_are_skipped() {
+    let mut world = World::new();
+    let dragged = element(&mut world, 100, 200);
+    let sequence = Sequence::spawn(&mut world, "Nested");
+    let nested = element(&mut world, 300, 400);
+    world.entity_mut(nested).insert(InSequence { sequence });
+    let marker = world.spawn(Marker::new(305, "root only")).id();
+
+    let options = SnapOptions::default().ignore([dragged]);
+    assert_eq!(target(&world, 100, 5, &options), None);
+    assert_eq!(
+        target(&world, 304, 5, &SnapOptions::default()),
+        Some(SnapTarget::Marker(marker))
+    );
+    let inside = SnapOptions::default().in_sequence(sequence);
+    assert_eq!(
+        target(&world, 304, 5, &inside),
+        Some(SnapTarget::ElementStart(nested))
+    );
+}
+
+#[test]
+fn frames_snap_last() {
+    let mut world = World::new();
+    let tps = Timebase::of(&world).tps();
+    let rate = FrameRate::FPS_25;
+    let frame_3 = rate.frame_to_ticks(3, tps);
+    let options = SnapOptions::default().frames(rate);
+    let candidate = frame_3 + 1;
+    assert_eq!(
+        snap(&world, candidate, SnapTolerance::Ticks(1), &options),
+        Some(Snap {
+            tick: frame_3,
+            target: SnapTarget::Frame(3),
+            distance: 1,
+        })
+    );
+    let marker = world.spawn(Marker::new(frame_3, "on a frame")).id();
+    assert_eq!(
+        target(&world, candidate, 1, &options),
+        Some(SnapTarget::Marker(marker))
+    );
+}
+
+#[test]
+fn index_and_scan_agree() {
+    let mut world = World::new();
+    let left = element(&mut world, 0, 100);
+    let right = element(&mut world, 110, 200);
+    let options = SnapOptions::default();
+    let scanned = [
+        target(&world, 104, 4, &options),
+        target(&world, 106, 4, &options),
+    ];
+    assert_eq!(
+        scanned,
+        [
+            Some(SnapTarget::ElementEnd(left)),
+            Some(SnapTarget::ElementStart(right))
+        ]
+    );
+    world.init_resource::<TimelineIndex>();
+    world.run_system_once(sync_timeline_index).unwrap();
+    assert_eq!(
+        [
+            target(&world, 104, 4, &options),
+            target(&world, 106, 4, &options),
+        ],
+        scanned
+    );
+}
+
+#[test]
+fn spans_snap_by_their_closer_edge() {
+    let mut world = World::new();
+    let next = element(&mut world, 200, 300);
+    let marker = world.spawn(Marker::new(90, "in")).id();
+    let options = SnapOptions::default();
+    let tolerance = SnapTolerance::Ticks(10);
+
+    let (shift, snap) = snap_span(&world, span(97, 198), tolerance, &options).unwrap();
+    assert_eq!((shift, snap.target), (2, SnapTarget::ElementStart(next)));
+    let (shift, snap) = snap_span(&world, span(92, 190), tolerance, &options).unwrap();
+    assert_eq!((shift, snap.target), (-2, SnapTarget::Marker(marker)));
+    // Equally close edges prefer the start.
+    let (shift, snap) = snap_span(&world, span(95, 195), tolerance, &options).unwrap();
+    assert_eq!((shift, snap.target), (-5, SnapTarget::Marker(marker)));
+    assert_eq!(snap_span(&world, span(50, 60), tolerance, &options), None);
+}
@@ -0,0 +1,84 @@
+use lunaris_api::timeline::{FrameRate, TimelineSpan};
+
+fn span(start: u64, end: u64) -> TimelineSpan {
+    TimelineSpan::new(start, end).unwrap()
+}
+
+#[test]
+fn construction_is_checked() {
+    assert!(TimelineSpan::new(10, 5).is_err());
+    assert_eq!(TimelineSpan::with_duration(10, 5).unwrap(), span(10, 15));
+    assert!(TimelineSpan::with_duration(u64::MAX, 1).is_err());
+    assert!(TimelineSpan::try_from((3, 2)).is_err());
+    assert_eq!(<(u64, u64)>::from(span(1, 2)), (1, 2));
+    assert!(serde_json::from_str::<TimelineSpan>("[5, 1]").is_err());
+    assert_eq!(span(1, 2).to_string(), "[1, 2)");
+}
+
+#[test]
+fn half_open_membership() {
+    let s = span(10, 20);
+    assert!(s.contains(10));
+    assert!(!s.contains(20));
+    assert!(!TimelineSpan::empty_at(10).contains(10));
+    assert!(s.contains_span(&span(10, 20)));
+    assert!(s.contains_span(&TimelineSpan::empty_at(20)));
+    assert!(!s.contains_span(&span(15, 25)));
+}
+
+#[test]
+fn intersection_and_union() {
+    let s = span(10, 20);
+    assert_eq!(s.intersection(&span(15, 30)), Some(span(15, 20)));
+    // Back to back spans touch but share no tick.
+    assert!(!s.intersects(&span(20, 30)));
+    assert!(s.touches(&span(20, 30)));
+    assert_eq!(s.intersection(&span(20, 30)), None);
+    assert_eq!(s.union(&span(20, 30)), Some(span(10, 30)));
+    assert_eq!(s.union(&span(21, 30)), None);
+    assert_eq!(s.hull(&span(25, 30)), span(10, 30));
+    // Empty spans intersect nothing, not even inside another span.
+    assert!(!s.intersects(&TimelineSpan::empty_at(15)));
+}
+
+#[test]
+fn splitting_keeps_both_halves_non_empty() {
+    assert_eq!(
+        span(10, 20).split_at(15).unwrap(),
+        (span(10, 15), span(15, 20))
+    );
+    assert!(span(10, 20).split_at(10).is_err());
+    assert!(span(10, 20).split_at(20).is_err());
+}
+
+#[test]
+fn shifting() {
+    assert_eq!(span(10, 20).shift(-10).unwrap(), span(0, 10));
+    assert!(span(10, 20).shift(-11).is_err());
+    assert!(span(10, u64::MAX - 1).shift(2).is_err());
+    assert_eq!(span(10, 20).saturating_shift(-15), span(0, 5));
+    assert_eq!(
+        span(u64::MAX - 10, u64::MAX).saturating_add(20),
+        span(u64::MAX, u64::MAX)
+    );
+    assert_eq!(span(10, 20).saturating_sub(15), span(0, 5));
+}
+
+#[test]
+fn trimming() {
+    assert_eq!(span(10, 20).trim_start(15).unwrap(), span(15, 20));
+    assert_eq!(span(10, 20).trim_end(12).unwrap(), span(10, 12));
+    assert!(span(10, 20).trim_start(21).is_err());
+    assert!(span(10, 20).trim_end(9).is_err());
+}
+
+#[test]
+fn frames_cover_partial_frames() {
+    // 1000 ticks per second at 25 fps: 40 ticks per frame.
+    assert_eq!(span(0, 80).frames(FrameRate::FPS_25, 1000), 0..2);
+    assert_eq!(span(30, 81).frames(FrameRate::FPS_25, 1000), 0..3);
+    assert_eq!(
+        TimelineSpan::empty_at(120).frames(FrameRate::FPS_25, 1000),
+        3..3
+    );
+}
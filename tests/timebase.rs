@@ -0,0 +1,110 @@
+use bevy_ecs::{system::Command, world::World};
+use lunaris_api::{
+    history::UndoHistory,
+    timeline::{
+        Marker, Playhead, PlayheadTarget, Timebase, TimelineEdit, TimelineSpan,
+        elements::TimelineElement, timebase::RescaleReport,
+    },
+};
+
+fn world(tps: u64) -> World {
+    let mut world = World::new();
+    world.insert_resource(Timebase::new(tps).unwrap());
+    world
+}
+
+fn rate(tps: u64) -> Timebase {
+    Timebase::new(tps).unwrap()
+}
+
+#[test]
+fn reports_rounding_loss() {
+    let mut world = world(1000);
+    let element = world
+        .spawn(TimelineElement {
+            track_num: 0,
+            position: TimelineSpan::new(1000, 2000).unwrap(),
+        })
+        .id();
+    let marker = world.spawn(Marker::new(10, "early")).id();
+    let playhead = Playhead::spawn(&mut world, PlayheadTarget::Root);
+    Playhead::seek(&mut world, playhead, 50).unwrap();
+
+    let report = Timebase::change(&mut world, rate(30));
+    assert_eq!(
+        report,
+        RescaleReport {
+            from: rate(1000),
+            to: rate(30),
+            rescaled: 4,
+            inexact: 2,
+            max_error: 0.5,
+            total_error: 0.8,
+        }
+    );
+    assert!(!report.is_lossless());
+    assert_eq!(Timebase::of(&world), rate(30));
+    assert_eq!(
+        world.get::<TimelineElement>(element).unwrap().position,
+        TimelineSpan::new(30, 60).unwrap()
+    );
+    // 0.3 rounds down, 1.5 rounds half-way up.
+    assert_eq!(world.get::<Marker>(marker).unwrap().tick, 0);
+    assert_eq!(Playhead::tick(&world, playhead).unwrap(), 2);
+}
+
+#[test]
+fn exact_changes_are_lossless() {
+    let mut world = world(24);
+    world.spawn(Marker::new(7, "a"));
+    let report = Timebase::change(&mut world, rate(48));
+    assert!(report.is_lossless());
+    assert_eq!(report.rescaled, 1);
+    assert_eq!(report.total_error, 0.0);
+
+    let report = Timebase::change(&mut world, rate(24));
+    assert!(report.is_lossless());
+    let mut query = world.query::<&Marker>();
+    assert_eq!(query.single(&world).unwrap().tick, 7);
+}
+
+#[test]
+fn same_rate_changes_nothing() {
+    let mut world = world(1000);
+    world.spawn(Marker::new(7, "a"));
+    let report = Timebase::change(&mut world, rate(1000));
+    assert_eq!(report.rescaled, 0);
+    assert!(report.is_lossless());
+}
+
+#[test]
+fn reports_are_deterministic() {
+    let build = || {
+        let mut world = world(48000);
+        for tick in [1, 7, 23, 24_001, 99_999] {
+            world.spawn(Marker::new(tick, "m"));
+        }
+        world
+    };
+    let first = Timebase::change(&mut build(), rate(44100));
+    let second = Timebase::change(&mut build(), rate(44100));
+    assert_eq!(first, second);
+    assert!(first.max_error <= 0.5);
+}
+
+#[test]
+fn change_clears_the_undo_history() {
+    let mut world = world(1000);
+    world.init_resource::<UndoHistory>();
+    let element = world
+        .spawn(TimelineElement {
+            track_num: 0,
+            position: TimelineSpan::new(0, 1000).unwrap(),
+        })
+        .id();
+    TimelineEdit::Razor { element, at: 500 }.apply(&mut world);
+    assert!(world.resource::<UndoHistory>().can_undo());
+
+    Timebase::change(&mut world, rate(30));
+    assert!(!world.resource::<UndoHistory>().can_undo());
+}
@@ -0,0 +1,141 @@
+use lunaris_api::{
+    consts::DEFAULT_TPS,
+    timeline::{FrameRate, Timecode},
+};
+
+fn df(hours: u32, minutes: u32, seconds: u32, frames: u32) -> Timecode {
+    Timecode::new(hours, minutes, seconds, frames, true)
+}
+
+#[test]
+fn drop_frame_labels_at_29_97() {
+    let rate = FrameRate::FPS_29_97;
+    let cases = [
+        (0, df(0, 0, 0, 0)),
+        (1799, df(0, 0, 59, 29)),
+        // Labels ;00 and ;01 are skipped at the start of each minute...
+        (1800, df(0, 1, 0, 2)),
+        (3597, df(0, 1, 59, 29)),
+        (3598, df(0, 2, 0, 2)),
+        // ...except every tenth one.
+        (17981, df(0, 9, 59, 29)),
+        (17982, df(0, 10, 0, 0)),
+        (17984, df(0, 10, 0, 2)),
+        (107892, df(1, 0, 0, 0)),
+    ];
+    for (frame, timecode) in cases {
+        assert_eq!(Timecode::from_frame(frame, rate, true).unwrap(), timecode);
+        assert_eq!(timecode.to_frame(rate).unwrap(), frame);
+    }
+}
+
+#[test]
+fn drop_frame_labels_at_59_94() {
+    let rate = FrameRate::FPS_59_94;
+    assert_eq!(
+        Timecode::from_frame(3599, rate, true).unwrap(),
+        df(0, 0, 59, 59)
+    );
+    assert_eq!(
+        Timecode::from_frame(3600, rate, true).unwrap(),
+        df(0, 1, 0, 4)
+    );
+    assert_eq!(df(0, 1, 0, 4).to_frame(rate).unwrap(), 3600);
+    assert_eq!(df(0, 10, 0, 0).to_frame(rate).unwrap(), 35964);
+}
+
+#[test]
+fn drop_frame_round_trips_every_frame() {
+    for rate in [FrameRate::FPS_29_97, FrameRate::FPS_59_94] {
+        for frame in 0..rate.nominal() as u64 * 60 * 21 {
+            let timecode = Timecode::from_frame(frame, rate, true).unwrap();
+            assert_eq!(timecode.to_frame(rate).unwrap(), frame, "{timecode}");
+            assert_eq!(timecode.to_string().parse::<Timecode>().unwrap(), timecode);
+        }
+    }
+}
+
+#[test]
+fn rejects_invalid_drop_frame_labels() {
+    let rate = FrameRate::FPS_29_97;
+    assert!(df(0, 1, 0, 0).to_frame(rate).is_err());
+    assert!(df(0, 1, 0, 1).to_frame(rate).is_err());
+    assert!(df(0, 0, 0, 30).to_frame(rate).is_err());
+    assert!(df(0, 0, 60, 0).to_frame(rate).is_err());
+    assert!(df(0, 10, 0, 0).to_frame(rate).is_ok());
+
+    assert!(Timecode::from_frame(0, FrameRate::FPS_25, true).is_err());
+    assert!(df(0, 0, 1, 0).to_frame(FrameRate::FPS_30).is_err());
+}
+
+#[test]
+fn non_drop_frame_labels() {
+    let rate = FrameRate::FPS_25;
+    let timecode = Timecode::new(1, 2, 3, 4, false);
+    let frame = ((3600 + 2 * 60 + 3) * 25 + 4) as u64;
+    assert_eq!(timecode.to_frame(rate).unwrap(), frame);
+    assert_eq!(Timecode::from_frame(frame, rate, false).unwrap(), timecode);
+    // Non-drop labels at 29.97 count nominal frames and drift from the wall
+    // clock.
+    assert_eq!(
+        Timecode::from_frame(1800, FrameRate::FPS_29_97, false).unwrap(),
+        Timecode::new(0, 1, 0, 0, false)
+    );
+}
+
+#[test]
+fn parses_timecode() {
+    let cases = [
+        ("01:02:03:04", Timecode::new(1, 2, 3, 4, false)),
+        ("01:02:03;04", df(1, 2, 3, 4)),
+        ("01:02:03.04", df(1, 2, 3, 4)),
+        ("01;02;03;04", df(1, 2, 3, 4)),
+        ("  10:00:00:00\n", Timecode::new(10, 0, 0, 0, false)),
+        ("100:00:00:00", Timecode::new(100, 0, 0, 0, false)),
+    ];
+    for (text, timecode) in cases {
+        assert_eq!(text.parse::<Timecode>().unwrap(), timecode, "{text}");
+    }
+    for text in [
+        "",
+        "01:02:03",
+        "01:02:03:04:05",
+        "aa:00:00:00",
+        "00:00:00:",
+        "00:00:00:+1",
+        "00:00:-1:00",
+        "00 00 00 00",
+    ] {
+        assert!(text.parse::<Timecode>().is_err(), "{text}");
+    }
+}
+
+#[test]
+fn displays_timecode() {
+    assert_eq!(Timecode::new(1, 2, 3, 4, false).to_string(), "01:02:03:04");
+    assert_eq!(df(1, 2, 3, 4).to_string(), "01:02:03;04");
+}
+
+#[test]
+fn frame_rates() {
+    assert_eq!(FrameRate::new(48, 2).unwrap(), FrameRate::FPS_24);
+    assert!(FrameRate::new(0, 1).is_err());
+    assert!(FrameRate::new(24, 0).is_err());
+    assert_eq!(FrameRate::FPS_29_97.nominal(), 30);
+    assert_eq!(FrameRate::FPS_23_976.to_string(), "24000/1001");
+
+    for rate in [
+        FrameRate::FPS_23_976,
+        FrameRate::FPS_29_97,
+        FrameRate::FPS_60,
+    ] {
+        for frame in [1, 1000, 1_000_000] {
+            let ticks = rate.frame_to_ticks(frame, DEFAULT_TPS);
+            assert_eq!(rate.ticks_to_frame(ticks, DEFAULT_TPS), frame);
+            assert_eq!(
+                rate.ticks_to_frame(ticks - 1, DEFAULT_TPS),
+                frame.saturating_sub(1)
+            );
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use bevy_ecs::{entity::Entity, world::World};
+use lunaris_api::{
+    prelude::*,
+    timeline::{InSequence, Sequence, TimelineSpan, Track, TrackKind, elements::TimelineElement},
+};
+
+fn names(world: &World, sequence: Option<Entity>) -> Vec<(u64, String)> {
+    Track::all_in(world, sequence)
+        .into_iter()
+        .map(|(_, track)| (track.index(), track.name.clone()))
+        .collect()
+}
+
+fn element_on(world: &mut World, track: u64) -> Entity {
+    world
+        .spawn(TimelineElement {
+            track_num: track,
+            position: TimelineSpan::new(0, 10).unwrap(),
+        })
+        .id()
+}
+
+#[test]
+fn push_in_stacks_per_sequence() {
+    let mut world = World::new();
+    Track::push(&mut world, "V1", TrackKind::Video);
+    Track::push(&mut world, "A1", TrackKind::Audio);
+    let sequence = Sequence::spawn(&mut world, "Nested");
+    let nested = Track::push_in(&mut world, Some(sequence), "N1", TrackKind::Video);
+
+    assert_eq!(world.get::<Track>(nested).unwrap().index(), 0);
+    assert_eq!(
+        world.get::<InSequence>(nested).map(|p| p.sequence),
+        Some(sequence)
+    );
+    assert_eq!(
+        names(&world, None),
+        vec![(0, "V1".to_string()), (1, "A1".to_string())]
+    );
+    assert_eq!(Track::find_in(&world, Some(sequence), 0), Some(nested));
+    assert_eq!(Track::ordered(&world, TrackKind::Audio).len(), 1);
+}
+
+#[test]
+fn spawn_refuses_taken_indices() {
+    let mut world = World::new();
+    Track::push(&mut world, "V1", TrackKind::Video);
+    let taken = Track::spawn(&mut world, Track::new("V2", TrackKind::Video, 0));
+    assert!(matches!(taken, Err(LunarisError::AlreadyExists { .. })));
+
+    // The same index is free in another sequence.
+    let sequence = Sequence::spawn(&mut world, "Nested");
+    assert!(
+        Track::spawn_in(
+            &mut world,
+            Some(sequence),
+            Track::new("N1", TrackKind::Video, 0)
+        )
+        .is_ok()
+    );
+}
+
+#[test]
+fn reorder_moves_tracks_and_their_elements() {
+    let mut world = World::new();
+    let v1 = Track::push(&mut world, "V1", TrackKind::Video);
+    Track::push(&mut world, "V2", TrackKind::Video);
+    Track::push(&mut world, "V3", TrackKind::Video);
+    let on_v1 = element_on(&mut world, 0);
+    let on_v2 = element_on(&mut world, 1);
+    let on_v3 = element_on(&mut world, 2);
+    let sequence = Sequence::spawn(&mut world, "Nested");
+    Track::push_in(&mut world, Some(sequence), "N1", TrackKind::Video);
+    let nested = element_on(&mut world, 0);
+    world.entity_mut(nested).insert(InSequence { sequence });
+
+    Track::reorder(&mut world, v1, 2).unwrap();
+    assert_eq!(
+        names(&world, None),
+        vec![
+            (0, "V2".to_string()),
+            (1, "V3".to_string()),
+            (2, "V1".to_string())
+        ]
+    );
+    let track = |world: &World, entity| world.get::<TimelineElement>(entity).unwrap().track_num;
+    assert_eq!(track(&world, on_v1), 2);
+    assert_eq!(track(&world, on_v2), 0);
+    assert_eq!(track(&world, on_v3), 1);
+    assert_eq!(track(&world, nested), 0);
+    assert_eq!(Track::elements(&world, v1).unwrap(), vec![on_v1]);
+
+    // And back down again.
+    Track::reorder(&mut world, v1, 0).unwrap();
+    assert_eq!(
+        names(&world, None),
+        vec![
+            (0, "V1".to_string()),
+            (1, "V2".to_string()),
+            (2, "V3".to_string())
+        ]
+    );
+    assert_eq!(
+        [
+            track(&world, on_v1),
+            track(&world, on_v2),
+            track(&world, on_v3)
+        ],
+        [0, 1, 2]
+    );
+    assert_eq!(names(&world, Some(sequence)), vec![(0, "N1".to_string())]);
+}
+
+#[test]
+fn reorder_of_a_missing_track_fails() {
+    let mut world = World::new();
+    let not_a_track = world.spawn_empty().id();
+    assert!(matches!(
+        Track::reorder(&mut world, not_a_track, 1),
+        Err(LunarisError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn solo_and_mute_pick_the_active_tracks() {
+    let mut world = World::new();
+    let v1 = Track::push(&mut world, "V1", TrackKind::Video);
+    let v2 = Track::push(&mut world, "V2", TrackKind::Video);
+    let v3 = Track::push(&mut world, "V3", TrackKind::Video);
+    let active = |world: &World| -> Vec<Entity> {
+        Track::active(world, TrackKind::Video)
+            .into_iter()
+            .map(|(entity, _)| entity)
+            .collect()
+    };
+    world.get_mut::<Track>(v2).unwrap().muted = true;
+    assert_eq!(active(&world), vec![v1, v3]);
+    world.get_mut::<Track>(v3).unwrap().solo = true;
+    assert_eq!(active(&world), vec![v3]);
+    // A disabled soloed track does not silence the others.
+    world.get_mut::<Track>(v3).unwrap().enabled = false;
+    assert_eq!(active(&world), vec![v1]);
+}
@@ -0,0 +1,241 @@
+use bevy_ecs::{entity::Entity, system::Command, world::World};
+use lunaris_api::{
+    history::UndoHistory,
+    prelude::*,
+    render::{PixelFormat, RawImage},
+    timeline::{TimelineEdit, TimelineSpan, Transition, TransitionKind, elements::TimelineElement},
+};
+
+fn adjacent(world: &mut World, start: u64, cut: u64, end: u64) -> (Entity, Entity) {
+    let mut element = |start, end| {
+        world
+            .spawn(TimelineElement {
+                track_num: 0,
+                position: TimelineSpan::new(start, end).unwrap(),
+            })
+            .id()
+    };
+    (element(start, cut), element(cut, end))
+}
+
+#[test]
+fn spawns_centred_on_the_cut() {
+    let mut world = World::new();
+    let (outgoing, incoming) = adjacent(&mut world, 0, 100, 200);
+    let transition =
+        Transition::spawn(&mut world, outgoing, incoming, 20, TransitionKind::Dissolve).unwrap();
+    assert_eq!(
+        world.get::<Transition>(transition).unwrap().span,
+        TimelineSpan::new(90, 110).unwrap()
+    );
+    assert_eq!(
+        Transition::between(&world, outgoing, incoming),
+        Some(transition)
+    );
+}
+
+#[test]
+fn rejects_durations_past_the_timeline() {
+    let mut world = World::new();
+    let (outgoing, incoming) = adjacent(&mut world, u64::MAX - 100, u64::MAX - 50, u64::MAX);
+    let result = Transition::spawn(
+        &mut world,
+        outgoing,
+        incoming,
+        u64::MAX,
+        TransitionKind::Dissolve,
+    );
+    assert!(matches!(result, Err(LunarisError::InvalidArgument { .. })));
+}
+
+/// Elements at 0..50, 50..150 and 150..250 on track 0, with a transition
+/// over 140..160 between the last two.
+fn three(world: &mut World) -> ([Entity; 3], Entity) {
+    world.init_resource::<UndoHistory>();
+    let (a, b) = adjacent(world, 0, 50, 150);
+    let c = world
+        .spawn(TimelineElement {
+            track_num: 0,
+            position: TimelineSpan::new(150, 250).unwrap(),
+        })
+        .id();
+    let transition = Transition::spawn(world, b, c, 20, TransitionKind::Dissolve).unwrap();
+    ([a, b, c], transition)
+}
+
+fn span_of(world: &World, transition: Entity) -> Option<(u64, u64)> {
+    let transition = world.get::<Transition>(transition)?;
+    Some((transition.span.start(), transition.span.end()))
+}
+
+/// Apply the edit built by `edit`, check where it leaves the transition and
+/// that undo and redo take it back and forth.
+fn check_edit(
+    edit: impl FnOnce(&mut World, [Entity; 3]) -> TimelineEdit,
+    after: Option<(u64, u64)>,
+) {
+    let mut world = World::new();
+    let (elements, transition) = three(&mut world);
+    edit(&mut world, elements).apply(&mut world);
+    assert_eq!(span_of(&world, transition), after);
+    UndoHistory::undo(&mut world).unwrap();
+    assert_eq!(span_of(&world, transition), Some((140, 160)));
+    UndoHistory::redo(&mut world).unwrap();
+    assert_eq!(span_of(&world, transition), after);
+}
+
+#[test]
+fn ripples_shift_transitions() {
+    check_edit(
+        |_, [a, ..]| TimelineEdit::RippleDelete { element: a },
+        Some((90, 110)),
+    );
+    check_edit(
+        |_, _| TimelineEdit::Extract {
+            track: 0,
+            span: TimelineSpan::new(0, 50).unwrap(),
+        },
+        Some((90, 110)),
+    );
+    check_edit(
+        |world, _| TimelineEdit::Insert {
+            element: world.spawn_empty().id(),
+            track: 0,
+            at: 0,
+            duration: 30,
+        },
+        Some((170, 190)),
+    );
+}
+
+#[test]
+fn slides_and_rolls_shift_transitions() {
+    check_edit(
+        |_, [_, b, _]| TimelineEdit::Slide {
+            element: b,
+            delta: 10,
+        },
+        Some((150, 170)),
+    );
+    check_edit(
+        |_, [_, b, c]| TimelineEdit::Roll {
+            outgoing: b,
+            incoming: c,
+            to: 155,
+        },
+        Some((145, 165)),
+    );
+}
+
+#[test]
+fn splitting_or_removing_either_side_drops_transitions() {
+    check_edit(
+        |_, [_, b, _]| TimelineEdit::Razor {
+            element: b,
+            at: 100,
+        },
+        None,
+    );
+    check_edit(
+        |_, [.., c]| TimelineEdit::Razor {
+            element: c,
+            at: 200,
+        },
+        None,
+    );
+    check_edit(
+        |_, [_, b, _]| TimelineEdit::RippleDelete { element: b },
+        None,
+    );
+    check_edit(
+        |_, _| TimelineEdit::Lift {
+            track: 0,
+            span: TimelineSpan::new(150, 250).unwrap(),
+        },
+        None,
+    );
+    // Material inserted at the cut pulls the elements apart.
+    check_edit(
+        |world, _| TimelineEdit::Insert {
+            element: world.spawn_empty().id(),
+            track: 0,
+            at: 150,
+            duration: 30,
+        },
+        None,
+    );
+}
+
+#[test]
+fn unrelated_edits_leave_transitions_alone() {
+    check_edit(
+        |_, [a, ..]| TimelineEdit::Razor { element: a, at: 20 },
+        Some((140, 160)),
+    );
+    check_edit(
+        |_, [_, b, _]| TimelineEdit::Slip {
+            element: b,
+            delta: 10,
+        },
+        Some((140, 160)),
+    );
+}
+
+fn gray(width: u32, height: u32, value: u8) -> RawImage {
+    RawImage::from_bytes(
+        PixelFormat::Gray8,
+        width,
+        height,
+        vec![value; (width * height) as usize],
+    )
+    .unwrap()
+}
+
+fn rgba(pixel: [u8; 4]) -> RawImage {
+    RawImage::from_bytes(PixelFormat::Rgba8Unorm, 2, 1, pixel.repeat(2)).unwrap()
+}
+
+#[test]
+fn dissolve_blends_linearly() {
+    let (from, to) = (gray(2, 2, 0), gray(2, 2, 200));
+    for (progress, value) in [(0.0, 0), (0.5, 100), (1.0, 200)] {
+        let blended = from.dissolve(&to, progress).unwrap();
+        assert_eq!(blended.as_bytes(), [value; 4]);
+    }
+}
+
+#[test]
+fn dip_passes_through_the_color() {
+    let (from, to) = (rgba([100, 120, 140, 255]), rgba([200, 220, 240, 255]));
+    let black = [0, 0, 0, 255];
+    for (progress, pixel) in [
+        (0.0, [100, 120, 140, 255]),
+        (0.25, [50, 60, 70, 255]),
+        (0.5, black),
+        (1.0, [200, 220, 240, 255]),
+    ] {
+        let blended = from.dip_to_color(&to, progress, black).unwrap();
+        assert_eq!(blended.as_bytes(), pixel.repeat(2));
+    }
+}
+
+#[test]
+fn wipe_sweeps_in_the_direction_of_its_angle() {
+    let (from, to) = (gray(4, 4, 0), gray(4, 4, 255));
+    let rows = |image: &RawImage| -> Vec<Vec<u8>> {
+        image.as_bytes().chunks(4).map(<[u8]>::to_vec).collect()
+    };
+    let half_columns = vec![vec![255, 255, 0, 0]; 4];
+    let half_rows = vec![vec![255; 4], vec![255; 4], vec![0; 4], vec![0; 4]];
+    for (angle, half) in [(0.0, half_columns), (90.0, half_rows)] {
+        assert_eq!(
+            rows(&from.wipe(&to, 0.0, angle).unwrap()),
+            vec![vec![0; 4]; 4]
+        );
+        assert_eq!(rows(&from.wipe(&to, 0.5, angle).unwrap()), half);
+        assert_eq!(
+            rows(&from.wipe(&to, 1.0, angle).unwrap()),
+            vec![vec![255; 4]; 4]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::{sync::Arc, time::Duration};
+
+use bevy_ecs::{entity::Entity, system::RunSystemOnce, world::World};
+use lunaris_api::timeline::{
+    FrameRate, Playhead, PlayheadTarget, Timebase, TimelineSpan, Transport,
+    transport::{ManualClock, advance_transport},
+};
+
+const TPS: u64 = 1000;
+
+/// World at [`TPS`] with one playhead at `start`, driven by the returned
+/// clock.
+fn setup(start: u64) -> (World, Entity, ManualClock) {
+    let mut world = World::new();
+    world.insert_resource(Timebase::new(TPS).unwrap());
+    let clock = ManualClock::new();
+    let playhead = Playhead::spawn(&mut world, PlayheadTarget::Root);
+    Playhead::seek(&mut world, playhead, start).unwrap();
+    world.entity_mut(playhead).insert(Transport::with_clock(
+        FrameRate::FPS_25,
+        Arc::new(clock.clone()),
+    ));
+    (world, playhead, clock)
+}
+
+fn run(world: &mut World, clock: &ManualClock, millis: u64) {
+    clock.advance(Duration::from_millis(millis));
+    world.run_system_once(advance_transport).unwrap();
+}
+
+fn tick(world: &World, playhead: Entity) -> u64 {
+    Playhead::tick(world, playhead).unwrap()
+}
+
+#[test]
+fn play_follows_the_clock() {
+    let (mut world, playhead, clock) = setup(0);
+    run(&mut world, &clock, 500);
+    assert_eq!(tick(&world, playhead), 0);
+
+    Transport::of(&mut world, playhead).unwrap().play();
+    run(&mut world, &clock, 500);
+    assert_eq!(tick(&world, playhead), 500);
+    run(&mut world, &clock, 250);
+    assert_eq!(tick(&world, playhead), 750);
+
+    Transport::of(&mut world, playhead).unwrap().pause();
+    run(&mut world, &clock, 1000);
+    assert_eq!(tick(&world, playhead), 750);
+}
+
+#[test]
+fn shuttle_steps_through_speeds() {
+    let (mut world, playhead, clock) = setup(1000);
+    {
+        let mut transport = Transport::of(&mut world, playhead).unwrap();
+        transport.shuttle_forward();
+        transport.shuttle_forward();
+        assert_eq!(transport.speed(), 2.0);
+    }
+    run(&mut world, &clock, 500);
+    assert_eq!(tick(&world, playhead), 2000);
+
+    {
+        let mut transport = Transport::of(&mut world, playhead).unwrap();
+        transport.shuttle_reverse();
+        assert_eq!(transport.speed(), -1.0);
+        transport.shuttle_reverse();
+        assert_eq!(transport.speed(), -2.0);
+    }
+    run(&mut world, &clock, 250);
+    assert_eq!(tick(&world, playhead), 1500);
+
+    // Reverse playback stops at the start of the timeline.
+    run(&mut world, &clock, 1000);
+    assert_eq!(tick(&world, playhead), 0);
+    assert!(!Transport::of(&mut world, playhead).unwrap().is_playing());
+}
+
+#[test]
+fn loop_wraps_both_ways() {
+    let (mut world, playhead, clock) = setup(1800);
+    {
+        let mut transport = Transport::of(&mut world, playhead).unwrap();
+        transport.set_loop(TimelineSpan::new(1000, 2000).unwrap());
+        transport.play();
+    }
+    run(&mut world, &clock, 500);
+    assert_eq!(tick(&world, playhead), 1300);
+
+    Transport::of(&mut world, playhead).unwrap().set_speed(-1.0);
+    run(&mut world, &clock, 500);
+    assert_eq!(tick(&world, playhead), 1800);
+}
+
+#[test]
+fn step_lands_on_frame_boundaries() {
+    // 25 fps at 1000 ticks per second: one frame every 40 ticks.
+    let (mut world, playhead, _clock) = setup(50);
+    Transport::of(&mut world, playhead).unwrap().play();
+    Transport::step(&mut world, playhead, 1).unwrap();
+    assert_eq!(tick(&world, playhead), 80);
+    assert!(!Transport::of(&mut world, playhead).unwrap().is_playing());
+
+    Playhead::seek(&mut world, playhead, 50).unwrap();
+    Transport::step(&mut world, playhead, -1).unwrap();
+    assert_eq!(tick(&world, playhead), 40);
+    Transport::step(&mut world, playhead, -5).unwrap();
+    assert_eq!(tick(&world, playhead), 0);
+}
+
+#[test]
+fn timebase_change_rescales_loop() {
+    let (mut world, playhead, _clock) = setup(0);
+    Transport::of(&mut world, playhead)
+        .unwrap()
+        .set_loop(TimelineSpan::new(1000, 2000).unwrap());
+    Timebase::change(&mut world, Timebase::new(2 * TPS).unwrap());
+    assert_eq!(
+        Transport::of(&mut world, playhead).unwrap().loop_span,
+        Some(TimelineSpan::new(2000, 4000).unwrap())
+    );
+}
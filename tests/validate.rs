@@ -0,0 +1,191 @@
+use bevy_ecs::{entity::Entity, world::World};
+use lunaris_api::timeline::{
+    TimelineSpan, Validator,
+    elements::{BindTo, Properties, Property, TimelineElement},
+    validate::{Fix, Issue, IssueKind, Severity, validate},
+};
+
+fn span(start: u64, end: u64) -> TimelineSpan {
+    TimelineSpan::new(start, end).unwrap()
+}
+
+fn element(world: &mut World, start: u64, end: u64) -> Entity {
+    world
+        .spawn(TimelineElement {
+            track_num: 0,
+            position: span(start, end),
+        })
+        .id()
+}
+
+fn spans(world: &mut World) -> Vec<(u64, u64)> {
+    let mut query = world.query::<&TimelineElement>();
+    let mut spans: Vec<_> = query
+        .iter(world)
+        .map(|e| (e.position.start(), e.position.end()))
+        .collect();
+    spans.sort();
+    spans
+}
+
+#[test]
+fn finds_overlaps() {
+    let mut world = World::new();
+    let earlier = element(&mut world, 0, 100);
+    let later = element(&mut world, 60, 200);
+    assert_eq!(
+        validate(&world),
+        vec![Issue {
+            kind: IssueKind::Overlap {
+                sequence: None,
+                track: 0,
+                earlier,
+                later,
+                span: span(60, 100),
+            },
+            fix: Some(Fix::Trim {
+                entity: earlier,
+                end: 60,
+            }),
+        }]
+    );
+}
+
+#[test]
+fn moves_elements_that_start_together() {
+    let mut world = World::new();
+    let first = element(&mut world, 0, 100);
+    let second = element(&mut world, 0, 50);
+    let issues = validate(&world);
+    assert_eq!(issues.len(), 1);
+    let (IssueKind::Overlap { earlier, later, .. }, Some(Fix::Move { entity, start })) =
+        (&issues[0].kind, &issues[0].fix)
+    else {
+        panic!("expected an overlap fixed by a move, got {issues:?}");
+    };
+    // Ties sort by end, so the shorter element comes first.
+    assert_eq!((*earlier, *later), (second, first));
+    assert_eq!((*entity, *start), (first, 50));
+}
+
+#[test]
+fn finds_gaps_as_warnings() {
+    let mut world = World::new();
+    element(&mut world, 0, 100);
+    element(&mut world, 150, 200);
+    // Other tracks are checked on their own.
+    world.spawn(TimelineElement {
+        track_num: 1,
+        position: span(120, 130),
+    });
+    let issues = validate(&world);
+    assert_eq!(
+        issues,
+        vec![Issue {
+            kind: IssueKind::Gap {
+                sequence: None,
+                track: 0,
+                span: span(100, 150),
+            },
+            fix: Some(Fix::CloseGap {
+                sequence: None,
+                track: 0,
+                span: span(100, 150),
+            }),
+        }]
+    );
+    assert_eq!(issues[0].severity(), Severity::Warning);
+}
+
+#[test]
+fn finds_empty_spans() {
+    let mut world = World::new();
+    let empty = element(&mut world, 50, 50);
+    let issues = validate(&world);
+    assert_eq!(
+        issues,
+        vec![Issue {
+            kind: IssueKind::EmptySpan { entity: empty },
+            fix: Some(Fix::RemoveElement(empty)),
+        }]
+    );
+    assert!(
+        Validator::new()
+            .fix(&mut world, Severity::Error)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(world.get::<TimelineElement>(empty).is_none());
+}
+
+#[test]
+fn finds_dangling_references() {
+    let mut world = World::new();
+    let gone = world.spawn_empty().id();
+    world.despawn(gone);
+    let mut properties = Properties::default();
+    properties.insert("mask", Property::Entity(gone));
+    let bound = world.spawn(BindTo { id: gone }).id();
+    let pointing = world.spawn(properties).id();
+
+    let issues = validate(&world);
+    assert_eq!(
+        issues,
+        vec![
+            Issue {
+                kind: IssueKind::DanglingBind {
+                    entity: bound,
+                    target: gone,
+                },
+                fix: Some(Fix::Unbind(bound)),
+            },
+            Issue {
+                kind: IssueKind::DanglingProperty {
+                    entity: pointing,
+                    key: "mask".to_string(),
+                    target: gone,
+                },
+                fix: Some(Fix::RemoveProperty {
+                    entity: pointing,
+                    key: "mask".to_string(),
+                }),
+            },
+        ]
+    );
+
+    assert!(
+        Validator::new()
+            .fix(&mut world, Severity::Error)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(world.get::<BindTo>(bound).is_none());
+    assert_eq!(world.get::<Properties>(pointing).unwrap().get("mask"), None);
+}
+
+#[test]
+fn fixing_errors_settles_overlap_cascades_and_keeps_gaps() {
+    let mut world = World::new();
+    element(&mut world, 0, 100);
+    element(&mut world, 50, 150);
+    element(&mut world, 80, 180);
+    element(&mut world, 300, 400);
+
+    let remaining = Validator::new().fix(&mut world, Severity::Error).unwrap();
+    assert_eq!(
+        spans(&mut world),
+        vec![(0, 50), (50, 80), (80, 180), (300, 400)]
+    );
+    assert_eq!(remaining.len(), 1);
+    assert!(matches!(
+        remaining[0].kind,
+        IssueKind::Gap { span, .. } if span == TimelineSpan::new(180, 300).unwrap()
+    ));
+
+    let remaining = Validator::new().fix(&mut world, Severity::Warning).unwrap();
+    assert!(remaining.is_empty());
+    assert_eq!(
+        spans(&mut world),
+        vec![(0, 50), (50, 80), (80, 180), (180, 280)]
+    );
+}